@@ -1,5 +1,16 @@
 use sea_orm::entity::prelude::*;
 
+#[derive(Clone, Debug, PartialEq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "baseline_status")]
+pub enum BaselineStatus {
+    #[sea_orm(string_value = "Draft")]
+    Draft,
+    #[sea_orm(string_value = "Approved")]
+    Approved,
+    #[sea_orm(string_value = "Locked")]
+    Locked,
+}
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
 #[sea_orm(table_name = "baselines")]
 pub struct Model {
@@ -10,6 +21,10 @@ pub struct Model {
     pub comment: Option<String>,
     #[sea_orm(default_value = true)]
     pub is_active: bool,
+    #[sea_orm(default_value = "Draft")]
+    pub status: BaselineStatus,
+    pub approved_by: Option<String>,
+    pub approved_at: Option<ChronoDateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
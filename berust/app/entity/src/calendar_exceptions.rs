@@ -0,0 +1,50 @@
+use sea_orm::entity::prelude::*;
+
+use crate::calendars::Entity as Calendar;
+
+#[derive(Clone, Debug, PartialEq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "Enum",
+    enum_name = "calendar_exception_kind"
+)]
+pub enum CalendarExceptionKind {
+    #[sea_orm(string_value = "Holiday")]
+    Holiday,
+    #[sea_orm(string_value = "Exception")]
+    Exception,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "calendar_exceptions")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub calendar_exception_id: i64,
+    #[sea_orm(index)]
+    pub calendar_id: i64,
+    pub date: Date,
+    pub name: String,
+    #[sea_orm(default_value = "Holiday")]
+    pub kind: CalendarExceptionKind,
+    /// Working hours of the day in minutes since local midnight; none for a day off.
+    pub start_minute: Option<i16>,
+    pub finish_minute: Option<i16>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::calendars::Entity",
+        from = "Column::CalendarId",
+        to = "super::calendars::Column::CalendarId"
+    )]
+    Calendar,
+}
+
+impl Related<Calendar> for Entity {
+    fn to() -> RelationDef {
+        Relation::Calendar.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
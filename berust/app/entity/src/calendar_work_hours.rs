@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+
+use crate::calendars::Entity as Calendar;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "calendar_work_hours")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub calendar_work_hours_id: i64,
+    #[sea_orm(index)]
+    pub calendar_id: i64,
+    /// Day of the week, 0 for Monday up to 6 for Sunday.
+    pub weekday: i16,
+    /// Minutes since local midnight.
+    pub start_minute: i16,
+    pub finish_minute: i16,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::calendars::Entity",
+        from = "Column::CalendarId",
+        to = "super::calendars::Column::CalendarId"
+    )]
+    Calendar,
+}
+
+impl Related<Calendar> for Entity {
+    fn to() -> RelationDef {
+        Relation::Calendar.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,19 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "calendars")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub calendar_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    #[sea_orm(default_value = "Europe/Warsaw")]
+    pub timezone: String,
+    /// Calendar the work hours and exceptions are inherited from.
+    pub parent_calendar_id: Option<i64>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
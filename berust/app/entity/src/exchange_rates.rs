@@ -0,0 +1,19 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "exchange_rates")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub exchange_rate_id: i64,
+    /// Day the rate was published for; it applies until the next published rate.
+    pub date: Date,
+    pub from_currency: String,
+    pub to_currency: String,
+    /// Units of `to_currency` one unit of `from_currency` buys.
+    pub rate: f64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,39 @@
+use sea_orm::entity::prelude::*;
+
+use crate::tasks::Entity as Task;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "expenses")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub expense_id: i64,
+    /// Number of the invoice or other document the expense was booked from.
+    #[sea_orm(unique)]
+    pub document_number: String,
+    pub date: Date,
+    pub description: Option<String>,
+    pub amount: f64,
+    pub currency: String,
+    /// Cost type the expense is reported under, such as travel or equipment.
+    pub category: String,
+    #[sea_orm(index)]
+    pub task_id: Option<i64>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::tasks::Entity",
+        from = "Column::TaskId",
+        to = "super::tasks::Column::TaskId"
+    )]
+    Task,
+}
+
+impl Related<Task> for Entity {
+    fn to() -> RelationDef {
+        Relation::Task.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,8 +1,17 @@
-// pub mod team_members;
 pub mod baselines;
+pub mod calendar_exceptions;
+pub mod calendar_work_hours;
+pub mod calendars;
 pub mod config;
+pub mod exchange_rates;
+pub mod expenses;
 pub mod resource_types;
 pub mod resources;
 pub mod resources_baselines;
+pub mod task_dependencies;
+pub mod task_progress;
+pub mod task_status_history;
 pub mod tasks;
 pub mod tasks_baselines;
+pub mod team_members;
+pub mod time_entries;
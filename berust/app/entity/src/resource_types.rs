@@ -8,6 +8,8 @@ pub struct Model {
     pub name: String,
     pub description: Option<String>,
     pub comment: Option<String>,
+    #[sea_orm(default_value = true)]
+    pub is_active: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
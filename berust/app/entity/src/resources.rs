@@ -35,7 +35,8 @@ pub struct Model {
     pub cost_currency: String,
     pub billing_frequency: Option<Frequency>,
     pub billing_interval: Option<i32>,
-    pub availability: Option<String>, // TODO: placeholder, modify to reference to calendar
+    /// Working calendar; the default calendar from config applies without one.
+    pub calendar_id: Option<i64>,
     pub capacity: Option<f64>,
     pub capacity_unit: Option<String>,
     #[sea_orm(default_value = true)]
@@ -0,0 +1,64 @@
+use sea_orm::entity::prelude::*;
+
+use crate::baselines::Entity as Baseline;
+
+#[derive(Clone, Debug, PartialEq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "dependency_type")]
+pub enum DependencyType {
+    #[sea_orm(string_value = "FinishToStart")]
+    FinishToStart,
+    #[sea_orm(string_value = "StartToStart")]
+    StartToStart,
+    #[sea_orm(string_value = "FinishToFinish")]
+    FinishToFinish,
+    #[sea_orm(string_value = "StartToFinish")]
+    StartToFinish,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "task_dependencies")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub task_dependency_id: i64,
+    #[sea_orm(index)]
+    pub baseline_id: i64,
+    #[sea_orm(index)]
+    pub predecessor_id: i64,
+    #[sea_orm(index)]
+    pub successor_id: i64,
+    #[sea_orm(default_value = "FinishToStart")]
+    pub dependency_type: DependencyType,
+    /// Seconds between the linked dates; negative for a lead.
+    #[sea_orm(default_value = 0)]
+    pub lag: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::baselines::Entity",
+        from = "Column::BaselineId",
+        to = "super::baselines::Column::BaselineId"
+    )]
+    Baseline,
+    #[sea_orm(
+        belongs_to = "super::tasks::Entity",
+        from = "Column::PredecessorId",
+        to = "super::tasks::Column::TaskId"
+    )]
+    Predecessor,
+    #[sea_orm(
+        belongs_to = "super::tasks::Entity",
+        from = "Column::SuccessorId",
+        to = "super::tasks::Column::TaskId"
+    )]
+    Successor,
+}
+
+impl Related<Baseline> for Entity {
+    fn to() -> RelationDef {
+        Relation::Baseline.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,40 @@
+use sea_orm::entity::prelude::*;
+
+use crate::tasks::Entity as Task;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "task_progress")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub task_progress_id: i64,
+    #[sea_orm(index)]
+    pub task_id: i64,
+    /// Day the progress was assessed on; a task has at most one assessment per day.
+    pub status_date: Date,
+    pub percent_complete: f64,
+    pub actual_start: Option<Date>,
+    pub actual_finish: Option<Date>,
+    /// Hours of work still expected, when estimated.
+    pub remaining_work: Option<f64>,
+    /// Cost incurred up to the status date, in `cost_currency`, when known.
+    pub actual_cost: Option<f64>,
+    pub cost_currency: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::tasks::Entity",
+        from = "Column::TaskId",
+        to = "super::tasks::Column::TaskId"
+    )]
+    Task,
+}
+
+impl Related<Task> for Entity {
+    fn to() -> RelationDef {
+        Relation::Task.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+
+use crate::tasks::Entity as Task;
+use crate::tasks::TaskStatus;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "task_status_history")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub task_status_history_id: i64,
+    #[sea_orm(index)]
+    pub task_id: i64,
+    pub old_status: TaskStatus,
+    pub new_status: TaskStatus,
+    pub changed_at: ChronoDateTimeUtc,
+    pub changed_by: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::tasks::Entity",
+        from = "Column::TaskId",
+        to = "super::tasks::Column::TaskId"
+    )]
+    Task,
+}
+
+impl Related<Task> for Entity {
+    fn to() -> RelationDef {
+        Relation::Task.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
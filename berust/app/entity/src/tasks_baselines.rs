@@ -19,6 +19,9 @@ pub struct Model {
     pub start_timezone: String,
     pub finish: ChronoDateTime,
     pub finish_timezone: String,
+    /// Marks a deliverable date rather than work; a milestone finishes when it starts.
+    #[sea_orm(default_value = false)]
+    pub milestone: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,5 +1,7 @@
 use sea_orm::entity::prelude::*;
 
+use crate::resources::Entity as Resource;
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
 #[sea_orm(table_name = "team_members")]
 pub struct Model {
@@ -9,9 +11,28 @@ pub struct Model {
     pub user_last_name: String,
     pub position: Option<String>,
     pub comment: Option<String>,
+    /// The Personnel resource planned and costed for this person.
+    #[sea_orm(unique)]
+    pub resource_id: i64,
+    /// Id of the person in the source they were imported from; none for members added by hand.
+    #[sea_orm(unique)]
+    pub external_id: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::resources::Entity",
+        from = "Column::ResourceId",
+        to = "super::resources::Column::ResourceId"
+    )]
+    Resource,
+}
+
+impl Related<Resource> for Entity {
+    fn to() -> RelationDef {
+        Relation::Resource.def()
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,54 @@
+use sea_orm::entity::prelude::*;
+
+use crate::resources::Entity as Resource;
+use crate::tasks::Entity as Task;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "time_entries")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub time_entry_id: i64,
+    #[sea_orm(index)]
+    pub resource_id: i64,
+    #[sea_orm(index)]
+    pub task_id: i64,
+    /// First and last day the hours were worked on, the same day for a daily entry.
+    pub start: Date,
+    pub finish: Date,
+    pub hours: f64,
+    pub note: Option<String>,
+    /// Identifies an imported entry in its source, so reimporting updates it instead of adding it
+    /// again; none for entries recorded by hand.
+    #[sea_orm(unique)]
+    pub import_key: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::resources::Entity",
+        from = "Column::ResourceId",
+        to = "super::resources::Column::ResourceId"
+    )]
+    Resource,
+    #[sea_orm(
+        belongs_to = "super::tasks::Entity",
+        from = "Column::TaskId",
+        to = "super::tasks::Column::TaskId"
+    )]
+    Task,
+}
+
+impl Related<Resource> for Entity {
+    fn to() -> RelationDef {
+        Relation::Resource.def()
+    }
+}
+
+impl Related<Task> for Entity {
+    fn to() -> RelationDef {
+        Relation::Task.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
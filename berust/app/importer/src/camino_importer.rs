@@ -1,10 +1,11 @@
-use chrono::Months;
-use polars::prelude::*;
+use chrono::{Months, NaiveDate};
 use sea_orm::QueryFilter;
 use sea_orm::entity::*;
+use sea_orm::sea_query::OnConflict;
 use sea_orm::{ActiveValue::Set, DatabaseConnection, EntityTrait, TransactionTrait};
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use spreadsheet_ods::*;
+use std::collections::{HashMap, HashSet};
 use std::fs::read_to_string;
 use tracing::*;
 
@@ -12,18 +13,28 @@ const APP_PATH: &str = "/app";
 const PROJECT_INFO: &str = "/app/local/tmp/project_info.json";
 const LIST_OF_TASKS: &str = "/app/local/tmp/list_of_tasks.json";
 const RESOURCES: &str = "/app/local/tmp/resources.json";
-const ROW_OFFSET: i32 = 8;
-
-// fn ods_cell_to_string(cell: CellContent) -> String {
-//     match cell.value() {
-//         polars::value::Value::Empty => "".to_string(),
-//         polars::value::Value::Text(s) => s.to_string(),
-//         polars::value::Value::Number(n) => n.to_string(),
-//         polars::value::Value::DateTime(dt) => dt.to_string(),
-//         polars::value::Value::Boolean(b) => b.to_string(),
-//         _ => panic!("{:?}", cell.value()),
-//     }
-// }
+pub const OTHER_COSTS: &str = "/app/local/tmp/Team - Faktury.csv";
+pub const EXCHANGE_RATES: &str = "/app/local/tmp/exchange_rates.csv";
+const ROW_OFFSET: u32 = 8;
+/// Invoices are booked in the currency of the accounting, whatever the project plan uses.
+const EXPENSES_CURRENCY: &str = "PLN";
+/// Category of expenses the invoice export gives no cost type for.
+const EXPENSES_CATEGORY: &str = "Other";
+/// Resource type team members are linked to.
+const PERSONNEL: &str = "Personnel";
+/// Prefix of the import key of entries loaded from the time report.
+const TIME_REPORT_KEY: &str = "camino/";
+
+fn ods_cell_to_string(cell: CellContent) -> String {
+    match cell.value() {
+        spreadsheet_ods::Value::Empty => "".to_string(),
+        spreadsheet_ods::Value::Text(s) => s.to_string(),
+        spreadsheet_ods::Value::Number(n) => n.to_string(),
+        spreadsheet_ods::Value::DateTime(dt) => dt.to_string(),
+        spreadsheet_ods::Value::Boolean(b) => b.to_string(),
+        _ => format!("{:?}", cell.value()),
+    }
+}
 
 fn read_json_file<T: DeserializeOwned>(path: &str) -> Result<T, String> {
     read_to_string(path)
@@ -86,17 +97,21 @@ struct ResourceInput {
     capacity_unit: Option<String>,
 }
 
-pub async fn import_project_plan(db: DatabaseConnection) -> Result<(), String> {
-    let baseline_id = entity::config::Entity::find()
+async fn default_baseline_id(db: &DatabaseConnection) -> Result<i64, String> {
+    entity::config::Entity::find()
         .filter(entity::config::Column::ConfigKey.eq("baseline_id_default"))
-        .one(&db) // Use .one() as we expect a single result for the key
+        .one(db) // Use .one() as we expect a single result for the key
         .await
         .map_err(|e| format!("Database error finding baseline_id: {e}"))? // Handle potential DB error
         .ok_or_else(|| "Baseline ID 'baseline_id_default' not found".to_string())? // Handle case where key doesn't exist
         .config_value
         .ok_or_else(|| "Baseline ID 'baseline_id_default' has a NULL value".to_string())?
         .parse::<i64>()
-        .map_err(|e| format!("Failed to parse baseline_id: {e}"))?; // Handle case where value is NULL
+        .map_err(|e| format!("Failed to parse baseline_id: {e}")) // Handle case where value is NULL
+}
+
+pub async fn import_project_plan(db: DatabaseConnection) -> Result<(), String> {
+    let baseline_id = default_baseline_id(&db).await?;
 
     let project_info = read_json_file::<ProjectInfo>(PROJECT_INFO)?;
     let tasks = read_json_file::<Vec<TaskInput>>(LIST_OF_TASKS)?;
@@ -242,3 +257,810 @@ pub async fn import_project_plan(db: DatabaseConnection) -> Result<(), String> {
 
     Ok(())
 }
+
+/// Hours one person reported on one task in one project month.
+#[derive(Debug)]
+struct TimeReportRow {
+    user_id: String,
+    /// WBS code of the task.
+    wbs: String,
+    hours: f64,
+    /// 1 for the month the project starts in.
+    month: u32,
+}
+
+/// WBS codes of the tasks without subtasks in WBS order, which is the order of the task columns of
+/// the time report.
+fn time_report_tasks(tasks: &[entity::tasks_baselines::Model]) -> Vec<String> {
+    let parents: HashSet<i64> = tasks.iter().filter_map(|task| task.parent).collect();
+    let mut leaves: Vec<&str> = tasks
+        .iter()
+        .filter(|task| !parents.contains(&task.task_id))
+        .map(|task| task.wbs.as_str())
+        .collect();
+    leaves.sort_by_key(|wbs| {
+        wbs.split('.')
+            .map(|part| part.parse::<u32>().unwrap_or(u32::MAX))
+            .collect::<Vec<_>>()
+    });
+    leaves.into_iter().map(str::to_string).collect()
+}
+
+/// Reads the sheets of the time report, one per person named after their user id, with a row per
+/// project month and a column per task of `tasks`.
+fn read_time_report(wb: &WorkBook, tasks: &[String]) -> Result<Vec<TimeReportRow>, String> {
+    let mut rows = vec![];
+    for sheet in wb
+        .iter_sheets()
+        .filter(|sheet| sheet.name().parse::<i32>().is_ok())
+    {
+        // Months are on rows 9-38, tasks from column B on
+        for row_idx in ROW_OFFSET..=(ROW_OFFSET + 29) {
+            for (col_idx, wbs) in (1..).zip(tasks) {
+                let value = match sheet.cell(row_idx, col_idx) {
+                    Some(cell) => ods_cell_to_string(cell),
+                    None => continue,
+                };
+                if value.trim().is_empty() || value == "0" {
+                    continue;
+                }
+                rows.push(TimeReportRow {
+                    user_id: sheet.name().to_string(),
+                    wbs: wbs.clone(),
+                    hours: value.trim().parse().map_err(|e| {
+                        format!(
+                            "Invalid hours {value} of {} on T{wbs} in row {}: {e}",
+                            sheet.name(),
+                            row_idx + 1
+                        )
+                    })?,
+                    month: row_idx - ROW_OFFSET + 1,
+                });
+            }
+        }
+    }
+    Ok(rows)
+}
+
+/// A time entry of the report, identified by its import key.
+#[derive(Debug, PartialEq)]
+struct TimeEntryInput {
+    import_key: String,
+    resource_id: i64,
+    task_id: i64,
+    start: NaiveDate,
+    finish: NaiveDate,
+    hours: f64,
+}
+
+/// Time entries of `report`, spanning the calendar month of the project each row reports on.
+///
+/// The import key is the place of a row in the report, so the same hours always get the same key.
+/// Rows of people without a team member or of unknown tasks are skipped.
+fn time_entries(
+    report: Vec<TimeReportRow>,
+    resources: &HashMap<String, i64>,
+    tasks: &HashMap<String, i64>,
+    project_start: NaiveDate,
+) -> Result<Vec<TimeEntryInput>, String> {
+    let mut entries = vec![];
+    for row in report {
+        let Some(resource_id) = resources.get(&row.user_id) else {
+            warn!("No team member {}, skipping {:?}", row.user_id, row);
+            continue;
+        };
+        let Some(task_id) = tasks.get(&row.wbs) else {
+            warn!("No task with WBS {}, skipping {:?}", row.wbs, row);
+            continue;
+        };
+        let start = project_start
+            .checked_add_months(Months::new(row.month - 1))
+            .ok_or_else(|| format!("Month {} is out of range", row.month))?;
+        let finish = project_start
+            .checked_add_months(Months::new(row.month))
+            .and_then(|next| next.pred_opt())
+            .ok_or_else(|| format!("Month {} is out of range", row.month))?;
+        entries.push(TimeEntryInput {
+            import_key: format!("{TIME_REPORT_KEY}{}/{}/{}", row.user_id, row.wbs, row.month),
+            resource_id: *resource_id,
+            task_id: *task_id,
+            start,
+            finish,
+            hours: row.hours,
+        });
+    }
+    Ok(entries)
+}
+
+/// Keys of the entries imported before that `entries` no longer report.
+fn no_longer_reported(imported: Vec<String>, entries: &[TimeEntryInput]) -> Vec<String> {
+    let reported: HashSet<&str> = entries
+        .iter()
+        .map(|entry| entry.import_key.as_str())
+        .collect();
+    imported
+        .into_iter()
+        .filter(|import_key| !reported.contains(import_key.as_str()))
+        .collect()
+}
+
+/// One person of the team sheet.
+#[derive(Debug)]
+struct TeamMemberRow {
+    user_id: String,
+    user_name: String,
+    user_last_name: String,
+    position: Option<String>,
+    comment: Option<String>,
+}
+
+/// Reads the team sheet from row 3 on, up to the first row without a user id and full name.
+fn read_team_members(wb: &WorkBook) -> Result<Vec<TeamMemberRow>, String> {
+    let sheet = get_sheet(wb, "Team_reported_time")?;
+    let optional = |row, col| {
+        sheet
+            .cell(row, col)
+            .map(ods_cell_to_string)
+            .filter(|value| !value.trim().is_empty())
+    };
+    let mut rows = vec![];
+    let mut row = 2;
+    while let (Some(user_id), Some(user_name), Some(user_last_name)) =
+        (optional(row, 0), optional(row, 1), optional(row, 2))
+    {
+        rows.push(TeamMemberRow {
+            user_id,
+            user_name,
+            user_last_name,
+            position: optional(row, 3),
+            comment: optional(row, 4),
+        });
+        row += 1;
+    }
+    Ok(rows)
+}
+
+/// What importing one row of the team sheet does.
+#[derive(Debug, PartialEq)]
+enum TeamMemberChange {
+    /// Update the team member imported before with the user id.
+    Update(i64),
+    /// Add a team member linked to the existing resource with the id.
+    Link(i64),
+    /// Add a team member with a new Personnel resource.
+    Create,
+}
+
+/// Matches the rows of the team sheet to the team members imported before by their user id, and
+/// new members to the Personnel resource named after them that no team member is linked to yet.
+fn team_member_changes(
+    rows: &[TeamMemberRow],
+    team_members: &[entity::team_members::Model],
+    personnel: &[entity::resources::Model],
+) -> Vec<TeamMemberChange> {
+    let mut linked: HashSet<i64> = team_members
+        .iter()
+        .map(|team_member| team_member.resource_id)
+        .collect();
+    let imported: HashMap<&str, i64> = team_members
+        .iter()
+        .filter_map(|team_member| Some((team_member.external_id.as_deref()?, team_member.user_id)))
+        .collect();
+    rows.iter()
+        .map(|row| {
+            if let Some(team_member_id) = imported.get(row.user_id.as_str()) {
+                return TeamMemberChange::Update(*team_member_id);
+            }
+            let name = format!("{} {}", row.user_name, row.user_last_name);
+            match personnel.iter().find(|resource| {
+                resource.summary == name && !linked.contains(&resource.resource_id)
+            }) {
+                Some(resource) => {
+                    linked.insert(resource.resource_id);
+                    TeamMemberChange::Link(resource.resource_id)
+                }
+                None => TeamMemberChange::Create,
+            }
+        })
+        .collect()
+}
+
+/// Loads the team sheet into the team members, keyed by their camino user id.
+///
+/// Members imported before are updated. A new member is linked to the unlinked Personnel resource
+/// named after them, or to a new Personnel resource when there is none.
+pub async fn import_team_members(db: DatabaseConnection) -> Result<(), String> {
+    let project_info = read_json_file::<ProjectInfo>(PROJECT_INFO)?;
+    let wb = read_ods_file()?;
+    let rows = read_team_members(&wb)?;
+
+    let personnel_type_id = entity::resource_types::Entity::find()
+        .filter(entity::resource_types::Column::Name.eq(PERSONNEL))
+        .one(&db)
+        .await
+        .map_err(|e| format!("Failed to read resource types: {e}"))?
+        .ok_or_else(|| format!("Resource type {PERSONNEL} not found"))?
+        .resource_type_id;
+
+    let txn = db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {e}"))?;
+
+    let team_members = entity::team_members::Entity::find()
+        .all(&txn)
+        .await
+        .map_err(|e| format!("Failed to read team members: {e}"))?;
+    let personnel = entity::resources::Entity::find()
+        .filter(entity::resources::Column::ResourceTypeId.eq(personnel_type_id))
+        .all(&txn)
+        .await
+        .map_err(|e| format!("Failed to read resources: {e}"))?;
+    let changes = team_member_changes(&rows, &team_members, &personnel);
+    let team_members: HashMap<i64, entity::team_members::Model> = team_members
+        .into_iter()
+        .map(|team_member| (team_member.user_id, team_member))
+        .collect();
+
+    for (row, change) in rows.iter().zip(changes) {
+        let resource_id = match change {
+            TeamMemberChange::Update(team_member_id) => {
+                let mut team_member = team_members[&team_member_id].clone().into_active_model();
+                team_member.user_name = Set(row.user_name.clone());
+                team_member.user_last_name = Set(row.user_last_name.clone());
+                team_member.position = Set(row.position.clone());
+                team_member.comment = Set(row.comment.clone());
+                team_member
+                    .update(&txn)
+                    .await
+                    .map_err(|e| format!("Failed to update team member {}: {e}", row.user_id))?;
+                continue;
+            }
+            TeamMemberChange::Link(resource_id) => resource_id,
+            TeamMemberChange::Create => {
+                let name = format!("{} {}", row.user_name, row.user_last_name);
+                entity::resources::ActiveModel {
+                    summary: Set(name.clone()),
+                    description: Set(row.position.clone()),
+                    resource_type_id: Set(personnel_type_id),
+                    cost_currency: Set(project_info.config.currency.clone()),
+                    capacity: Set(Some(1.)),
+                    capacity_unit: Set(Some("FTE".to_string())),
+                    ..Default::default()
+                }
+                .insert(&txn)
+                .await
+                .map_err(|e| format!("Failed to insert resource {name}: {e}"))?
+                .resource_id
+            }
+        };
+
+        entity::team_members::ActiveModel {
+            user_name: Set(row.user_name.clone()),
+            user_last_name: Set(row.user_last_name.clone()),
+            position: Set(row.position.clone()),
+            comment: Set(row.comment.clone()),
+            resource_id: Set(resource_id),
+            external_id: Set(Some(row.user_id.clone())),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await
+        .map_err(|e| format!("Failed to insert team member {}: {e}", row.user_id))?;
+    }
+
+    txn.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {e}"))?;
+
+    info!("Imported {} team members", rows.len());
+    Ok(())
+}
+
+/// Loads the camino time report into the time entries, one per person, task and month.
+///
+/// People are matched to the resource of the team member with their user id and tasks by their WBS
+/// code in the default baseline. Entries are keyed by their place in the report, so importing it
+/// again updates them and removes the ones no longer reported instead of duplicating anything.
+pub async fn import_time_report(db: DatabaseConnection) -> Result<(), String> {
+    let baseline_id = default_baseline_id(&db).await?;
+    let project_info = read_json_file::<ProjectInfo>(PROJECT_INFO)?;
+
+    let resources = entity::team_members::Entity::find()
+        .all(&db)
+        .await
+        .map_err(|e| format!("Failed to read team members: {e}"))?
+        .into_iter()
+        .filter_map(|team_member| Some((team_member.external_id?, team_member.resource_id)))
+        .collect::<HashMap<String, i64>>();
+    let tasks_baselines = entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::BaselineId.eq(baseline_id))
+        .all(&db)
+        .await
+        .map_err(|e| format!("Failed to read tasks baselines: {e}"))?;
+
+    let wb = read_ods_file()?;
+    let report = read_time_report(&wb, &time_report_tasks(&tasks_baselines))?;
+    let tasks = tasks_baselines
+        .into_iter()
+        .map(|task| (task.wbs, task.task_id))
+        .collect::<HashMap<String, i64>>();
+    let entries = time_entries(
+        report,
+        &resources,
+        &tasks,
+        project_info.project_start.date(),
+    )?;
+
+    let txn = db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {e}"))?;
+
+    let imported = entity::time_entries::Entity::find()
+        .filter(entity::time_entries::Column::ImportKey.starts_with(TIME_REPORT_KEY))
+        .all(&txn)
+        .await
+        .map_err(|e| format!("Failed to read time entries: {e}"))?
+        .into_iter()
+        .filter_map(|time_entry| time_entry.import_key)
+        .collect();
+    let removed = no_longer_reported(imported, &entries);
+    if !removed.is_empty() {
+        entity::time_entries::Entity::delete_many()
+            .filter(entity::time_entries::Column::ImportKey.is_in(removed))
+            .exec(&txn)
+            .await
+            .map_err(|e| format!("Failed to delete time entries no longer reported: {e}"))?;
+    }
+
+    let imported = entries.len();
+    if !entries.is_empty() {
+        entity::time_entries::Entity::insert_many(entries.into_iter().map(|entry| {
+            entity::time_entries::ActiveModel {
+                resource_id: Set(entry.resource_id),
+                task_id: Set(entry.task_id),
+                start: Set(entry.start),
+                finish: Set(entry.finish),
+                hours: Set(entry.hours),
+                import_key: Set(Some(entry.import_key)),
+                ..Default::default()
+            }
+        }))
+        .on_conflict(
+            OnConflict::column(entity::time_entries::Column::ImportKey)
+                .update_columns([
+                    entity::time_entries::Column::ResourceId,
+                    entity::time_entries::Column::TaskId,
+                    entity::time_entries::Column::Start,
+                    entity::time_entries::Column::Finish,
+                    entity::time_entries::Column::Hours,
+                ])
+                .to_owned(),
+        )
+        .exec(&txn)
+        .await
+        .map_err(|e| format!("Failed to insert time entries: {e}"))?;
+    }
+
+    txn.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {e}"))?;
+
+    info!("Imported {imported} time entries");
+    Ok(())
+}
+
+/// One row of the invoice export.
+#[derive(Debug, Deserialize)]
+struct ExpenseInput {
+    #[serde(rename = "Dokument")]
+    document_number: String,
+    #[serde(rename = "Data dok.")]
+    date: Option<String>,
+    #[serde(rename = "Treść")]
+    description: Option<String>,
+    #[serde(rename = "Suma")]
+    amount: String,
+    #[serde(rename = "Task")]
+    task_name: Option<String>,
+    #[serde(rename = "Cost type")]
+    cost_type: Option<String>,
+}
+
+/// Parses ISO dates as well as the `dd.mm.yyyy` the accounting exports.
+fn parse_date(value: &str) -> Result<chrono::NaiveDate, String> {
+    chrono::NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .or_else(|_| chrono::NaiveDate::parse_from_str(value.trim(), "%d.%m.%Y"))
+        .map_err(|e| format!("Invalid date {value}: {e}"))
+}
+
+/// Parses amounts with a decimal point or a decimal comma, ignoring thousands separators.
+fn parse_amount(value: &str) -> Result<f64, String> {
+    let mut amount: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '\u{a0}')
+        .collect();
+    if amount.contains(',') {
+        amount = amount.replace('.', "").replace(',', ".");
+    }
+    amount
+        .parse()
+        .map_err(|e| format!("Invalid amount {value}: {e}"))
+}
+
+/// Rows of the export with a date whose document is not booked yet, each document once, with
+/// their line in the export and the number of rows skipped as duplicates.
+fn unbooked(
+    rows: Vec<(usize, ExpenseInput)>,
+    mut booked: HashSet<String>,
+) -> (Vec<(usize, ExpenseInput)>, usize) {
+    let mut expenses = vec![];
+    let mut duplicates = 0;
+    for (line, mut row) in rows {
+        if row
+            .date
+            .as_deref()
+            .is_none_or(|date| date.trim().is_empty())
+        {
+            continue;
+        }
+        row.document_number = row.document_number.trim().to_string();
+        if !booked.insert(row.document_number.clone()) {
+            debug!(
+                "Document {} already booked, skipping row {line}",
+                row.document_number
+            );
+            duplicates += 1;
+            continue;
+        }
+        expenses.push((line, row));
+    }
+    (expenses, duplicates)
+}
+
+/// Loads the invoice export at `path` into the expenses.
+///
+/// Documents already booked, or repeated within the export, are skipped, so the same or a newer
+/// export can be imported again. Tasks are matched by their WBS code or name in the default
+/// baseline; expenses of unknown tasks are booked without one.
+pub async fn import_expenses(db: DatabaseConnection, path: &str) -> Result<(), String> {
+    let baseline_id = default_baseline_id(&db).await?;
+    let mut reader =
+        csv::Reader::from_path(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+
+    let mut tasks = HashMap::new();
+    for task in entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::BaselineId.eq(baseline_id))
+        .find_also_related(entity::tasks::Entity)
+        .all(&db)
+        .await
+        .map_err(|e| format!("Failed to read tasks baselines: {e}"))?
+    {
+        if let (task_baseline, Some(task)) = task {
+            tasks.insert(task.summary, task_baseline.task_id);
+            tasks.insert(task_baseline.wbs, task_baseline.task_id);
+        }
+    }
+    let booked: HashSet<String> = entity::expenses::Entity::find()
+        .all(&db)
+        .await
+        .map_err(|e| format!("Failed to read expenses: {e}"))?
+        .into_iter()
+        .map(|expense| expense.document_number)
+        .collect();
+
+    let mut rows = vec![];
+    for (line, row) in (2..).zip(reader.deserialize::<ExpenseInput>()) {
+        rows.push((
+            line,
+            row.map_err(|e| format!("Invalid row {line} of {path}: {e}"))?,
+        ));
+    }
+    let (rows, duplicates) = unbooked(rows, booked);
+
+    let mut expenses = vec![];
+    for (line, row) in rows {
+        let document_number = row.document_number;
+        let task_name = row.task_name.unwrap_or_default();
+        let task_id = tasks
+            .get(task_name.trim())
+            .or_else(|| tasks.get(task_name.trim().trim_start_matches('T')))
+            .copied();
+        if task_id.is_none() && !task_name.trim().is_empty() {
+            warn!("No task {task_name}, booking document {document_number} without a task");
+        }
+
+        expenses.push(entity::expenses::ActiveModel {
+            document_number: Set(document_number),
+            date: Set(parse_date(row.date.as_deref().unwrap_or_default())
+                .map_err(|e| format!("{e} in row {line} of {path}"))?),
+            description: Set(row
+                .description
+                .filter(|description| !description.is_empty())),
+            amount: Set(
+                parse_amount(&row.amount).map_err(|e| format!("{e} in row {line} of {path}"))?
+            ),
+            currency: Set(EXPENSES_CURRENCY.to_string()),
+            category: Set(row
+                .cost_type
+                .filter(|cost_type| !cost_type.trim().is_empty())
+                .unwrap_or_else(|| EXPENSES_CATEGORY.to_string())),
+            task_id: Set(task_id),
+            ..Default::default()
+        });
+    }
+
+    let imported = expenses.len();
+    if !expenses.is_empty() {
+        entity::expenses::Entity::insert_many(expenses)
+            .exec(&db)
+            .await
+            .map_err(|e| format!("Failed to insert expenses: {e}"))?;
+    }
+
+    info!("Imported {imported} expenses, skipped {duplicates} duplicate documents");
+    Ok(())
+}
+
+/// One row of the exchange rates table: `date,from,to,rate`.
+#[derive(Debug, Deserialize)]
+struct ExchangeRateInput {
+    date: String,
+    from: String,
+    to: String,
+    rate: String,
+}
+
+/// Loads the exchange rates at `path`. A rate already stored for the same day and pair is replaced,
+/// so corrected tables can be imported again.
+pub async fn import_exchange_rates(db: DatabaseConnection, path: &str) -> Result<(), String> {
+    let mut reader =
+        csv::Reader::from_path(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+
+    // A later row for the same day and pair wins
+    let mut by_key = HashMap::new();
+    for (line, row) in (2..).zip(reader.deserialize::<ExchangeRateInput>()) {
+        let row = row.map_err(|e| format!("Invalid row {line} of {path}: {e}"))?;
+        let rate = parse_amount(&row.rate)?;
+        if rate <= 0. {
+            return Err(format!("Invalid rate {} in row {line} of {path}", row.rate));
+        }
+        let key = (
+            parse_date(&row.date)?,
+            row.from.trim().to_uppercase(),
+            row.to.trim().to_uppercase(),
+        );
+        by_key.insert(key, rate);
+    }
+    let rates: Vec<_> = by_key
+        .into_iter()
+        .map(
+            |((date, from_currency, to_currency), rate)| entity::exchange_rates::ActiveModel {
+                date: Set(date),
+                from_currency: Set(from_currency),
+                to_currency: Set(to_currency),
+                rate: Set(rate),
+                ..Default::default()
+            },
+        )
+        .collect();
+
+    let imported = rates.len();
+    if !rates.is_empty() {
+        entity::exchange_rates::Entity::insert_many(rates)
+            .on_conflict(
+                OnConflict::columns([
+                    entity::exchange_rates::Column::Date,
+                    entity::exchange_rates::Column::FromCurrency,
+                    entity::exchange_rates::Column::ToCurrency,
+                ])
+                .update_column(entity::exchange_rates::Column::Rate)
+                .to_owned(),
+            )
+            .exec(&db)
+            .await
+            .map_err(|e| format!("Failed to insert exchange rates: {e}"))?;
+    }
+
+    info!("Imported {imported} exchange rates");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(task_id: i64, wbs: &str, parent: Option<i64>) -> entity::tasks_baselines::Model {
+        entity::tasks_baselines::Model {
+            task_baseline_id: task_id,
+            task_id,
+            baseline_id: 1,
+            wbs: wbs.to_string(),
+            parent,
+            start: NaiveDate::from_ymd_opt(2025, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+            start_timezone: "UTC".to_string(),
+            finish: NaiveDate::from_ymd_opt(2025, 2, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+            finish_timezone: "UTC".to_string(),
+            milestone: false,
+        }
+    }
+
+    fn reported(user_id: &str, wbs: &str, month: u32, hours: f64) -> TimeReportRow {
+        TimeReportRow {
+            user_id: user_id.to_string(),
+            wbs: wbs.to_string(),
+            hours,
+            month,
+        }
+    }
+
+    fn member(user_id: &str, user_name: &str, user_last_name: &str) -> TeamMemberRow {
+        TeamMemberRow {
+            user_id: user_id.to_string(),
+            user_name: user_name.to_string(),
+            user_last_name: user_last_name.to_string(),
+            position: None,
+            comment: None,
+        }
+    }
+
+    fn team_member(
+        user_id: i64,
+        resource_id: i64,
+        external_id: Option<&str>,
+    ) -> entity::team_members::Model {
+        entity::team_members::Model {
+            user_id,
+            user_name: "Ada".to_string(),
+            user_last_name: "Lovelace".to_string(),
+            position: None,
+            comment: None,
+            resource_id,
+            external_id: external_id.map(str::to_string),
+        }
+    }
+
+    fn personnel(resource_id: i64, summary: &str) -> entity::resources::Model {
+        entity::resources::Model {
+            resource_id,
+            summary: summary.to_string(),
+            resource_type_id: 1,
+            description: None,
+            comment: None,
+            cost: None,
+            cost_currency: "PLN".to_string(),
+            billing_frequency: None,
+            billing_interval: None,
+            calendar_id: None,
+            capacity: Some(1.),
+            capacity_unit: Some("FTE".to_string()),
+            is_active: true,
+        }
+    }
+
+    #[test]
+    fn team_members_are_matched_by_external_id_then_by_an_unlinked_resource() {
+        let team_members = [team_member(1, 10, Some("7")), team_member(2, 20, None)];
+        let resources = [
+            personnel(10, "Ada Lovelace"),
+            personnel(20, "Alan Turing"),
+            personnel(30, "Grace Hopper"),
+        ];
+        let rows = [
+            member("7", "Ada", "Byron"),
+            member("8", "Alan", "Turing"),
+            member("9", "Grace", "Hopper"),
+            member("10", "Grace", "Hopper"),
+        ];
+        assert_eq!(
+            team_member_changes(&rows, &team_members, &resources),
+            [
+                TeamMemberChange::Update(1),
+                TeamMemberChange::Create,
+                TeamMemberChange::Link(30),
+                TeamMemberChange::Create,
+            ]
+        );
+    }
+
+    fn invoice(document_number: &str, date: Option<&str>) -> ExpenseInput {
+        ExpenseInput {
+            document_number: document_number.to_string(),
+            date: date.map(str::to_string),
+            description: None,
+            amount: "100,00".to_string(),
+            task_name: None,
+            cost_type: None,
+        }
+    }
+
+    #[test]
+    fn documents_are_booked_once() {
+        let rows = vec![
+            (2, invoice("FV/1/2025", Some("02.01.2025"))),
+            (3, invoice(" FV/2/2025 ", Some("03.01.2025"))),
+            (4, invoice("FV/3/2025", Some("04.01.2025"))),
+            (5, invoice("FV/3/2025 ", Some("04.01.2025"))),
+            (6, invoice("FV/4/2025", None)),
+            (7, invoice("FV/5/2025", Some(" "))),
+        ];
+        let booked = HashSet::from(["FV/1/2025".to_string()]);
+        let (expenses, duplicates) = unbooked(rows, booked);
+        let booked: Vec<(usize, &str)> = expenses
+            .iter()
+            .map(|(line, row)| (*line, row.document_number.as_str()))
+            .collect();
+        assert_eq!(booked, [(3, "FV/2/2025"), (4, "FV/3/2025")]);
+        assert_eq!(duplicates, 2);
+    }
+
+    #[test]
+    fn the_time_report_has_a_column_per_leaf_task_in_wbs_order() {
+        let tasks = [
+            task(1, "1", None),
+            task(2, "10", None),
+            task(3, "1.2", Some(1)),
+            task(4, "2", None),
+            task(5, "1.10", Some(1)),
+            task(6, "1.1", Some(1)),
+        ];
+        assert_eq!(time_report_tasks(&tasks), ["1.1", "1.2", "1.10", "2", "10"]);
+    }
+
+    #[test]
+    fn time_entries_are_keyed_by_their_place_in_the_report() {
+        let resources = HashMap::from([("7".to_string(), 70)]);
+        let tasks = HashMap::from([("1.1".to_string(), 11)]);
+        let project_start = NaiveDate::from_ymd_opt(2025, 1, 15).unwrap();
+        let report = || {
+            vec![
+                reported("7", "1.1", 2, 12.5),
+                reported("8", "1.1", 2, 4.),
+                reported("7", "9.9", 2, 4.),
+            ]
+        };
+        let entries = time_entries(report(), &resources, &tasks, project_start).unwrap();
+        assert_eq!(
+            entries,
+            [TimeEntryInput {
+                import_key: "camino/7/1.1/2".to_string(),
+                resource_id: 70,
+                task_id: 11,
+                start: NaiveDate::from_ymd_opt(2025, 2, 15).unwrap(),
+                finish: NaiveDate::from_ymd_opt(2025, 3, 14).unwrap(),
+                hours: 12.5,
+            }]
+        );
+        // Importing the same report again upserts the same keys.
+        assert_eq!(
+            time_entries(report(), &resources, &tasks, project_start).unwrap(),
+            entries
+        );
+    }
+
+    #[test]
+    fn entries_no_longer_reported_are_removed() {
+        let resources = HashMap::from([("7".to_string(), 70)]);
+        let tasks = HashMap::from([("1.1".to_string(), 11), ("1.2".to_string(), 12)]);
+        let project_start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let entries = time_entries(
+            vec![reported("7", "1.1", 1, 8.), reported("7", "1.2", 2, 8.)],
+            &resources,
+            &tasks,
+            project_start,
+        )
+        .unwrap();
+        let imported = ["camino/7/1.1/1", "camino/7/1.1/2", "camino/8/1.2/2"];
+        assert_eq!(
+            no_longer_reported(imported.map(str::to_string).to_vec(), &entries),
+            ["camino/7/1.1/2", "camino/8/1.2/2"]
+        );
+    }
+}
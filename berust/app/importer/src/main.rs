@@ -1,9 +1,11 @@
 mod camino_importer;
 use crate::camino_importer::*;
 use sea_orm::Database;
+use std::path::Path;
+use tracing::warn;
 
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), String> {
     tracing_subscriber::fmt()
         .with_max_level(tracing::Level::DEBUG)
         .init();
@@ -12,8 +14,40 @@ async fn main() {
         panic!("DATABASE_URL environment variable not set, using default");
     });
 
-    let db = Database::connect(db_connection_string).await.unwrap();
-    import_project_plan(db.clone()).await.unwrap();
-
-    todo!("Import reports");
+    let db = Database::connect(db_connection_string)
+        .await
+        .map_err(|e| format!("Failed to connect to the database: {e}"))?;
+    match std::env::args().nth(1).as_deref() {
+        // Invoices are exported again whenever new ones arrive, so they can be imported alone
+        Some("expenses") => {
+            let path = std::env::args()
+                .nth(2)
+                .unwrap_or_else(|| OTHER_COSTS.to_string());
+            import_expenses(db.clone(), &path).await?;
+        }
+        Some("exchange_rates") => {
+            let path = std::env::args()
+                .nth(2)
+                .unwrap_or_else(|| EXCHANGE_RATES.to_string());
+            import_exchange_rates(db.clone(), &path).await?;
+        }
+        Some(command) => {
+            return Err(format!(
+                "Unknown command {command}, expected none, expenses [path] or exchange_rates [path]"
+            ));
+        }
+        None => {
+            import_project_plan(db.clone()).await?;
+            import_team_members(db.clone()).await?;
+            import_time_report(db.clone()).await?;
+            // Rates are only needed to report costs in a single currency
+            if Path::new(EXCHANGE_RATES).exists() {
+                import_exchange_rates(db.clone(), EXCHANGE_RATES).await?;
+            } else {
+                warn!("No exchange rates at {EXCHANGE_RATES}, skipping them");
+            }
+            import_expenses(db.clone(), OTHER_COSTS).await?;
+        }
+    }
+    Ok(())
 }
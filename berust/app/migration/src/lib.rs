@@ -1,6 +1,19 @@
 pub use sea_orm_migration::prelude::*;
 
+mod m20250409_000001_baseline_status_type;
 mod m20250410_000001_init;
+mod m20250415_000001_baseline_status;
+mod m20250420_000001_resource_type_is_active;
+mod m20250425_000001_task_dependencies;
+mod m20250501_000001_calendars;
+mod m20250505_000001_time_entries;
+mod m20250510_000001_team_members;
+mod m20250515_000001_expenses;
+mod m20250520_000001_exchange_rates;
+mod m20250525_000001_task_status_history;
+mod m20250601_000001_task_progress;
+mod m20250605_000001_task_progress_actual_cost;
+mod m20250610_000001_milestones;
 
 pub struct Migrator;
 
@@ -8,7 +21,20 @@ pub struct Migrator;
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
         vec![
+            Box::new(m20250409_000001_baseline_status_type::Migration),
             Box::new(m20250410_000001_init::Migration),
+            Box::new(m20250415_000001_baseline_status::Migration),
+            Box::new(m20250420_000001_resource_type_is_active::Migration),
+            Box::new(m20250425_000001_task_dependencies::Migration),
+            Box::new(m20250501_000001_calendars::Migration),
+            Box::new(m20250505_000001_time_entries::Migration),
+            Box::new(m20250510_000001_team_members::Migration),
+            Box::new(m20250515_000001_expenses::Migration),
+            Box::new(m20250520_000001_exchange_rates::Migration),
+            Box::new(m20250525_000001_task_status_history::Migration),
+            Box::new(m20250601_000001_task_progress::Migration),
+            Box::new(m20250605_000001_task_progress_actual_cost::Migration),
+            Box::new(m20250610_000001_milestones::Migration),
         ]
     }
 }
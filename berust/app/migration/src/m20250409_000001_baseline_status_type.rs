@@ -0,0 +1,32 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                extension::postgres::Type::create()
+                    .as_enum(Alias::new("baseline_status"))
+                    .values([
+                        Alias::new("Draft"),
+                        Alias::new("Approved"),
+                        Alias::new("Locked"),
+                    ])
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_type(
+                extension::postgres::Type::drop()
+                    .name(Alias::new("baseline_status"))
+                    .to_owned(),
+            )
+            .await
+    }
+}
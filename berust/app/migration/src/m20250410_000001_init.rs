@@ -211,7 +211,7 @@ $$ LANGUAGE plpgsql strict immutable;
     }
 }
 
-fn default_id_statement(table_name: &str, column_name: &str) -> Vec<String> {
+pub(crate) fn default_id_statement(table_name: &str, column_name: &str) -> Vec<String> {
     vec![
         format!("CREATE SEQUENCE {column_name}_seq;"),
         format!(
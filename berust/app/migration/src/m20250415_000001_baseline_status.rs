@@ -0,0 +1,71 @@
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use sea_orm_migration::prelude::*;
+
+use entity::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if !manager.has_column("baselines", "status").await? {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(baselines::Entity)
+                        .add_column(
+                            ColumnDef::new(baselines::Column::Status)
+                                .custom(Alias::new("baseline_status"))
+                                .not_null()
+                                .default("Draft"),
+                        )
+                        .add_column(ColumnDef::new(baselines::Column::ApprovedBy).string())
+                        .add_column(
+                            ColumnDef::new(baselines::Column::ApprovedAt)
+                                .timestamp_with_time_zone(),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // Only approved baselines may be current, so the one init seeds as current is approved.
+        let db = manager.get_connection();
+        let current = config::Entity::find()
+            .filter(config::Column::ConfigKey.eq("baseline_id_current"))
+            .one(db)
+            .await?
+            .and_then(|config| config.config_value)
+            .and_then(|value| value.parse::<i64>().ok());
+        if let Some(baseline_id) = current {
+            baselines::Entity::update_many()
+                .col_expr(
+                    baselines::Column::Status,
+                    Expr::val("Approved").as_enum(Alias::new("baseline_status")),
+                )
+                .col_expr(
+                    baselines::Column::ApprovedAt,
+                    Expr::current_timestamp().into(),
+                )
+                .filter(baselines::Column::BaselineId.eq(baseline_id))
+                .filter(baselines::Column::Status.eq(baselines::BaselineStatus::Draft))
+                .exec(db)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(baselines::Entity)
+                    .drop_column(baselines::Column::Status)
+                    .drop_column(baselines::Column::ApprovedBy)
+                    .drop_column(baselines::Column::ApprovedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+use entity::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.has_column("resource_types", "is_active").await? {
+            return Ok(());
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(resource_types::Entity)
+                    .add_column(
+                        ColumnDef::new(resource_types::Column::IsActive)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(resource_types::Entity)
+                    .drop_column(resource_types::Column::IsActive)
+                    .to_owned(),
+            )
+            .await
+    }
+}
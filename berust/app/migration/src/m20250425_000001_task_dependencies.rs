@@ -0,0 +1,54 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+
+use crate::m20250410_000001_init::default_id_statement;
+use entity::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let schema = sea_orm::Schema::new(manager.get_database_backend());
+        let db = manager.get_connection();
+
+        manager
+            .create_type(
+                extension::postgres::Type::create()
+                    .as_enum(Alias::new("dependency_type"))
+                    .values([
+                        Alias::new("FinishToStart"),
+                        Alias::new("StartToStart"),
+                        Alias::new("FinishToFinish"),
+                        Alias::new("StartToFinish"),
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(schema.create_table_from_entity(task_dependencies::Entity))
+            .await?;
+        for statement in default_id_statement("task_dependencies", "task_dependency_id") {
+            db.execute(Statement::from_string(db.get_database_backend(), statement))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(task_dependencies::Entity).to_owned())
+            .await?;
+
+        manager
+            .drop_type(
+                extension::postgres::Type::drop()
+                    .name(Alias::new("dependency_type"))
+                    .to_owned(),
+            )
+            .await
+    }
+}
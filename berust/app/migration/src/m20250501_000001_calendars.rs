@@ -0,0 +1,155 @@
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, Set, Statement};
+use sea_orm_migration::prelude::*;
+
+use crate::m20250410_000001_init::default_id_statement;
+use entity::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let schema = sea_orm::Schema::new(manager.get_database_backend());
+        let db = manager.get_connection();
+
+        manager
+            .create_type(
+                extension::postgres::Type::create()
+                    .as_enum(Alias::new("calendar_exception_kind"))
+                    .values([Alias::new("Holiday"), Alias::new("Exception")])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(schema.create_table_from_entity(calendars::Entity))
+            .await?;
+        for statement in default_id_statement("calendars", "calendar_id") {
+            db.execute(Statement::from_string(db.get_database_backend(), statement))
+                .await?;
+        }
+
+        manager
+            .create_table(schema.create_table_from_entity(calendar_work_hours::Entity))
+            .await?;
+        for statement in default_id_statement("calendar_work_hours", "calendar_work_hours_id") {
+            db.execute(Statement::from_string(db.get_database_backend(), statement))
+                .await?;
+        }
+
+        manager
+            .create_table(schema.create_table_from_entity(calendar_exceptions::Entity))
+            .await?;
+        for statement in default_id_statement("calendar_exceptions", "calendar_exception_id") {
+            db.execute(Statement::from_string(db.get_database_backend(), statement))
+                .await?;
+        }
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-calendar_exceptions-calendar_id-date")
+                    .table(calendar_exceptions::Entity)
+                    .col(calendar_exceptions::Column::CalendarId)
+                    .col(calendar_exceptions::Column::Date)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // The project default calendar: Monday to Friday, 8:00 to 16:00
+        calendars::Entity::insert(calendars::ActiveModel {
+            calendar_id: Set(1),
+            name: Set("Standard".to_string()),
+            timezone: Set("Europe/Warsaw".to_string()),
+            ..Default::default()
+        })
+        .exec(db)
+        .await?;
+        calendar_work_hours::Entity::insert_many((0..5).map(|weekday| {
+            calendar_work_hours::ActiveModel {
+                calendar_id: Set(1),
+                weekday: Set(weekday),
+                start_minute: Set(8 * 60),
+                finish_minute: Set(16 * 60),
+                ..Default::default()
+            }
+        }))
+        .exec(db)
+        .await?;
+
+        let configured = config::Entity::find()
+            .filter(config::Column::ConfigKey.eq("calendar_id_default"))
+            .count(db)
+            .await?;
+        if configured == 0 {
+            config::Entity::insert(config::ActiveModel {
+                config_key: Set("calendar_id_default".to_string()),
+                config_value: Set(Some("1".to_string())),
+                ..Default::default()
+            })
+            .exec(db)
+            .await?;
+        }
+
+        if !manager.has_column("resources", "calendar_id").await? {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(resources::Entity)
+                        .add_column(ColumnDef::new(resources::Column::CalendarId).big_integer())
+                        .to_owned(),
+                )
+                .await?;
+        }
+        if manager.has_column("resources", "availability").await? {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(resources::Entity)
+                        .drop_column(Alias::new("availability"))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(resources::Entity)
+                    .add_column(ColumnDef::new(Alias::new("availability")).string())
+                    .drop_column(resources::Column::CalendarId)
+                    .to_owned(),
+            )
+            .await?;
+
+        config::Entity::delete_many()
+            .filter(config::Column::ConfigKey.eq("calendar_id_default"))
+            .exec(db)
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(calendar_exceptions::Entity).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(calendar_work_hours::Entity).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(calendars::Entity).to_owned())
+            .await?;
+
+        manager
+            .drop_type(
+                extension::postgres::Type::drop()
+                    .name(Alias::new("calendar_exception_kind"))
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,32 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+
+use crate::m20250410_000001_init::default_id_statement;
+use entity::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let schema = sea_orm::Schema::new(manager.get_database_backend());
+        let db = manager.get_connection();
+
+        manager
+            .create_table(schema.create_table_from_entity(team_members::Entity))
+            .await?;
+        for statement in default_id_statement("team_members", "user_id") {
+            db.execute(Statement::from_string(db.get_database_backend(), statement))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(team_members::Entity).to_owned())
+            .await
+    }
+}
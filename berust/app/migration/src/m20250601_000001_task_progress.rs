@@ -0,0 +1,43 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+
+use crate::m20250410_000001_init::default_id_statement;
+use entity::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let schema = sea_orm::Schema::new(manager.get_database_backend());
+        let db = manager.get_connection();
+
+        manager
+            .create_table(schema.create_table_from_entity(task_progress::Entity))
+            .await?;
+        for statement in default_id_statement("task_progress", "task_progress_id") {
+            db.execute(Statement::from_string(db.get_database_backend(), statement))
+                .await?;
+        }
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-task_progress-task_id-status_date")
+                    .table(task_progress::Entity)
+                    .col(task_progress::Column::TaskId)
+                    .col(task_progress::Column::StatusDate)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(task_progress::Entity).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,37 @@
+use sea_orm_migration::prelude::*;
+
+use entity::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.has_column("task_progress", "actual_cost").await? {
+            return Ok(());
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(task_progress::Entity)
+                    .add_column(ColumnDef::new(task_progress::Column::ActualCost).double())
+                    .add_column(ColumnDef::new(task_progress::Column::CostCurrency).string())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(task_progress::Entity)
+                    .drop_column(task_progress::Column::ActualCost)
+                    .drop_column(task_progress::Column::CostCurrency)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+use entity::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.has_column("tasks_baselines", "milestone").await? {
+            return Ok(());
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(tasks_baselines::Entity)
+                    .add_column(
+                        ColumnDef::new(tasks_baselines::Column::Milestone)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(tasks_baselines::Entity)
+                    .drop_column(tasks_baselines::Column::Milestone)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,768 @@
+use actix_web::{Result, web};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    EntityTrait, IntoActiveModel, QueryFilter, TransactionTrait,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use tracing::{error, info, warn};
+
+use crate::MyError;
+use crate::config::{config_value, set_config_value};
+use crate::response::{Encoded, Payload};
+use crate::tasks::find_task_baselines;
+
+pub trait IntoModelBaselineStatus {
+    fn into_model_baseline_status(self) -> communication::baselines::BaselineStatus;
+}
+
+impl IntoModelBaselineStatus for entity::baselines::BaselineStatus {
+    fn into_model_baseline_status(self) -> communication::baselines::BaselineStatus {
+        match self {
+            entity::baselines::BaselineStatus::Draft => {
+                communication::baselines::BaselineStatus::Draft
+            }
+            entity::baselines::BaselineStatus::Approved => {
+                communication::baselines::BaselineStatus::Approved
+            }
+            entity::baselines::BaselineStatus::Locked => {
+                communication::baselines::BaselineStatus::Locked
+            }
+        }
+    }
+}
+
+pub trait IntoModelBaseline {
+    fn into_model_baseline(self) -> communication::baselines::Baseline;
+}
+
+impl IntoModelBaseline for entity::baselines::Model {
+    fn into_model_baseline(self) -> communication::baselines::Baseline {
+        communication::baselines::Baseline {
+            baseline_id: self.baseline_id,
+            name: self.name,
+            description: self.description,
+            comment: self.comment,
+            is_active: self.is_active,
+            status: self.status.into_model_baseline_status(),
+            approved_by: self.approved_by,
+            approved_at: self.approved_at.map(|approved_at| approved_at.into()),
+        }
+    }
+}
+
+async fn find_baseline<C: ConnectionTrait>(
+    db: &C,
+    baseline_id: i64,
+) -> Result<entity::baselines::Model, MyError> {
+    entity::baselines::Entity::find_by_id(baseline_id)
+        .one(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching baseline: {}", e);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("baseline {baseline_id}")))
+}
+
+pub async fn ensure_baseline_exists<C: ConnectionTrait>(
+    db: &C,
+    baseline_id: i64,
+) -> Result<(), MyError> {
+    find_baseline(db, baseline_id).await.map(|_| ())
+}
+
+/// The baseline the project is currently executed against, from config.
+pub async fn current_baseline_id<C: ConnectionTrait>(db: &C) -> Result<i64, MyError> {
+    let value = config_value(db, &communication::config::BASELINE_ID_CURRENT).await?;
+    value.parse().map_err(|e| {
+        error!("Invalid current baseline {}: {}", value, e);
+        MyError::DatabaseError
+    })
+}
+
+/// Rows of `tasks_baselines` and `resources_baselines` belonging to a locked baseline must not change.
+pub async fn ensure_baseline_editable<C: ConnectionTrait>(
+    db: &C,
+    baseline_id: i64,
+) -> Result<(), MyError> {
+    let baseline = find_baseline(db, baseline_id).await?;
+    if baseline.status == entity::baselines::BaselineStatus::Locked {
+        warn!("Rejected change of locked baseline {}", baseline_id);
+        return Err(MyError::Conflict(format!(
+            "baseline {baseline_id} is locked"
+        )));
+    }
+    Ok(())
+}
+
+pub async fn get_baselines(
+    db: web::Data<DatabaseConnection>,
+) -> Result<Encoded<Vec<communication::baselines::Baseline>>, MyError> {
+    let baselines: Vec<communication::baselines::Baseline> = entity::baselines::Entity::find()
+        .all(db.get_ref())
+        .await
+        .map_err(|e| {
+            error!("Database error fetching baselines: {}", e);
+            MyError::DatabaseError
+        })?
+        .into_iter()
+        .map(|record| record.into_model_baseline())
+        .collect();
+
+    Ok(Encoded::ok(baselines))
+}
+
+/// Copies every task, resource allocation and task dependency of `source_baseline_id` into a newly
+/// created baseline.
+///
+/// Parents reference task ids, which are shared by all baselines, so the WBS tree carries over as is.
+/// A parent that is not planned in the source baseline is dropped rather than left dangling.
+/// All rows are written inside `db`, so callers passing a transaction get an all or nothing copy.
+pub async fn snapshot_baseline<C: ConnectionTrait>(
+    db: &C,
+    source_baseline_id: i64,
+    snapshot: communication::baselines::BaselineSnapshot,
+) -> Result<entity::baselines::Model, MyError> {
+    ensure_baseline_exists(db, source_baseline_id).await?;
+
+    let baseline = entity::baselines::ActiveModel {
+        name: Set(snapshot.name),
+        description: Set(snapshot.description),
+        comment: Set(snapshot.comment),
+        is_active: Set(true),
+        status: Set(entity::baselines::BaselineStatus::Draft),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .map_err(|e| {
+        error!("Database error inserting baseline: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let task_baselines = entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::BaselineId.eq(source_baseline_id))
+        .all(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task baselines: {}", e);
+            MyError::DatabaseError
+        })?;
+    let planned_tasks: HashSet<i64> = task_baselines.iter().map(|t| t.task_id).collect();
+
+    if !task_baselines.is_empty() {
+        entity::tasks_baselines::Entity::insert_many(
+            task_baselines
+                .into_iter()
+                .map(|task| entity::tasks_baselines::ActiveModel {
+                    task_id: Set(task.task_id),
+                    baseline_id: Set(baseline.baseline_id),
+                    wbs: Set(task.wbs),
+                    parent: Set(task.parent.filter(|parent| planned_tasks.contains(parent))),
+                    start: Set(task.start),
+                    start_timezone: Set(task.start_timezone),
+                    finish: Set(task.finish),
+                    finish_timezone: Set(task.finish_timezone),
+                    milestone: Set(task.milestone),
+                    ..Default::default()
+                })
+                .collect::<Vec<entity::tasks_baselines::ActiveModel>>(),
+        )
+        .exec(db)
+        .await
+        .map_err(|e| {
+            error!("Database error copying task baselines: {}", e);
+            MyError::DatabaseError
+        })?;
+    }
+
+    let resource_baselines = entity::resources_baselines::Entity::find()
+        .filter(entity::resources_baselines::Column::BaselineId.eq(source_baseline_id))
+        .all(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching resource allocations: {}", e);
+            MyError::DatabaseError
+        })?;
+
+    if !resource_baselines.is_empty() {
+        entity::resources_baselines::Entity::insert_many(
+            resource_baselines
+                .into_iter()
+                .map(|allocation| entity::resources_baselines::ActiveModel {
+                    resource_id: Set(allocation.resource_id),
+                    baseline_id: Set(baseline.baseline_id),
+                    task_id: Set(allocation.task_id),
+                    capacity_allocated: Set(allocation.capacity_allocated),
+                    ..Default::default()
+                })
+                .collect::<Vec<entity::resources_baselines::ActiveModel>>(),
+        )
+        .exec(db)
+        .await
+        .map_err(|e| {
+            error!("Database error copying resource allocations: {}", e);
+            MyError::DatabaseError
+        })?;
+    }
+
+    let task_dependencies = entity::task_dependencies::Entity::find()
+        .filter(entity::task_dependencies::Column::BaselineId.eq(source_baseline_id))
+        .all(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task dependencies: {}", e);
+            MyError::DatabaseError
+        })?;
+
+    if !task_dependencies.is_empty() {
+        entity::task_dependencies::Entity::insert_many(
+            task_dependencies
+                .into_iter()
+                .map(|dependency| entity::task_dependencies::ActiveModel {
+                    baseline_id: Set(baseline.baseline_id),
+                    predecessor_id: Set(dependency.predecessor_id),
+                    successor_id: Set(dependency.successor_id),
+                    dependency_type: Set(dependency.dependency_type),
+                    lag: Set(dependency.lag),
+                    ..Default::default()
+                })
+                .collect::<Vec<entity::task_dependencies::ActiveModel>>(),
+        )
+        .exec(db)
+        .await
+        .map_err(|e| {
+            error!("Database error copying task dependencies: {}", e);
+            MyError::DatabaseError
+        })?;
+    }
+
+    info!(
+        "Baseline {} copied into baseline {}",
+        source_baseline_id, baseline.baseline_id
+    );
+
+    Ok(baseline)
+}
+
+pub async fn create_baseline_snapshot(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    payload: Payload<communication::baselines::BaselineSnapshot>,
+) -> Result<Encoded<communication::baselines::Baseline>, MyError> {
+    let source_baseline_id = path.into_inner();
+    let snapshot = payload.into_inner();
+    if snapshot.name.trim().is_empty() {
+        return Err(MyError::InvalidField(
+            "name",
+            "name must not be empty".to_string(),
+        ));
+    }
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let baseline = snapshot_baseline(&txn, source_baseline_id, snapshot).await?;
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::created(baseline.into_model_baseline()))
+}
+
+fn sum_capacity(total: Option<f64>, capacity: Option<f64>) -> Option<f64> {
+    match (total, capacity) {
+        (Some(total), Some(capacity)) => Some(total + capacity),
+        (total, capacity) => total.or(capacity),
+    }
+}
+
+/// Capacity allocated per task and resource; several rows for the same pair are summed up.
+fn index_allocations(
+    allocations: Vec<communication::baselines::ResourceAllocation>,
+) -> BTreeMap<(i64, i64), (Option<String>, Option<f64>)> {
+    let mut index: BTreeMap<(i64, i64), (Option<String>, Option<f64>)> = BTreeMap::new();
+    for allocation in allocations {
+        let entry = index
+            .entry((allocation.task_id, allocation.resource_id))
+            .or_insert((allocation.resource_summary, None));
+        entry.1 = sum_capacity(entry.1, allocation.capacity_allocated);
+    }
+    index
+}
+
+/// Compares the tasks and resource allocations of two baselines, keyed by task id.
+pub fn compare(
+    baseline_id_from: i64,
+    baseline_id_to: i64,
+    tasks_from: Vec<communication::baselines::TaskBaseline>,
+    tasks_to: Vec<communication::baselines::TaskBaseline>,
+    allocations_from: Vec<communication::baselines::ResourceAllocation>,
+    allocations_to: Vec<communication::baselines::ResourceAllocation>,
+) -> communication::baselines::BaselineVariance {
+    use communication::baselines::{AllocationVariance, TaskVariance, VarianceKind};
+
+    let tasks_from: HashMap<i64, _> = tasks_from.into_iter().map(|t| (t.task_id, t)).collect();
+    let tasks_to: HashMap<i64, _> = tasks_to.into_iter().map(|t| (t.task_id, t)).collect();
+    let allocations_from = index_allocations(allocations_from);
+    let allocations_to = index_allocations(allocations_to);
+
+    let task_ids: BTreeSet<i64> = tasks_from.keys().chain(tasks_to.keys()).copied().collect();
+
+    let mut tasks: Vec<TaskVariance> = task_ids
+        .into_iter()
+        .map(|task_id| {
+            let from = tasks_from.get(&task_id);
+            let to = tasks_to.get(&task_id);
+
+            let resource_ids: BTreeSet<i64> = allocations_from
+                .range((task_id, i64::MIN)..=(task_id, i64::MAX))
+                .chain(allocations_to.range((task_id, i64::MIN)..=(task_id, i64::MAX)))
+                .map(|((_, resource_id), _)| *resource_id)
+                .collect();
+            let allocations: Vec<AllocationVariance> = resource_ids
+                .into_iter()
+                .map(|resource_id| {
+                    let allocated_from = allocations_from.get(&(task_id, resource_id));
+                    let allocated_to = allocations_to.get(&(task_id, resource_id));
+                    let kind = match (allocated_from, allocated_to) {
+                        (None, _) => VarianceKind::Added,
+                        (_, None) => VarianceKind::Removed,
+                        (Some(from), Some(to)) if from.1 != to.1 => VarianceKind::Changed,
+                        _ => VarianceKind::Unchanged,
+                    };
+                    AllocationVariance {
+                        resource_id,
+                        resource_summary: allocated_to
+                            .or(allocated_from)
+                            .and_then(|(summary, _)| summary.clone()),
+                        kind,
+                        capacity_allocated_from: allocated_from.and_then(|(_, c)| *c),
+                        capacity_allocated_to: allocated_to.and_then(|(_, c)| *c),
+                    }
+                })
+                .collect();
+
+            let moved = match (from, to) {
+                (Some(from), Some(to)) => from.wbs != to.wbs || from.parent != to.parent,
+                _ => false,
+            };
+            let milestone_changed = match (from, to) {
+                (Some(from), Some(to)) => from.milestone != to.milestone,
+                _ => false,
+            };
+            let (start_slip, finish_slip, duration_change) = match (from, to) {
+                (Some(from), Some(to)) => (
+                    Some((to.start - from.start).num_seconds()),
+                    Some((to.finish - from.finish).num_seconds()),
+                    Some(((to.finish - to.start) - (from.finish - from.start)).num_seconds()),
+                ),
+                _ => (None, None, None),
+            };
+            let kind = match (from, to) {
+                (None, _) => VarianceKind::Added,
+                (_, None) => VarianceKind::Removed,
+                _ if moved
+                    || milestone_changed
+                    || start_slip != Some(0)
+                    || finish_slip != Some(0)
+                    || allocations
+                        .iter()
+                        .any(|a| a.kind != VarianceKind::Unchanged) =>
+                {
+                    VarianceKind::Changed
+                }
+                _ => VarianceKind::Unchanged,
+            };
+
+            TaskVariance {
+                task_id,
+                task_summary: to
+                    .or(from)
+                    .map(|t| t.task_summary.clone())
+                    .unwrap_or_default(),
+                kind,
+                start_slip,
+                finish_slip,
+                duration_change,
+                wbs_from: from.map(|t| t.wbs.clone()),
+                wbs_to: to.map(|t| t.wbs.clone()),
+                parent_from: from.and_then(|t| t.parent),
+                parent_to: to.and_then(|t| t.parent),
+                moved,
+                milestone_changed,
+                allocations,
+            }
+        })
+        .collect();
+
+    tasks.sort_by(|a, b| {
+        a.wbs_to
+            .as_ref()
+            .or(a.wbs_from.as_ref())
+            .cmp(&b.wbs_to.as_ref().or(b.wbs_from.as_ref()))
+    });
+
+    communication::baselines::BaselineVariance {
+        baseline_id_from,
+        baseline_id_to,
+        tasks,
+    }
+}
+
+pub async fn compare_baselines(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<Encoded<communication::baselines::BaselineVariance>, MyError> {
+    let baseline_id_from: i64 = crate::query_param(&query, "from")?;
+    let baseline_id_to: i64 = crate::query_param(&query, "to")?;
+
+    ensure_baseline_exists(db.get_ref(), baseline_id_from).await?;
+    ensure_baseline_exists(db.get_ref(), baseline_id_to).await?;
+
+    let variance = compare(
+        baseline_id_from,
+        baseline_id_to,
+        find_task_baselines(db.get_ref(), baseline_id_from).await?,
+        find_task_baselines(db.get_ref(), baseline_id_to).await?,
+        crate::resources::find_resource_allocations(db.get_ref(), baseline_id_from).await?,
+        crate::resources::find_resource_allocations(db.get_ref(), baseline_id_to).await?,
+    );
+
+    Ok(Encoded::ok(variance))
+}
+
+async fn schedule_baseline<C: ConnectionTrait>(
+    db: &C,
+    baseline_id: i64,
+) -> Result<communication::schedule::Schedule, MyError> {
+    crate::schedule::schedule(
+        baseline_id,
+        &find_task_baselines(db, baseline_id).await?,
+        &crate::dependencies::find_task_dependencies(db, baseline_id).await?,
+    )
+    .map_err(MyError::Conflict)
+}
+
+/// Computes early and late dates, floats and the critical path of a baseline from its task links.
+pub async fn get_schedule(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+) -> Result<Encoded<communication::schedule::Schedule>, MyError> {
+    let baseline_id = path.into_inner();
+    ensure_baseline_exists(db.get_ref(), baseline_id).await?;
+
+    Ok(Encoded::ok(
+        schedule_baseline(db.get_ref(), baseline_id).await?,
+    ))
+}
+
+/// Schedules a baseline and moves every task to its early start and finish.
+pub async fn apply_schedule(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+) -> Result<Encoded<communication::schedule::Schedule>, MyError> {
+    let baseline_id = path.into_inner();
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    ensure_baseline_editable(&txn, baseline_id).await?;
+    let schedule = schedule_baseline(&txn, baseline_id).await?;
+
+    let scheduled: HashMap<i64, &communication::schedule::ScheduledTask> = schedule
+        .tasks
+        .iter()
+        .map(|task| (task.task_id, task))
+        .collect();
+    let task_baselines = entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::BaselineId.eq(baseline_id))
+        .all(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task baselines: {}", e);
+            MyError::DatabaseError
+        })?;
+    let mut moved = 0;
+    for record in task_baselines {
+        let Some(task) = scheduled.get(&record.task_id) else {
+            continue;
+        };
+        let (start, finish) = (task.early_start.naive(), task.early_finish.naive());
+        if record.start == start && record.finish == finish {
+            continue;
+        }
+        let mut record = record.into_active_model();
+        record.start = Set(start);
+        record.finish = Set(finish);
+        record.update(&txn).await.map_err(|e| {
+            error!("Database error updating task baseline: {}", e);
+            MyError::DatabaseError
+        })?;
+        moved += 1;
+    }
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    info!("Schedule of baseline {} moved {} tasks", baseline_id, moved);
+
+    Ok(Encoded::ok(schedule))
+}
+
+/// Approves a draft baseline, recording who approved it and when.
+pub async fn approve_baseline(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    payload: Payload<communication::baselines::BaselineApproval>,
+) -> Result<Encoded<communication::baselines::Baseline>, MyError> {
+    let baseline_id = path.into_inner();
+    let approval = payload.into_inner();
+    if approval.approved_by.trim().is_empty() {
+        return Err(MyError::InvalidField(
+            "approved_by",
+            "approved_by must not be empty".to_string(),
+        ));
+    }
+
+    let baseline = find_baseline(db.get_ref(), baseline_id).await?;
+    if baseline.status != entity::baselines::BaselineStatus::Draft {
+        return Err(MyError::Conflict(format!(
+            "only draft baselines can be approved, baseline {baseline_id} is {:?}",
+            baseline.status
+        )));
+    }
+
+    let mut baseline = baseline.into_active_model();
+    baseline.status = Set(entity::baselines::BaselineStatus::Approved);
+    baseline.approved_by = Set(Some(approval.approved_by));
+    baseline.approved_at = Set(Some(chrono::Utc::now()));
+    let updated = baseline.update(db.get_ref()).await.map_err(|e| {
+        error!("Database error updating baseline: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::ok(updated.into_model_baseline()))
+}
+
+/// Locks an approved baseline, after which its tasks and allocations can no longer change.
+pub async fn lock_baseline(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+) -> Result<Encoded<communication::baselines::Baseline>, MyError> {
+    let baseline_id = path.into_inner();
+
+    let baseline = find_baseline(db.get_ref(), baseline_id).await?;
+    if baseline.status != entity::baselines::BaselineStatus::Approved {
+        return Err(MyError::Conflict(format!(
+            "only approved baselines can be locked, baseline {baseline_id} is {:?}",
+            baseline.status
+        )));
+    }
+
+    let mut baseline = baseline.into_active_model();
+    baseline.status = Set(entity::baselines::BaselineStatus::Locked);
+    let updated = baseline.update(db.get_ref()).await.map_err(|e| {
+        error!("Database error updating baseline: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::ok(updated.into_model_baseline()))
+}
+
+/// Makes an approved or locked baseline the current one by updating the `baseline_id_current` config key.
+pub async fn promote_baseline(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+) -> Result<Encoded<communication::baselines::Baseline>, MyError> {
+    let baseline_id = path.into_inner();
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let baseline = find_baseline(&txn, baseline_id).await?;
+    if baseline.status == entity::baselines::BaselineStatus::Draft {
+        return Err(MyError::Conflict(format!(
+            "baseline {baseline_id} has to be approved before it becomes current"
+        )));
+    }
+
+    set_config_value(
+        &txn,
+        &communication::config::BASELINE_ID_CURRENT,
+        baseline_id.to_string(),
+    )
+    .await?;
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    info!("Baseline {} promoted to current", baseline_id);
+
+    Ok(Encoded::ok(baseline.into_model_baseline()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{allocation, task};
+    use communication::baselines::{BaselineVariance, TaskVariance, VarianceKind};
+
+    fn task_variance(variance: &BaselineVariance, task_id: i64) -> &TaskVariance {
+        variance
+            .tasks
+            .iter()
+            .find(|task| task.task_id == task_id)
+            .unwrap()
+    }
+
+    /// 1 is unchanged, 2 slips and loses capacity, 3 is removed, 4 added, 5 moves to another
+    /// resource and under task 1 and 6 becomes a milestone.
+    fn baselines() -> BaselineVariance {
+        let mut moved = task(5, 0, 3);
+        moved.parent = Some(1);
+        moved.wbs = "1.1".to_string();
+        let mut milestone = task(6, 5, 5);
+        milestone.milestone = true;
+        compare(
+            1,
+            2,
+            vec![
+                task(1, 0, 10),
+                task(2, 0, 5),
+                task(3, 0, 4),
+                task(5, 0, 3),
+                task(6, 0, 5),
+            ],
+            vec![
+                task(1, 0, 10),
+                task(2, 2, 7),
+                task(4, 0, 2),
+                moved,
+                milestone,
+            ],
+            vec![
+                allocation(1, 10, 1.),
+                allocation(2, 10, 1.),
+                allocation(3, 10, 1.),
+                allocation(5, 10, 1.),
+            ],
+            vec![
+                allocation(1, 10, 1.),
+                allocation(2, 10, 0.5),
+                allocation(4, 10, 1.),
+                allocation(5, 11, 1.),
+            ],
+        )
+    }
+
+    #[test]
+    fn unchanged_tasks_have_no_slip() {
+        let variance = baselines();
+        let unchanged = task_variance(&variance, 1);
+        assert_eq!(unchanged.kind, VarianceKind::Unchanged);
+        assert_eq!(
+            (
+                unchanged.start_slip,
+                unchanged.finish_slip,
+                unchanged.duration_change
+            ),
+            (Some(0), Some(0), Some(0))
+        );
+        assert!(!unchanged.moved);
+        assert!(!unchanged.milestone_changed);
+        assert_eq!(unchanged.allocations[0].kind, VarianceKind::Unchanged);
+    }
+
+    #[test]
+    fn changed_tasks_report_slips_and_allocation_changes() {
+        let variance = baselines();
+        let slipped = task_variance(&variance, 2);
+        assert_eq!(slipped.kind, VarianceKind::Changed);
+        assert_eq!(
+            (
+                slipped.start_slip,
+                slipped.finish_slip,
+                slipped.duration_change
+            ),
+            (Some(2 * 3600), Some(2 * 3600), Some(0))
+        );
+        let allocation = &slipped.allocations[0];
+        assert_eq!(allocation.kind, VarianceKind::Changed);
+        assert_eq!(
+            (
+                allocation.capacity_allocated_from,
+                allocation.capacity_allocated_to
+            ),
+            (Some(1.), Some(0.5))
+        );
+    }
+
+    #[test]
+    fn added_and_removed_tasks_carry_their_allocations() {
+        let variance = baselines();
+        let removed = task_variance(&variance, 3);
+        assert_eq!(removed.kind, VarianceKind::Removed);
+        assert_eq!(
+            (removed.wbs_from.as_deref(), removed.wbs_to.as_deref()),
+            (Some("3"), None)
+        );
+        assert_eq!(removed.start_slip, None);
+        assert_eq!(removed.allocations[0].kind, VarianceKind::Removed);
+
+        let added = task_variance(&variance, 4);
+        assert_eq!(added.kind, VarianceKind::Added);
+        assert_eq!(added.duration_change, None);
+        assert_eq!(added.allocations[0].kind, VarianceKind::Added);
+    }
+
+    #[test]
+    fn moved_tasks_and_swapped_resources_are_changes() {
+        let variance = baselines();
+        let moved = task_variance(&variance, 5);
+        assert_eq!(moved.kind, VarianceKind::Changed);
+        assert!(moved.moved);
+        assert_eq!((moved.parent_from, moved.parent_to), (None, Some(1)));
+        let kinds: Vec<(i64, VarianceKind)> = moved
+            .allocations
+            .iter()
+            .map(|allocation| (allocation.resource_id, allocation.kind.clone()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![(10, VarianceKind::Removed), (11, VarianceKind::Added)]
+        );
+    }
+
+    #[test]
+    fn milestone_changes_are_reported_apart_from_the_duration() {
+        let variance = baselines();
+        let milestone = task_variance(&variance, 6);
+        assert_eq!(milestone.kind, VarianceKind::Changed);
+        assert!(milestone.milestone_changed);
+        assert_eq!(
+            (milestone.finish_slip, milestone.duration_change),
+            (Some(0), Some(-5 * 3600))
+        );
+        assert!(!task_variance(&variance, 2).milestone_changed);
+        assert!(!task_variance(&variance, 4).milestone_changed);
+    }
+
+    #[test]
+    fn tasks_are_ordered_by_wbs() {
+        let task_ids: Vec<i64> = baselines().tasks.iter().map(|task| task.task_id).collect();
+        assert_eq!(task_ids, vec![1, 5, 2, 3, 4, 6]);
+    }
+}
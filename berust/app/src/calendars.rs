@@ -0,0 +1,782 @@
+use actix_web::{HttpResponse, Result, web};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use communication::calendars::{
+    Calendar, CalendarException, CalendarExceptionKind, WorkHours, WorkingTime,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder, TransactionTrait,
+};
+use std::collections::{HashMap, HashSet};
+use tracing::error;
+
+use crate::MyError;
+use crate::config::config_value;
+use crate::resources::find_resource;
+use crate::response::{Encoded, Payload};
+use crate::working_time::WorkingCalendar;
+
+/// Minutes in a day; the latest a working period may end.
+const DAY_MINUTES: u16 = 24 * 60;
+
+/// Longest span working time is summed over, in seconds: ten years. Working time is summed day by
+/// day, so a query may not walk more days than that.
+const MAX_SPAN: i64 = 10 * 366 * 24 * 60 * 60;
+
+pub trait IntoModelCalendar {
+    fn into_model_calendar(self) -> Calendar;
+}
+
+impl IntoModelCalendar for entity::calendars::Model {
+    fn into_model_calendar(self) -> Calendar {
+        Calendar {
+            calendar_id: self.calendar_id,
+            name: self.name,
+            description: self.description,
+            timezone: self.timezone,
+            parent_calendar_id: self.parent_calendar_id,
+        }
+    }
+}
+
+pub trait IntoModelWorkHours {
+    fn into_model_work_hours(self) -> WorkHours;
+}
+
+impl IntoModelWorkHours for entity::calendar_work_hours::Model {
+    fn into_model_work_hours(self) -> WorkHours {
+        WorkHours {
+            calendar_work_hours_id: self.calendar_work_hours_id,
+            calendar_id: self.calendar_id,
+            weekday: self.weekday as u8,
+            start_minute: self.start_minute as u16,
+            finish_minute: self.finish_minute as u16,
+        }
+    }
+}
+
+pub trait IntoModelCalendarExceptionKind {
+    fn into_model_calendar_exception_kind(self) -> CalendarExceptionKind;
+}
+
+impl IntoModelCalendarExceptionKind for entity::calendar_exceptions::CalendarExceptionKind {
+    fn into_model_calendar_exception_kind(self) -> CalendarExceptionKind {
+        match self {
+            entity::calendar_exceptions::CalendarExceptionKind::Holiday => {
+                CalendarExceptionKind::Holiday
+            }
+            entity::calendar_exceptions::CalendarExceptionKind::Exception => {
+                CalendarExceptionKind::Exception
+            }
+        }
+    }
+}
+
+pub trait IntoEntityCalendarExceptionKind {
+    fn into_entity_calendar_exception_kind(
+        self,
+    ) -> entity::calendar_exceptions::CalendarExceptionKind;
+}
+
+impl IntoEntityCalendarExceptionKind for CalendarExceptionKind {
+    fn into_entity_calendar_exception_kind(
+        self,
+    ) -> entity::calendar_exceptions::CalendarExceptionKind {
+        match self {
+            CalendarExceptionKind::Holiday => {
+                entity::calendar_exceptions::CalendarExceptionKind::Holiday
+            }
+            CalendarExceptionKind::Exception => {
+                entity::calendar_exceptions::CalendarExceptionKind::Exception
+            }
+        }
+    }
+}
+
+pub trait IntoModelCalendarException {
+    fn into_model_calendar_exception(self) -> CalendarException;
+}
+
+impl IntoModelCalendarException for entity::calendar_exceptions::Model {
+    fn into_model_calendar_exception(self) -> CalendarException {
+        CalendarException {
+            calendar_exception_id: self.calendar_exception_id,
+            calendar_id: self.calendar_id,
+            date: self.date.into(),
+            name: self.name,
+            kind: self.kind.into_model_calendar_exception_kind(),
+            start_minute: self.start_minute.map(|minute| minute as u16),
+            finish_minute: self.finish_minute.map(|minute| minute as u16),
+        }
+    }
+}
+
+async fn find_calendar<C: ConnectionTrait>(
+    db: &C,
+    calendar_id: i64,
+) -> Result<entity::calendars::Model, MyError> {
+    entity::calendars::Entity::find_by_id(calendar_id)
+        .one(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching calendar: {}", e);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("calendar {calendar_id}")))
+}
+
+/// Checks that a calendar referenced by the payload `field` exists.
+pub async fn ensure_calendar_exists<C: ConnectionTrait>(
+    db: &C,
+    calendar_id: i64,
+    field: &'static str,
+) -> Result<(), MyError> {
+    match find_calendar(db, calendar_id).await {
+        Err(MyError::NotFound(_)) => Err(MyError::InvalidField(
+            field,
+            format!("calendar {calendar_id} does not exist"),
+        )),
+        result => result.map(|_| ()),
+    }
+}
+
+/// Reads a calendar followed by the calendars it inherits from, up to the topmost one.
+async fn find_calendar_chain<C: ConnectionTrait>(
+    db: &C,
+    calendar_id: i64,
+) -> Result<Vec<entity::calendars::Model>, MyError> {
+    let mut chain = vec![find_calendar(db, calendar_id).await?];
+    let mut visited = HashSet::from([calendar_id]);
+    while let Some(parent_id) = chain
+        .last()
+        .and_then(|calendar| calendar.parent_calendar_id)
+    {
+        if !visited.insert(parent_id) {
+            break;
+        }
+        chain.push(find_calendar(db, parent_id).await?);
+    }
+    Ok(chain)
+}
+
+async fn find_work_hours<C: ConnectionTrait>(
+    db: &C,
+    calendar_ids: Vec<i64>,
+) -> Result<Vec<WorkHours>, MyError> {
+    Ok(entity::calendar_work_hours::Entity::find()
+        .filter(entity::calendar_work_hours::Column::CalendarId.is_in(calendar_ids))
+        .order_by_asc(entity::calendar_work_hours::Column::Weekday)
+        .order_by_asc(entity::calendar_work_hours::Column::StartMinute)
+        .all(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching work hours: {}", e);
+            MyError::DatabaseError
+        })?
+        .into_iter()
+        .map(|record| record.into_model_work_hours())
+        .collect())
+}
+
+async fn find_calendar_exceptions<C: ConnectionTrait>(
+    db: &C,
+    calendar_ids: Vec<i64>,
+) -> Result<Vec<CalendarException>, MyError> {
+    Ok(entity::calendar_exceptions::Entity::find()
+        .filter(entity::calendar_exceptions::Column::CalendarId.is_in(calendar_ids))
+        .order_by_asc(entity::calendar_exceptions::Column::Date)
+        .all(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching calendar exceptions: {}", e);
+            MyError::DatabaseError
+        })?
+        .into_iter()
+        .map(|record| record.into_model_calendar_exception())
+        .collect())
+}
+
+/// Loads a calendar merged with everything it inherits, the project default calendar last.
+pub async fn find_working_calendar<C: ConnectionTrait>(
+    db: &C,
+    calendar_id: i64,
+) -> Result<WorkingCalendar, MyError> {
+    let mut chain = find_calendar_chain(db, calendar_id).await?;
+    let mut visited: HashSet<i64> = chain.iter().map(|calendar| calendar.calendar_id).collect();
+    let default_calendar_id = default_calendar_id(db).await?;
+    if !visited.contains(&default_calendar_id) {
+        for calendar in find_calendar_chain(db, default_calendar_id).await? {
+            if visited.insert(calendar.calendar_id) {
+                chain.push(calendar);
+            }
+        }
+    }
+    let calendar_ids: Vec<i64> = chain.iter().map(|calendar| calendar.calendar_id).collect();
+
+    let mut work_hours: HashMap<i64, Vec<WorkHours>> = HashMap::new();
+    for hours in find_work_hours(db, calendar_ids.clone()).await? {
+        work_hours.entry(hours.calendar_id).or_default().push(hours);
+    }
+    let mut exceptions: HashMap<i64, Vec<CalendarException>> = HashMap::new();
+    for exception in find_calendar_exceptions(db, calendar_ids.clone()).await? {
+        exceptions
+            .entry(exception.calendar_id)
+            .or_default()
+            .push(exception);
+    }
+    let layers: Vec<(Vec<WorkHours>, Vec<CalendarException>)> = calendar_ids
+        .iter()
+        .map(|calendar_id| {
+            (
+                work_hours.remove(calendar_id).unwrap_or_default(),
+                exceptions.remove(calendar_id).unwrap_or_default(),
+            )
+        })
+        .collect();
+
+    let timezone: Tz = chain[0].timezone.parse().map_err(|e| {
+        error!(
+            "Invalid timezone stored for calendar {}: {}",
+            calendar_id, e
+        );
+        MyError::DatabaseError
+    })?;
+
+    Ok(WorkingCalendar::inherit(timezone, &layers))
+}
+
+/// The project default calendar from config.
+pub async fn default_calendar_id<C: ConnectionTrait>(db: &C) -> Result<i64, MyError> {
+    let value = config_value(db, &communication::config::CALENDAR_ID_DEFAULT).await?;
+    value.parse().map_err(|e| {
+        error!("Invalid default calendar {}: {}", value, e);
+        MyError::DatabaseError
+    })
+}
+
+/// Calendar of a resource, or the default calendar when it has none.
+pub async fn resource_calendar_id<C: ConnectionTrait>(
+    db: &C,
+    resource: &entity::resources::Model,
+) -> Result<i64, MyError> {
+    match resource.calendar_id {
+        Some(calendar_id) => Ok(calendar_id),
+        None => default_calendar_id(db).await,
+    }
+}
+
+/// Merged calendars of the given resources keyed by resource id, loading each calendar once.
+pub async fn find_resource_calendars<C: ConnectionTrait>(
+    db: &C,
+    resources: &HashMap<i64, entity::resources::Model>,
+) -> Result<HashMap<i64, WorkingCalendar>, MyError> {
+    let default_calendar_id = default_calendar_id(db).await?;
+    let mut calendars: HashMap<i64, WorkingCalendar> = HashMap::new();
+    let mut resource_calendars = HashMap::new();
+    for resource in resources.values() {
+        let calendar_id = resource.calendar_id.unwrap_or(default_calendar_id);
+        let calendar = match calendars.get(&calendar_id) {
+            Some(calendar) => calendar.clone(),
+            None => {
+                let calendar = find_working_calendar(db, calendar_id).await?;
+                calendars.insert(calendar_id, calendar.clone());
+                calendar
+            }
+        };
+        resource_calendars.insert(resource.resource_id, calendar);
+    }
+    Ok(resource_calendars)
+}
+
+async fn validate_calendar<C: ConnectionTrait>(
+    db: &C,
+    calendar: &Calendar,
+    calendar_id: Option<i64>,
+) -> Result<(), MyError> {
+    if calendar.name.trim().is_empty() {
+        return Err(MyError::InvalidField(
+            "name",
+            "name must not be empty".to_string(),
+        ));
+    }
+    calendar.timezone.parse::<Tz>().map_err(|e| {
+        MyError::InvalidField("timezone", format!("timezone must be a timezone name: {e}"))
+    })?;
+
+    let Some(parent_id) = calendar.parent_calendar_id else {
+        return Ok(());
+    };
+    ensure_calendar_exists(db, parent_id, "parent_calendar_id").await?;
+    if let Some(calendar_id) = calendar_id
+        && find_calendar_chain(db, parent_id)
+            .await?
+            .iter()
+            .any(|ancestor| ancestor.calendar_id == calendar_id)
+    {
+        return Err(MyError::Conflict(format!(
+            "inheriting calendar {calendar_id} from calendar {parent_id} would create a cycle"
+        )));
+    }
+    Ok(())
+}
+
+pub async fn get_calendars(
+    db: web::Data<DatabaseConnection>,
+) -> Result<Encoded<Vec<Calendar>>, MyError> {
+    let calendars = entity::calendars::Entity::find()
+        .order_by_asc(entity::calendars::Column::Name)
+        .all(db.get_ref())
+        .await
+        .map_err(|e| {
+            error!("Database error fetching calendars: {}", e);
+            MyError::DatabaseError
+        })?
+        .into_iter()
+        .map(|record| record.into_model_calendar())
+        .collect();
+
+    Ok(Encoded::ok(calendars))
+}
+
+pub async fn create_calendar(
+    db: web::Data<DatabaseConnection>,
+    payload: Payload<Calendar>,
+) -> Result<Encoded<Calendar>, MyError> {
+    let calendar = payload.into_inner();
+    validate_calendar(db.get_ref(), &calendar, None).await?;
+
+    let inserted = entity::calendars::ActiveModel {
+        name: Set(calendar.name),
+        description: Set(calendar.description),
+        timezone: Set(calendar.timezone),
+        parent_calendar_id: Set(calendar.parent_calendar_id),
+        ..Default::default()
+    }
+    .insert(db.get_ref())
+    .await
+    .map_err(|e| {
+        error!("Database error inserting calendar: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::created(inserted.into_model_calendar()))
+}
+
+pub async fn update_calendar(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    payload: Payload<Calendar>,
+) -> Result<Encoded<Calendar>, MyError> {
+    let calendar_id = path.into_inner();
+    let calendar = payload.into_inner();
+
+    let record = find_calendar(db.get_ref(), calendar_id).await?;
+    validate_calendar(db.get_ref(), &calendar, Some(calendar_id)).await?;
+
+    let mut record = record.into_active_model();
+    record.name = Set(calendar.name);
+    record.description = Set(calendar.description);
+    record.timezone = Set(calendar.timezone);
+    record.parent_calendar_id = Set(calendar.parent_calendar_id);
+
+    let updated = record.update(db.get_ref()).await.map_err(|e| {
+        error!("Database error updating calendar: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::ok(updated.into_model_calendar()))
+}
+
+/// A calendar can only be deleted when it is neither the default, nor used by a resource, nor
+/// inherited by another calendar.
+async fn ensure_calendar_unused<C: ConnectionTrait>(
+    db: &C,
+    calendar_id: i64,
+) -> Result<(), MyError> {
+    if default_calendar_id(db).await? == calendar_id {
+        return Err(MyError::Conflict(format!(
+            "calendar {calendar_id} is the default calendar"
+        )));
+    }
+
+    let resources = entity::resources::Entity::find()
+        .filter(entity::resources::Column::CalendarId.eq(calendar_id))
+        .count(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching resources: {}", e);
+            MyError::DatabaseError
+        })?;
+    if resources > 0 {
+        return Err(MyError::Conflict(format!(
+            "calendar {calendar_id} is used by {resources} resources"
+        )));
+    }
+
+    let children = entity::calendars::Entity::find()
+        .filter(entity::calendars::Column::ParentCalendarId.eq(calendar_id))
+        .count(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching calendars: {}", e);
+            MyError::DatabaseError
+        })?;
+    if children > 0 {
+        return Err(MyError::Conflict(format!(
+            "calendar {calendar_id} is inherited by {children} calendars"
+        )));
+    }
+    Ok(())
+}
+
+/// Deletes a calendar together with its work hours and exceptions.
+pub async fn delete_calendar(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, MyError> {
+    let calendar_id = path.into_inner();
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    find_calendar(&txn, calendar_id).await?;
+    ensure_calendar_unused(&txn, calendar_id).await?;
+
+    entity::calendar_work_hours::Entity::delete_many()
+        .filter(entity::calendar_work_hours::Column::CalendarId.eq(calendar_id))
+        .exec(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error deleting work hours: {}", e);
+            MyError::DatabaseError
+        })?;
+    entity::calendar_exceptions::Entity::delete_many()
+        .filter(entity::calendar_exceptions::Column::CalendarId.eq(calendar_id))
+        .exec(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error deleting calendar exceptions: {}", e);
+            MyError::DatabaseError
+        })?;
+    entity::calendars::Entity::delete_by_id(calendar_id)
+        .exec(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error deleting calendar: {}", e);
+            MyError::DatabaseError
+        })?;
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// The calendar's own weekly pattern; empty when it inherits the pattern of its parent.
+pub async fn get_work_hours(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+) -> Result<Encoded<Vec<WorkHours>>, MyError> {
+    let calendar_id = path.into_inner();
+    find_calendar(db.get_ref(), calendar_id).await?;
+
+    Ok(Encoded::ok(
+        find_work_hours(db.get_ref(), vec![calendar_id]).await?,
+    ))
+}
+
+fn validate_period(start: u16, finish: u16, field: &'static str) -> Result<(), MyError> {
+    if start >= finish || finish > DAY_MINUTES {
+        return Err(MyError::InvalidField(
+            field,
+            format!("working period {start}..{finish} must lie within a day and not be empty"),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_work_hours(work_hours: &[WorkHours]) -> Result<(), MyError> {
+    let mut week: [Vec<(u16, u16)>; 7] = Default::default();
+    for hours in work_hours {
+        let periods = week.get_mut(hours.weekday as usize).ok_or_else(|| {
+            MyError::InvalidField(
+                "weekday",
+                format!("weekday {} must be 0 (Monday) to 6 (Sunday)", hours.weekday),
+            )
+        })?;
+        validate_period(hours.start_minute, hours.finish_minute, "finish_minute")?;
+        periods.push((hours.start_minute, hours.finish_minute));
+    }
+    for (weekday, periods) in week.iter_mut().enumerate() {
+        periods.sort_unstable();
+        if periods.windows(2).any(|pair| pair[0].1 > pair[1].0) {
+            return Err(MyError::InvalidField(
+                "start_minute",
+                format!("working periods of weekday {weekday} overlap"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the calendar's weekly pattern; an empty week makes it inherit the parent's again.
+pub async fn replace_work_hours(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    payload: Payload<Vec<WorkHours>>,
+) -> Result<Encoded<Vec<WorkHours>>, MyError> {
+    let calendar_id = path.into_inner();
+    let work_hours = payload.into_inner();
+    validate_work_hours(&work_hours)?;
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    find_calendar(&txn, calendar_id).await?;
+
+    entity::calendar_work_hours::Entity::delete_many()
+        .filter(entity::calendar_work_hours::Column::CalendarId.eq(calendar_id))
+        .exec(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error deleting work hours: {}", e);
+            MyError::DatabaseError
+        })?;
+    if !work_hours.is_empty() {
+        entity::calendar_work_hours::Entity::insert_many(work_hours.iter().map(|hours| {
+            entity::calendar_work_hours::ActiveModel {
+                calendar_id: Set(calendar_id),
+                weekday: Set(hours.weekday as i16),
+                start_minute: Set(hours.start_minute as i16),
+                finish_minute: Set(hours.finish_minute as i16),
+                ..Default::default()
+            }
+        }))
+        .exec(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error inserting work hours: {}", e);
+            MyError::DatabaseError
+        })?;
+    }
+
+    let stored = find_work_hours(&txn, vec![calendar_id]).await?;
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::ok(stored))
+}
+
+/// The calendar's own holidays and exceptions, without inherited ones.
+pub async fn get_calendar_exceptions(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+) -> Result<Encoded<Vec<CalendarException>>, MyError> {
+    let calendar_id = path.into_inner();
+    find_calendar(db.get_ref(), calendar_id).await?;
+
+    Ok(Encoded::ok(
+        find_calendar_exceptions(db.get_ref(), vec![calendar_id]).await?,
+    ))
+}
+
+async fn find_calendar_exception<C: ConnectionTrait>(
+    db: &C,
+    calendar_exception_id: i64,
+) -> Result<entity::calendar_exceptions::Model, MyError> {
+    entity::calendar_exceptions::Entity::find_by_id(calendar_exception_id)
+        .one(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching calendar exception: {}", e);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("calendar exception {calendar_exception_id}")))
+}
+
+/// Checks the hours of the exception and that its calendar has no other exception on that date.
+async fn validate_calendar_exception<C: ConnectionTrait>(
+    db: &C,
+    exception: &CalendarException,
+    calendar_exception_id: Option<i64>,
+) -> Result<(), MyError> {
+    if exception.name.trim().is_empty() {
+        return Err(MyError::InvalidField(
+            "name",
+            "name must not be empty".to_string(),
+        ));
+    }
+    match (exception.start_minute, exception.finish_minute) {
+        (Some(_), Some(_)) if exception.kind == CalendarExceptionKind::Holiday => {
+            return Err(MyError::InvalidField(
+                "kind",
+                "a holiday has no working hours".to_string(),
+            ));
+        }
+        (Some(start), Some(finish)) => validate_period(start, finish, "finish_minute")?,
+        (None, None) => {}
+        _ => {
+            return Err(MyError::InvalidField(
+                "finish_minute",
+                "start_minute and finish_minute must be given together".to_string(),
+            ));
+        }
+    }
+
+    let mut same_date = entity::calendar_exceptions::Entity::find()
+        .filter(entity::calendar_exceptions::Column::CalendarId.eq(exception.calendar_id))
+        .filter(entity::calendar_exceptions::Column::Date.eq(exception.date.naive()));
+    if let Some(calendar_exception_id) = calendar_exception_id {
+        same_date = same_date.filter(
+            entity::calendar_exceptions::Column::CalendarExceptionId.ne(calendar_exception_id),
+        );
+    }
+    let same_date = same_date.count(db).await.map_err(|e| {
+        error!("Database error fetching calendar exceptions: {}", e);
+        MyError::DatabaseError
+    })?;
+    if same_date > 0 {
+        return Err(MyError::Conflict(format!(
+            "calendar {} already has an exception on {}",
+            exception.calendar_id, exception.date
+        )));
+    }
+    Ok(())
+}
+
+pub async fn create_calendar_exception(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    payload: Payload<CalendarException>,
+) -> Result<Encoded<CalendarException>, MyError> {
+    let mut exception = payload.into_inner();
+    exception.calendar_id = path.into_inner();
+
+    find_calendar(db.get_ref(), exception.calendar_id).await?;
+    validate_calendar_exception(db.get_ref(), &exception, None).await?;
+
+    let inserted = entity::calendar_exceptions::ActiveModel {
+        calendar_id: Set(exception.calendar_id),
+        date: Set(exception.date.naive()),
+        name: Set(exception.name),
+        kind: Set(exception.kind.into_entity_calendar_exception_kind()),
+        start_minute: Set(exception.start_minute.map(|minute| minute as i16)),
+        finish_minute: Set(exception.finish_minute.map(|minute| minute as i16)),
+        ..Default::default()
+    }
+    .insert(db.get_ref())
+    .await
+    .map_err(|e| {
+        error!("Database error inserting calendar exception: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::created(inserted.into_model_calendar_exception()))
+}
+
+/// Moves an exception to another date or changes its hours; it stays with its calendar.
+pub async fn update_calendar_exception(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    payload: Payload<CalendarException>,
+) -> Result<Encoded<CalendarException>, MyError> {
+    let calendar_exception_id = path.into_inner();
+    let mut exception = payload.into_inner();
+
+    let record = find_calendar_exception(db.get_ref(), calendar_exception_id).await?;
+    exception.calendar_id = record.calendar_id;
+    validate_calendar_exception(db.get_ref(), &exception, Some(calendar_exception_id)).await?;
+
+    let mut record = record.into_active_model();
+    record.date = Set(exception.date.naive());
+    record.name = Set(exception.name);
+    record.kind = Set(exception.kind.into_entity_calendar_exception_kind());
+    record.start_minute = Set(exception.start_minute.map(|minute| minute as i16));
+    record.finish_minute = Set(exception.finish_minute.map(|minute| minute as i16));
+
+    let updated = record.update(db.get_ref()).await.map_err(|e| {
+        error!("Database error updating calendar exception: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::ok(updated.into_model_calendar_exception()))
+}
+
+pub async fn delete_calendar_exception(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, MyError> {
+    let calendar_exception_id = path.into_inner();
+    find_calendar_exception(db.get_ref(), calendar_exception_id).await?;
+
+    entity::calendar_exceptions::Entity::delete_by_id(calendar_exception_id)
+        .exec(db.get_ref())
+        .await
+        .map_err(|e| {
+            error!("Database error deleting calendar exception: {}", e);
+            MyError::DatabaseError
+        })?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+async fn working_time<C: ConnectionTrait>(
+    db: &C,
+    calendar_id: i64,
+    query: &HashMap<String, String>,
+) -> Result<WorkingTime, MyError> {
+    let from: DateTime<Utc> = crate::query_param(query, "from")?;
+    let to: DateTime<Utc> = crate::query_param(query, "to")?;
+    if (to - from).num_seconds().abs() > MAX_SPAN {
+        return Err(MyError::InvalidField(
+            "to",
+            "to must be within ten years of from".to_string(),
+        ));
+    }
+
+    let calendar = find_working_calendar(db, calendar_id).await?;
+    let working = calendar.working_time(from, to);
+
+    Ok(WorkingTime {
+        calendar_id,
+        from: from.into(),
+        to: to.into(),
+        working_seconds: working.num_seconds(),
+        working_hours: working.num_seconds() as f64 / 3600.,
+    })
+}
+
+/// Working time of a calendar between the RFC 3339 instants `from` and `to`.
+pub async fn get_calendar_working_time(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<Encoded<WorkingTime>, MyError> {
+    Ok(Encoded::ok(
+        working_time(db.get_ref(), path.into_inner(), &query).await?,
+    ))
+}
+
+/// Working time of a resource between `from` and `to`, by its own calendar or the default one.
+pub async fn get_resource_working_time(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<Encoded<WorkingTime>, MyError> {
+    let resource = find_resource(db.get_ref(), path.into_inner()).await?;
+    let calendar_id = resource_calendar_id(db.get_ref(), &resource).await?;
+
+    Ok(Encoded::ok(
+        working_time(db.get_ref(), calendar_id, &query).await?,
+    ))
+}
@@ -0,0 +1,200 @@
+use actix_web::{Result, web};
+use communication::config::{BASELINE_ID_CURRENT, ConfigEntry, ConfigKey, ConfigValue, REGISTRY};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    EntityTrait, IntoActiveModel, QueryFilter, TransactionTrait,
+};
+use std::collections::HashMap;
+use tracing::{error, info};
+
+use crate::MyError;
+use crate::calendars::ensure_calendar_exists;
+use crate::response::{Encoded, Payload};
+
+pub trait IntoModelConfig {
+    fn into_model_config(self) -> communication::config::Config;
+}
+
+impl IntoModelConfig for entity::config::Model {
+    fn into_model_config(self) -> communication::config::Config {
+        communication::config::Config {
+            config_id: self.config_id,
+            config_key: self.config_key,
+            config_value: self.config_value,
+            description: self.description,
+        }
+    }
+}
+
+/// Stored value of a known config key, or its default when it has not been set.
+pub async fn config_value<C: ConnectionTrait>(
+    db: &C,
+    config_key: &ConfigKey,
+) -> Result<String, MyError> {
+    let record = entity::config::Entity::find()
+        .filter(entity::config::Column::ConfigKey.eq(config_key.key))
+        .one(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching config: {}", e);
+            MyError::DatabaseError
+        })?;
+
+    Ok(record
+        .and_then(|record| record.config_value)
+        .unwrap_or_else(|| config_key.default.to_string()))
+}
+
+/// Validates `value` against the registry and stores it, creating the row if it does not exist yet.
+/// Only an approved or locked baseline may become the current one.
+pub async fn set_config_value<C: ConnectionTrait>(
+    db: &C,
+    config_key: &ConfigKey,
+    value: String,
+) -> Result<entity::config::Model, MyError> {
+    match config_key
+        .validate(&value)
+        .map_err(|reason| MyError::InvalidField("config_value", reason))?
+    {
+        ConfigValue::BaselineId(baseline_id) => {
+            let baseline = entity::baselines::Entity::find_by_id(baseline_id)
+                .one(db)
+                .await
+                .map_err(|e| {
+                    error!("Database error fetching baseline: {}", e);
+                    MyError::DatabaseError
+                })?
+                .ok_or_else(|| {
+                    MyError::InvalidField(
+                        "config_value",
+                        format!("baseline {baseline_id} does not exist"),
+                    )
+                })?;
+            if config_key.key == BASELINE_ID_CURRENT.key
+                && baseline.status == entity::baselines::BaselineStatus::Draft
+            {
+                return Err(MyError::Conflict(format!(
+                    "baseline {baseline_id} has to be approved before it becomes current"
+                )));
+            }
+        }
+        ConfigValue::CalendarId(calendar_id) => {
+            ensure_calendar_exists(db, calendar_id, "config_value").await?;
+        }
+        ConfigValue::Timezone(_) => {}
+    }
+
+    let record = entity::config::Entity::find()
+        .filter(entity::config::Column::ConfigKey.eq(config_key.key))
+        .one(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching config: {}", e);
+            MyError::DatabaseError
+        })?;
+
+    let stored = match record {
+        Some(record) => {
+            let mut record = record.into_active_model();
+            record.config_value = Set(Some(value));
+            record.update(db).await
+        }
+        None => {
+            entity::config::ActiveModel {
+                config_key: Set(config_key.key.to_string()),
+                config_value: Set(Some(value)),
+                description: Set(Some(config_key.description.to_string())),
+                ..Default::default()
+            }
+            .insert(db)
+            .await
+        }
+    }
+    .map_err(|e| {
+        error!("Database error storing config: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    info!(
+        "Config {} set to {:?}",
+        stored.config_key, stored.config_value
+    );
+
+    Ok(stored)
+}
+
+/// Every key of the registry with its type, default and stored value, followed by stored keys the
+/// registry does not know.
+pub async fn get_config(
+    db: web::Data<DatabaseConnection>,
+) -> Result<Encoded<Vec<ConfigEntry>>, MyError> {
+    let mut stored: HashMap<String, entity::config::Model> = entity::config::Entity::find()
+        .all(db.get_ref())
+        .await
+        .map_err(|e| {
+            error!("Database error fetching config: {}", e);
+            MyError::DatabaseError
+        })?
+        .into_iter()
+        .map(|record| (record.config_key.clone(), record))
+        .collect();
+
+    let mut entries: Vec<ConfigEntry> = REGISTRY
+        .iter()
+        .map(|config_key| {
+            let record = stored.remove(config_key.key);
+            ConfigEntry {
+                config_key: config_key.key.to_string(),
+                value_type: Some(config_key.value_type),
+                default_value: Some(config_key.default.to_string()),
+                config_value: record.and_then(|record| record.config_value),
+                description: Some(config_key.description.to_string()),
+            }
+        })
+        .collect();
+    let mut unknown: Vec<entity::config::Model> = stored.into_values().collect();
+    unknown.sort_by(|a, b| a.config_key.cmp(&b.config_key));
+    entries.extend(unknown.into_iter().map(|record| ConfigEntry {
+        config_key: record.config_key,
+        value_type: None,
+        default_value: None,
+        config_value: record.config_value,
+        description: record.description,
+    }));
+
+    Ok(Encoded::ok(entries))
+}
+
+/// Sets the value of a known config key; unknown keys and invalid values are rejected.
+pub async fn update_config(
+    db: web::Data<DatabaseConnection>,
+    payload: Payload<communication::config::Config>,
+) -> Result<Encoded<communication::config::Config>, MyError> {
+    let config = payload.into_inner();
+    let config_key = ConfigKey::find(&config.config_key).ok_or_else(|| {
+        MyError::InvalidField(
+            "config_key",
+            format!("unknown config key: {}", config.config_key),
+        )
+    })?;
+    let value = config.config_value.ok_or_else(|| {
+        MyError::InvalidField(
+            "config_value",
+            format!("{} must have a value", config_key.key),
+        )
+    })?;
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let stored = set_config_value(&txn, config_key, value).await?;
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::ok(stored.into_model_config()))
+}
@@ -0,0 +1,378 @@
+use actix_web::{Result, web};
+use chrono::{DateTime, Datelike, Months, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use communication::baselines::{ResourceAllocation, TaskBaseline};
+use communication::costs::{CostReport, MonthCost, ResourceCost, TaskCost};
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+use std::collections::{BTreeMap, HashMap};
+use tracing::error;
+
+use crate::MyError;
+use crate::baselines::ensure_baseline_exists;
+use crate::calendars::find_resource_calendars;
+use crate::config::config_value;
+use crate::exchange_rates::ReportingCurrency;
+use crate::resources::{find_resource_allocations, find_resources_by_id};
+use crate::response::Encoded;
+use crate::tasks::find_task_baselines;
+use crate::working_time::WorkingCalendar;
+
+/// Year and month, 1 for January.
+type Month = (i32, u32);
+
+fn first_day((year, month): Month) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, 1).unwrap_or_default()
+}
+
+/// Length of one billing period in seconds; years and months take their Gregorian average length.
+fn billing_period_secs(frequency: &entity::resources::Frequency) -> f64 {
+    const DAY: f64 = 86_400.;
+    match frequency {
+        entity::resources::Frequency::Yearly => 365.2425 * DAY,
+        entity::resources::Frequency::Monthly => 365.2425 * DAY / 12.,
+        entity::resources::Frequency::Weekly => 7. * DAY,
+        entity::resources::Frequency::Daily => DAY,
+        entity::resources::Frequency::Hourly => 3_600.,
+        entity::resources::Frequency::Minutely => 60.,
+        entity::resources::Frequency::Secondly => 1.,
+    }
+}
+
+/// Seconds billed between two instants: the working time of the resource's calendar when it is
+/// billed by the hour or finer, the elapsed time when it is billed by the day or longer.
+fn billed_secs(
+    frequency: &entity::resources::Frequency,
+    calendar: &WorkingCalendar,
+    start: DateTime<Utc>,
+    finish: DateTime<Utc>,
+) -> f64 {
+    match frequency {
+        entity::resources::Frequency::Hourly
+        | entity::resources::Frequency::Minutely
+        | entity::resources::Frequency::Secondly => {
+            calendar.working_time(start, finish).num_seconds() as f64
+        }
+        _ => (finish - start).num_seconds().max(0) as f64,
+    }
+}
+
+/// Planned cost, in the resource's `cost_currency`, of allocating `capacity_allocated` of it
+/// between `start` and `finish`.
+///
+/// `cost` is charged per unit of capacity for every `billing_interval` of `billing_frequency`,
+/// pro rata for partial periods; a resource without a billing frequency costs `cost` once.
+pub fn allocation_cost(
+    resource: &entity::resources::Model,
+    capacity_allocated: f64,
+    start: DateTime<Utc>,
+    finish: DateTime<Utc>,
+    calendar: &WorkingCalendar,
+) -> f64 {
+    let Some(cost) = resource.cost else {
+        return 0.;
+    };
+    let periods = match &resource.billing_frequency {
+        Some(frequency) => {
+            let period =
+                billing_period_secs(frequency) * f64::from(resource.billing_interval.unwrap_or(1));
+            billed_secs(frequency, calendar, start, finish) / period
+        }
+        None => 1.,
+    };
+    cost * capacity_allocated * periods
+}
+
+/// Splits `start..finish` at the first of every month in `timezone`.
+fn split_by_month(
+    start: DateTime<Utc>,
+    finish: DateTime<Utc>,
+    timezone: Tz,
+) -> Vec<(Month, DateTime<Utc>, DateTime<Utc>)> {
+    let mut parts = Vec::new();
+    let mut from = start;
+    while from < finish {
+        let local = from.with_timezone(&timezone).date_naive();
+        let to = NaiveDate::from_ymd_opt(local.year(), local.month(), 1)
+            .and_then(|first| first.checked_add_months(Months::new(1)))
+            .and_then(|next| {
+                timezone
+                    .from_local_datetime(&next.and_time(Default::default()))
+                    .earliest()
+            })
+            .map(|next| next.with_timezone(&Utc).min(finish))
+            .unwrap_or(finish);
+        parts.push(((local.year(), local.month()), from, to));
+        from = to;
+    }
+    parts
+}
+
+/// Part of an allocation falling into one month: the month, the instants it covers and its cost.
+pub type MonthPart = (Month, DateTime<Utc>, DateTime<Utc>, f64);
+
+/// Splits the cost of an allocation at the months of `timezone` its task spans. A one-off cost
+/// falls into the month the task starts in and covers only its start.
+pub fn split_cost(
+    resource: &entity::resources::Model,
+    capacity_allocated: f64,
+    start: DateTime<Utc>,
+    finish: DateTime<Utc>,
+    calendar: &WorkingCalendar,
+    timezone: Tz,
+) -> Vec<MonthPart> {
+    if resource.billing_frequency.is_none() {
+        let local = start.with_timezone(&timezone);
+        return vec![(
+            (local.year(), local.month()),
+            start,
+            start,
+            allocation_cost(resource, capacity_allocated, start, finish, calendar),
+        )];
+    }
+    split_by_month(start, finish, timezone)
+        .into_iter()
+        .map(|(month, from, to)| {
+            (
+                month,
+                from,
+                to,
+                allocation_cost(resource, capacity_allocated, from, to, calendar),
+            )
+        })
+        .collect()
+}
+
+/// Spreads the cost of an allocation over the months of `timezone` its task spans. A one-off cost
+/// falls into the month the task starts in.
+pub fn spread_cost(
+    resource: &entity::resources::Model,
+    capacity_allocated: f64,
+    start: DateTime<Utc>,
+    finish: DateTime<Utc>,
+    calendar: &WorkingCalendar,
+    timezone: Tz,
+) -> Vec<(Month, f64)> {
+    split_cost(
+        resource,
+        capacity_allocated,
+        start,
+        finish,
+        calendar,
+        timezone,
+    )
+    .into_iter()
+    .map(|(month, _, _, cost)| (month, cost))
+    .collect()
+}
+
+/// `amount` of `currency` planned in `month`, as it is to be reported. Planned costs are converted
+/// at the rate of the first day of their month, by every report alike so their totals agree.
+pub fn report_month(
+    reporting: &ReportingCurrency,
+    currency: &str,
+    month: Month,
+    amount: f64,
+) -> Result<(String, f64), MyError> {
+    reporting.report(currency, amount, first_day(month))
+}
+
+fn add(cost: &mut BTreeMap<String, f64>, currency: &str, amount: f64) {
+    *cost.entry(currency.to_string()).or_insert(0.) += amount;
+}
+
+/// Planned cost of every allocation of a baseline, summed up by task, resource and month. Amounts
+/// are converted to the reporting currency at the rate of the first day of their month.
+///
+/// Allocations whose task, resource or calendar is missing are left out.
+pub fn cost_report(
+    baseline_id: i64,
+    timezone: Tz,
+    tasks: &[TaskBaseline],
+    allocations: &[ResourceAllocation],
+    resources: &HashMap<i64, entity::resources::Model>,
+    calendars: &HashMap<i64, WorkingCalendar>,
+    reporting: &ReportingCurrency,
+) -> Result<CostReport, MyError> {
+    let tasks: HashMap<i64, &TaskBaseline> =
+        tasks.iter().map(|task| (task.task_id, task)).collect();
+
+    let mut total = BTreeMap::new();
+    let mut by_task: BTreeMap<i64, TaskCost> = BTreeMap::new();
+    let mut by_resource: BTreeMap<i64, ResourceCost> = BTreeMap::new();
+    let mut by_month: BTreeMap<Month, BTreeMap<String, f64>> = BTreeMap::new();
+    for allocation in allocations {
+        let (Some(task), Some(resource), Some(calendar)) = (
+            tasks.get(&allocation.task_id),
+            resources.get(&allocation.resource_id),
+            calendars.get(&allocation.resource_id),
+        ) else {
+            continue;
+        };
+        let task_cost = by_task.entry(task.task_id).or_insert_with(|| TaskCost {
+            task_id: task.task_id,
+            task_summary: task.task_summary.clone(),
+            cost: BTreeMap::new(),
+        });
+        let resource_cost =
+            by_resource
+                .entry(resource.resource_id)
+                .or_insert_with(|| ResourceCost {
+                    resource_id: resource.resource_id,
+                    resource_summary: resource.summary.clone(),
+                    cost: BTreeMap::new(),
+                });
+
+        for (month, amount) in spread_cost(
+            resource,
+            allocation.capacity_allocated.unwrap_or(0.),
+            task.start.utc(),
+            task.finish.utc(),
+            calendar,
+            timezone,
+        ) {
+            let (currency, amount) =
+                report_month(reporting, &resource.cost_currency, month, amount)?;
+            add(&mut total, &currency, amount);
+            add(&mut task_cost.cost, &currency, amount);
+            add(&mut resource_cost.cost, &currency, amount);
+            add(by_month.entry(month).or_default(), &currency, amount);
+        }
+    }
+
+    Ok(CostReport {
+        baseline_id,
+        timezone: timezone.name().to_string(),
+        currency: reporting.currency(),
+        total,
+        tasks: by_task.into_values().collect(),
+        resources: by_resource.into_values().collect(),
+        months: by_month
+            .into_iter()
+            .map(|((year, month), cost)| MonthCost { year, month, cost })
+            .collect(),
+    })
+}
+
+/// The project timezone from config.
+pub async fn default_timezone<C: ConnectionTrait>(db: &C) -> Result<Tz, MyError> {
+    let value = config_value(db, &communication::config::TIMEZONE_DEFAULT).await?;
+    value.parse().map_err(|e| {
+        error!("Invalid default timezone {}: {}", value, e);
+        MyError::DatabaseError
+    })
+}
+
+/// Planned cost of a baseline per task, per resource and per month of the project timezone,
+/// optionally converted to one reporting `currency`.
+pub async fn get_costs(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<Encoded<CostReport>, MyError> {
+    let baseline_id = path.into_inner();
+    let reporting = ReportingCurrency::from_query(db.get_ref(), &query).await?;
+    ensure_baseline_exists(db.get_ref(), baseline_id).await?;
+
+    let tasks = find_task_baselines(db.get_ref(), baseline_id).await?;
+    let allocations = find_resource_allocations(db.get_ref(), baseline_id).await?;
+    let resources = find_resources_by_id(
+        db.get_ref(),
+        allocations.iter().map(|allocation| allocation.resource_id),
+    )
+    .await?;
+    let calendars = find_resource_calendars(db.get_ref(), &resources).await?;
+    let timezone = default_timezone(db.get_ref()).await?;
+
+    Ok(Encoded::ok(cost_report(
+        baseline_id,
+        timezone,
+        &tasks,
+        &allocations,
+        &resources,
+        &calendars,
+        &reporting,
+    )?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{at, office_calendar, resource};
+    use entity::resources::Frequency;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-6, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn months_split_at_local_midnight() {
+        // Berlin enters February at 23:00 UTC.
+        let parts = split_by_month(
+            at(30 * 24 + 12).and_utc(),
+            at(31 * 24 + 12).and_utc(),
+            chrono_tz::Europe::Berlin,
+        );
+        let months: Vec<(Month, i64)> = parts
+            .iter()
+            .map(|(month, from, to)| (*month, (*to - *from).num_hours()))
+            .collect();
+        assert_eq!(months, vec![((2025, 1), 11), ((2025, 2), 13)]);
+    }
+
+    #[test]
+    fn monthly_billing_is_spread_pro_rata_over_the_months() {
+        let resource = resource(1, 3000., Some(Frequency::Monthly));
+        // 16 January to 16 February: 16 days in January, 15 in February.
+        let (start, finish) = (at(15 * 24).and_utc(), at(46 * 24).and_utc());
+        let month = 365.2425 * 86_400. / 12.;
+
+        let spread = spread_cost(
+            &resource,
+            0.5,
+            start,
+            finish,
+            &office_calendar(),
+            chrono_tz::UTC,
+        );
+        assert_eq!(spread.len(), 2);
+        assert_eq!((spread[0].0, spread[1].0), ((2025, 1), (2025, 2)));
+        assert_close(spread[0].1, 1500. * 16. * 86_400. / month);
+        assert_close(spread[1].1, 1500. * 15. * 86_400. / month);
+        assert_close(
+            spread[0].1 + spread[1].1,
+            allocation_cost(&resource, 0.5, start, finish, &office_calendar()),
+        );
+    }
+
+    #[test]
+    fn hourly_billing_counts_working_time_only() {
+        let mut resource = resource(1, 100., Some(Frequency::Hourly));
+        // Friday noon to Monday noon holds 8 office hours.
+        let (start, finish) = (at(2 * 24 + 12).and_utc(), at(5 * 24 + 12).and_utc());
+        let calendar = office_calendar();
+        assert_close(
+            allocation_cost(&resource, 0.5, start, finish, &calendar),
+            400.,
+        );
+
+        resource.billing_interval = Some(2);
+        assert_close(
+            allocation_cost(&resource, 0.5, start, finish, &calendar),
+            200.,
+        );
+    }
+
+    #[test]
+    fn one_off_costs_fall_into_the_first_month() {
+        let resource = resource(1, 250., None);
+        let spread = spread_cost(
+            &resource,
+            2.,
+            at(30 * 24).and_utc(),
+            at(40 * 24).and_utc(),
+            &office_calendar(),
+            chrono_tz::UTC,
+        );
+        assert_eq!(spread, vec![((2025, 1), 500.)]);
+    }
+}
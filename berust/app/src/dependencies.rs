@@ -0,0 +1,378 @@
+use actix_web::{HttpResponse, Result, web};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter, TransactionTrait,
+};
+use std::collections::{HashMap, HashSet};
+use tracing::error;
+
+use crate::MyError;
+use crate::baselines::ensure_baseline_editable;
+use crate::response::{Encoded, Payload};
+
+/// Longest lead or lag a link may have, in seconds: ten years.
+const MAX_LAG: i64 = 10 * 366 * 24 * 60 * 60;
+
+pub trait IntoModelDependencyType {
+    fn into_model_dependency_type(self) -> communication::baselines::DependencyType;
+}
+
+impl IntoModelDependencyType for entity::task_dependencies::DependencyType {
+    fn into_model_dependency_type(self) -> communication::baselines::DependencyType {
+        match self {
+            entity::task_dependencies::DependencyType::FinishToStart => {
+                communication::baselines::DependencyType::FinishToStart
+            }
+            entity::task_dependencies::DependencyType::StartToStart => {
+                communication::baselines::DependencyType::StartToStart
+            }
+            entity::task_dependencies::DependencyType::FinishToFinish => {
+                communication::baselines::DependencyType::FinishToFinish
+            }
+            entity::task_dependencies::DependencyType::StartToFinish => {
+                communication::baselines::DependencyType::StartToFinish
+            }
+        }
+    }
+}
+
+pub trait IntoEntityDependencyType {
+    fn into_entity_dependency_type(self) -> entity::task_dependencies::DependencyType;
+}
+
+impl IntoEntityDependencyType for communication::baselines::DependencyType {
+    fn into_entity_dependency_type(self) -> entity::task_dependencies::DependencyType {
+        match self {
+            communication::baselines::DependencyType::FinishToStart => {
+                entity::task_dependencies::DependencyType::FinishToStart
+            }
+            communication::baselines::DependencyType::StartToStart => {
+                entity::task_dependencies::DependencyType::StartToStart
+            }
+            communication::baselines::DependencyType::FinishToFinish => {
+                entity::task_dependencies::DependencyType::FinishToFinish
+            }
+            communication::baselines::DependencyType::StartToFinish => {
+                entity::task_dependencies::DependencyType::StartToFinish
+            }
+        }
+    }
+}
+
+pub trait IntoModelTaskDependency {
+    fn into_model_task_dependency(self) -> communication::baselines::TaskDependency;
+}
+
+impl IntoModelTaskDependency for entity::task_dependencies::Model {
+    fn into_model_task_dependency(self) -> communication::baselines::TaskDependency {
+        communication::baselines::TaskDependency {
+            task_dependency_id: self.task_dependency_id,
+            baseline_id: self.baseline_id,
+            predecessor_id: self.predecessor_id,
+            successor_id: self.successor_id,
+            dependency_type: self.dependency_type.into_model_dependency_type(),
+            lag: self.lag,
+        }
+    }
+}
+
+/// Reads all task dependencies of a baseline.
+pub async fn find_task_dependencies<C: ConnectionTrait>(
+    db: &C,
+    baseline_id: i64,
+) -> Result<Vec<communication::baselines::TaskDependency>, MyError> {
+    Ok(entity::task_dependencies::Entity::find()
+        .filter(entity::task_dependencies::Column::BaselineId.eq(baseline_id))
+        .all(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task dependencies: {}", e);
+            MyError::DatabaseError
+        })?
+        .into_iter()
+        .map(|record| record.into_model_task_dependency())
+        .collect())
+}
+
+async fn find_task_dependency<C: ConnectionTrait>(
+    db: &C,
+    task_dependency_id: i64,
+) -> Result<entity::task_dependencies::Model, MyError> {
+    entity::task_dependencies::Entity::find_by_id(task_dependency_id)
+        .one(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task dependency: {}", e);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("task dependency {task_dependency_id}")))
+}
+
+pub async fn get_task_dependencies(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<Encoded<Vec<communication::baselines::TaskDependency>>, MyError> {
+    let baseline_id: i64 = crate::query_param(&query, "baseline_id")?;
+
+    let task_dependencies = find_task_dependencies(db.get_ref(), baseline_id).await?;
+
+    Ok(Encoded::ok(task_dependencies))
+}
+
+/// Whether linking `predecessor` to `successor` closes a cycle, i.e. `successor` already leads to
+/// `predecessor` through `links` given as (predecessor, successor) pairs.
+pub fn creates_cycle(links: &[(i64, i64)], predecessor: i64, successor: i64) -> bool {
+    let mut successors: HashMap<i64, Vec<i64>> = HashMap::new();
+    for (from, to) in links {
+        successors.entry(*from).or_default().push(*to);
+    }
+
+    let mut visited = HashSet::new();
+    let mut stack = vec![successor];
+    while let Some(task_id) = stack.pop() {
+        if task_id == predecessor {
+            return true;
+        }
+        if visited.insert(task_id) {
+            stack.extend(successors.get(&task_id).into_iter().flatten());
+        }
+    }
+    false
+}
+
+async fn ensure_planned<C: ConnectionTrait>(
+    db: &C,
+    baseline_id: i64,
+    task_id: i64,
+    field: &'static str,
+) -> Result<(), MyError> {
+    let planned = entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::BaselineId.eq(baseline_id))
+        .filter(entity::tasks_baselines::Column::TaskId.eq(task_id))
+        .count(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task baselines: {}", e);
+            MyError::DatabaseError
+        })?;
+    if planned == 0 {
+        return Err(MyError::InvalidField(
+            field,
+            format!("task {task_id} is not part of baseline {baseline_id}"),
+        ));
+    }
+
+    // Summary tasks span their subtasks, so only the subtasks can be linked.
+    let subtasks = entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::BaselineId.eq(baseline_id))
+        .filter(entity::tasks_baselines::Column::Parent.eq(task_id))
+        .filter(entity::tasks_baselines::Column::TaskId.ne(task_id))
+        .count(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching subtasks: {}", e);
+            MyError::DatabaseError
+        })?;
+    if subtasks > 0 {
+        return Err(MyError::InvalidField(
+            field,
+            format!("task {task_id} is a summary task in baseline {baseline_id}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the lag is in range, that both tasks are planned in the baseline without subtasks,
+/// are not yet linked and that the link does not close a cycle among the other links of the
+/// baseline.
+async fn validate_task_dependency<C: ConnectionTrait>(
+    db: &C,
+    dependency: &communication::baselines::TaskDependency,
+    task_dependency_id: Option<i64>,
+) -> Result<(), MyError> {
+    if dependency.predecessor_id == dependency.successor_id {
+        return Err(MyError::InvalidField(
+            "successor_id",
+            "task cannot depend on itself".to_string(),
+        ));
+    }
+    if !(-MAX_LAG..=MAX_LAG).contains(&dependency.lag) {
+        return Err(MyError::InvalidField(
+            "lag",
+            "lag must not exceed ten years either way".to_string(),
+        ));
+    }
+    ensure_planned(
+        db,
+        dependency.baseline_id,
+        dependency.predecessor_id,
+        "predecessor_id",
+    )
+    .await?;
+    ensure_planned(
+        db,
+        dependency.baseline_id,
+        dependency.successor_id,
+        "successor_id",
+    )
+    .await?;
+
+    let links: Vec<(i64, i64)> = find_task_dependencies(db, dependency.baseline_id)
+        .await?
+        .into_iter()
+        .filter(|link| Some(link.task_dependency_id) != task_dependency_id)
+        .map(|link| (link.predecessor_id, link.successor_id))
+        .collect();
+    if links.contains(&(dependency.predecessor_id, dependency.successor_id)) {
+        return Err(MyError::Conflict(format!(
+            "task {} already depends on task {} in baseline {}",
+            dependency.successor_id, dependency.predecessor_id, dependency.baseline_id
+        )));
+    }
+    if creates_cycle(&links, dependency.predecessor_id, dependency.successor_id) {
+        return Err(MyError::Conflict(format!(
+            "linking task {} to task {} would create a cycle in baseline {}",
+            dependency.predecessor_id, dependency.successor_id, dependency.baseline_id
+        )));
+    }
+
+    Ok(())
+}
+
+/// Links two tasks of a baseline.
+pub async fn create_task_dependency(
+    db: web::Data<DatabaseConnection>,
+    payload: Payload<communication::baselines::TaskDependency>,
+) -> Result<Encoded<communication::baselines::TaskDependency>, MyError> {
+    let dependency = payload.into_inner();
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    ensure_baseline_editable(&txn, dependency.baseline_id).await?;
+    validate_task_dependency(&txn, &dependency, None).await?;
+
+    let inserted = entity::task_dependencies::ActiveModel {
+        baseline_id: Set(dependency.baseline_id),
+        predecessor_id: Set(dependency.predecessor_id),
+        successor_id: Set(dependency.successor_id),
+        dependency_type: Set(dependency.dependency_type.into_entity_dependency_type()),
+        lag: Set(dependency.lag),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|e| {
+        error!("Database error inserting task dependency: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::created(inserted.into_model_task_dependency()))
+}
+
+/// Relinks a dependency to other tasks of the same baseline, or changes its type or lag.
+pub async fn update_task_dependency(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    payload: Payload<communication::baselines::TaskDependency>,
+) -> Result<Encoded<communication::baselines::TaskDependency>, MyError> {
+    let task_dependency_id = path.into_inner();
+    let mut dependency = payload.into_inner();
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let record = find_task_dependency(&txn, task_dependency_id).await?;
+
+    dependency.baseline_id = record.baseline_id;
+    ensure_baseline_editable(&txn, record.baseline_id).await?;
+    validate_task_dependency(&txn, &dependency, Some(task_dependency_id)).await?;
+
+    let mut record = record.into_active_model();
+    record.predecessor_id = Set(dependency.predecessor_id);
+    record.successor_id = Set(dependency.successor_id);
+    record.dependency_type = Set(dependency.dependency_type.into_entity_dependency_type());
+    record.lag = Set(dependency.lag);
+    let updated = record.update(&txn).await.map_err(|e| {
+        error!("Database error updating task dependency: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::ok(updated.into_model_task_dependency()))
+}
+
+/// Unlinks two tasks.
+pub async fn delete_task_dependency(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, MyError> {
+    let task_dependency_id = path.into_inner();
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let record = find_task_dependency(&txn, task_dependency_id).await?;
+    ensure_baseline_editable(&txn, record.baseline_id).await?;
+
+    entity::task_dependencies::Entity::delete_by_id(task_dependency_id)
+        .exec(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error deleting task dependency: {}", e);
+            MyError::DatabaseError
+        })?;
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_direct_back_edge_is_a_cycle() {
+        assert!(creates_cycle(&[(1, 2)], 2, 1));
+    }
+
+    #[test]
+    fn an_indirect_back_edge_is_a_cycle() {
+        let links = [(1, 2), (2, 3), (3, 4)];
+        assert!(creates_cycle(&links, 4, 1));
+        assert!(creates_cycle(&links, 3, 2));
+    }
+
+    #[test]
+    fn a_diamond_is_not_a_cycle() {
+        // 1 leads to 4 both through 2 and through 3.
+        let links = [(1, 2), (1, 3), (2, 4)];
+        assert!(!creates_cycle(&links, 3, 4));
+        assert!(!creates_cycle(&links, 1, 4));
+    }
+
+    #[test]
+    fn cycles_elsewhere_do_not_matter() {
+        let links = [(1, 2), (3, 4), (4, 3)];
+        assert!(!creates_cycle(&links, 2, 5));
+    }
+}
@@ -0,0 +1,441 @@
+use actix_web::{Result, web};
+use chrono::{DateTime, Datelike, Months, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use communication::actuals::TaskProgress;
+use communication::baselines::{ResourceAllocation, TaskBaseline};
+use communication::evm::{
+    EarnedValue, EarnedValuePoint, EarnedValueReport, EarnedValueSeries, TaskEarnedValue,
+};
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::MyError;
+use crate::baselines::ensure_baseline_exists;
+use crate::calendars::find_resource_calendars;
+use crate::costs::{MonthPart, allocation_cost, default_timezone, report_month, split_cost};
+use crate::exchange_rates::ReportingCurrency;
+use crate::progress::{find_progress_history, latest_progress};
+use crate::resources::{find_resource_allocations, find_resources_by_id};
+use crate::response::Encoded;
+use crate::tasks::find_task_baselines;
+use crate::working_time::WorkingCalendar;
+
+/// The additive inputs of the earned value figures.
+#[derive(Clone, Copy, Default)]
+struct Values {
+    bac: f64,
+    pv: f64,
+    ev: f64,
+    ac: f64,
+}
+
+impl Values {
+    fn add(&mut self, other: Values) {
+        self.bac += other.bac;
+        self.pv += other.pv;
+        self.ev += other.ev;
+        self.ac += other.ac;
+    }
+
+    fn earned_value(self) -> EarnedValue {
+        let spi = (self.pv > 0.).then(|| self.ev / self.pv);
+        let cpi = (self.ac > 0.).then(|| self.ev / self.ac);
+        let eac = match cpi {
+            Some(cpi) if cpi > 0. => self.bac / cpi,
+            // Without a usable CPI the remaining work is assumed to cost what was planned.
+            _ => self.ac + self.bac - self.ev,
+        };
+        EarnedValue {
+            bac: self.bac,
+            pv: self.pv,
+            ev: self.ev,
+            ac: self.ac,
+            sv: self.ev - self.pv,
+            cv: self.ev - self.ac,
+            spi,
+            cpi,
+            eac,
+            etc: eac - self.ac,
+            vac: self.bac - eac,
+        }
+    }
+}
+
+/// An allocation with its cost split at the months of the project timezone.
+struct Spread {
+    task_id: i64,
+    resource_id: i64,
+    capacity_allocated: f64,
+    parts: Vec<MonthPart>,
+}
+
+/// Tasks of a baseline with the planned cost of their allocations and everything needed to cost
+/// parts of them.
+struct Plan {
+    tasks: Vec<TaskBaseline>,
+    spreads: Vec<Spread>,
+    resources: HashMap<i64, entity::resources::Model>,
+    calendars: HashMap<i64, WorkingCalendar>,
+    timezone: Tz,
+}
+
+impl Plan {
+    /// Spreads the cost of every allocation once, so values at any date only add parts up.
+    fn new(
+        tasks: Vec<TaskBaseline>,
+        allocations: &[ResourceAllocation],
+        resources: HashMap<i64, entity::resources::Model>,
+        calendars: HashMap<i64, WorkingCalendar>,
+        timezone: Tz,
+    ) -> Plan {
+        let by_id: HashMap<i64, &TaskBaseline> =
+            tasks.iter().map(|task| (task.task_id, task)).collect();
+        let spreads = allocations
+            .iter()
+            .filter_map(|allocation| {
+                let (Some(task), Some(resource), Some(calendar)) = (
+                    by_id.get(&allocation.task_id),
+                    resources.get(&allocation.resource_id),
+                    calendars.get(&allocation.resource_id),
+                ) else {
+                    return None;
+                };
+                let capacity_allocated = allocation.capacity_allocated.unwrap_or(0.);
+                Some(Spread {
+                    task_id: task.task_id,
+                    resource_id: resource.resource_id,
+                    capacity_allocated,
+                    parts: split_cost(
+                        resource,
+                        capacity_allocated,
+                        task.start.utc(),
+                        task.finish.utc(),
+                        calendar,
+                        timezone,
+                    ),
+                })
+            })
+            .collect();
+        Plan {
+            tasks,
+            spreads,
+            resources,
+            calendars,
+            timezone,
+        }
+    }
+
+    async fn find<C: ConnectionTrait>(db: &C, baseline_id: i64) -> Result<Plan, MyError> {
+        let mut tasks = find_task_baselines(db, baseline_id).await?;
+        tasks.sort_by(|a, b| (&a.wbs, a.task_id).cmp(&(&b.wbs, b.task_id)));
+        let allocations = find_resource_allocations(db, baseline_id).await?;
+        let resources = find_resources_by_id(
+            db,
+            allocations.iter().map(|allocation| allocation.resource_id),
+        )
+        .await?;
+        let calendars = find_resource_calendars(db, &resources).await?;
+        let timezone = default_timezone(db).await?;
+        Ok(Plan::new(
+            tasks,
+            &allocations,
+            resources,
+            calendars,
+            timezone,
+        ))
+    }
+
+    /// End of `date` in the project timezone; work planned before it counts towards PV.
+    fn end_of(&self, date: NaiveDate) -> DateTime<Utc> {
+        let next = date.succ_opt().unwrap_or(date).and_time(NaiveTime::MIN);
+        self.timezone
+            .from_local_datetime(&next)
+            .earliest()
+            .map(|end| end.with_timezone(&Utc))
+            .unwrap_or_else(|| next.and_utc())
+    }
+
+    /// Values of every task at `status_date`, given the latest progress of each task by then.
+    ///
+    /// Planned costs are converted month by month like in the cost report, actual costs at the rate
+    /// of the day they were assessed on.
+    fn values(
+        &self,
+        reporting: &ReportingCurrency,
+        status_date: NaiveDate,
+        progress: &HashMap<i64, &TaskProgress>,
+    ) -> Result<HashMap<i64, Values>, MyError> {
+        let cutoff = self.end_of(status_date);
+        let mut values: HashMap<i64, Values> = self
+            .tasks
+            .iter()
+            .map(|task| (task.task_id, Values::default()))
+            .collect();
+        for spread in &self.spreads {
+            let resource = &self.resources[&spread.resource_id];
+            let currency = &resource.cost_currency;
+            let task_values = values.entry(spread.task_id).or_default();
+            for &(month, from, to, cost) in &spread.parts {
+                task_values.bac += report_month(reporting, currency, month, cost)?.1;
+                // Parts ending by the cutoff are planned in full, the one it falls into pro rata.
+                let pv = if from >= cutoff {
+                    continue;
+                } else if to <= cutoff {
+                    cost
+                } else {
+                    allocation_cost(
+                        resource,
+                        spread.capacity_allocated,
+                        from,
+                        cutoff,
+                        &self.calendars[&spread.resource_id],
+                    )
+                };
+                task_values.pv += report_month(reporting, currency, month, pv)?.1;
+            }
+        }
+
+        for (task_id, task_values) in values.iter_mut() {
+            let Some(progress) = progress.get(task_id) else {
+                continue;
+            };
+            task_values.ev = task_values.bac * progress.percent_complete / 100.;
+            if let (Some(actual_cost), Some(cost_currency)) =
+                (progress.actual_cost, &progress.cost_currency)
+            {
+                (_, task_values.ac) =
+                    reporting.report(cost_currency, actual_cost, progress.status_date.naive())?;
+            }
+        }
+        Ok(values)
+    }
+
+    /// Values of every task summed over its WBS subtree.
+    fn subtree_values(&self, values: &HashMap<i64, Values>) -> HashMap<i64, Values> {
+        let parents: HashMap<i64, i64> = self
+            .tasks
+            .iter()
+            .filter_map(|task| task.parent.map(|parent| (task.task_id, parent)))
+            .collect();
+        let mut subtree: HashMap<i64, Values> = HashMap::new();
+        for (task_id, task_values) in values {
+            // Every task counts once towards itself and each of its ancestors.
+            let mut visited = HashSet::new();
+            let mut current = Some(*task_id);
+            while let Some(ancestor) = current
+                .filter(|ancestor| values.contains_key(ancestor) && visited.insert(*ancestor))
+            {
+                subtree.entry(ancestor).or_default().add(*task_values);
+                current = parents.get(&ancestor).copied();
+            }
+        }
+        subtree
+    }
+}
+
+/// Reads the reporting currency, which earned value needs, as it adds up costs of all currencies.
+async fn reporting_currency<C: ConnectionTrait>(
+    db: &C,
+    query: &HashMap<String, String>,
+) -> Result<(ReportingCurrency, String), MyError> {
+    let currency: String = crate::query_param(query, "currency")?;
+    Ok((ReportingCurrency::from_query(db, query).await?, currency))
+}
+
+/// Earned value of every task, of every WBS subtree and of the whole baseline at a `status_date`,
+/// in a reporting `currency`.
+pub async fn get_earned_value(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<Encoded<EarnedValueReport>, MyError> {
+    let baseline_id = path.into_inner();
+    let status_date: NaiveDate = crate::query_param(&query, "status_date")?;
+    let (reporting, currency) = reporting_currency(db.get_ref(), &query).await?;
+    ensure_baseline_exists(db.get_ref(), baseline_id).await?;
+
+    let plan = Plan::find(db.get_ref(), baseline_id).await?;
+    let history = find_progress_history(db.get_ref(), Some(status_date)).await?;
+    let values = plan.values(
+        &reporting,
+        status_date,
+        &latest_progress(&history, status_date),
+    )?;
+    let subtree = plan.subtree_values(&values);
+
+    let mut project = Values::default();
+    for task_values in values.values() {
+        project.add(*task_values);
+    }
+    let tasks = plan
+        .tasks
+        .iter()
+        .map(|task| TaskEarnedValue {
+            task_id: task.task_id,
+            task_summary: task.task_summary.clone(),
+            wbs: task.wbs.clone(),
+            parent: task.parent,
+            task: values
+                .get(&task.task_id)
+                .copied()
+                .unwrap_or_default()
+                .earned_value(),
+            subtree: subtree
+                .get(&task.task_id)
+                .copied()
+                .unwrap_or_default()
+                .earned_value(),
+        })
+        .collect();
+
+    Ok(Encoded::ok(EarnedValueReport {
+        baseline_id,
+        status_date: status_date.into(),
+        currency,
+        project: project.earned_value(),
+        tasks,
+    }))
+}
+
+/// PV, EV and AC of the whole baseline over time, in a reporting `currency`, for charting.
+///
+/// Points fall on every month end of the schedule and on every day progress was assessed on. EV
+/// and AC stop at the last assessment.
+pub async fn get_earned_value_series(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<Encoded<EarnedValueSeries>, MyError> {
+    let baseline_id = path.into_inner();
+    let (reporting, currency) = reporting_currency(db.get_ref(), &query).await?;
+    ensure_baseline_exists(db.get_ref(), baseline_id).await?;
+
+    let plan = Plan::find(db.get_ref(), baseline_id).await?;
+    let history = find_progress_history(db.get_ref(), None).await?;
+    let last_assessed = history.last().map(|progress| progress.status_date.naive());
+
+    let mut dates: BTreeSet<NaiveDate> = history
+        .iter()
+        .map(|progress| progress.status_date.naive())
+        .collect();
+    let first = plan
+        .tasks
+        .iter()
+        .map(|task| task.start.naive().date())
+        .min();
+    let last = plan
+        .tasks
+        .iter()
+        .map(|task| task.finish.naive().date())
+        .max();
+    if let (Some(first), Some(last)) = (first, last) {
+        let mut month = first.with_day0(0).unwrap_or(first);
+        while month <= last {
+            let Some(next) = month.checked_add_months(Months::new(1)) else {
+                break;
+            };
+            dates.insert(next.pred_opt().unwrap_or(next));
+            month = next;
+        }
+    }
+
+    let mut bac = 0.;
+    let mut points = Vec::new();
+    for date in dates {
+        let mut total = Values::default();
+        for task_values in plan
+            .values(&reporting, date, &latest_progress(&history, date))?
+            .into_values()
+        {
+            total.add(task_values);
+        }
+        bac = total.bac;
+        let assessed = last_assessed.is_some_and(|last_assessed| date <= last_assessed);
+        points.push(EarnedValuePoint {
+            date: date.into(),
+            pv: total.pv,
+            ev: assessed.then_some(total.ev),
+            ac: assessed.then_some(total.ac),
+        });
+    }
+
+    Ok(Encoded::ok(EarnedValueSeries {
+        baseline_id,
+        currency,
+        bac,
+        points,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{allocation, day, office_calendar, resource, task};
+
+    fn values(bac: f64, pv: f64, ev: f64, ac: f64) -> Values {
+        Values { bac, pv, ev, ac }
+    }
+
+    #[test]
+    fn indices_compare_earned_to_planned_and_actual() {
+        let earned = values(1000., 500., 400., 800.).earned_value();
+        assert_eq!((earned.sv, earned.cv), (-100., -400.));
+        assert_eq!((earned.spi, earned.cpi), (Some(0.8), Some(0.5)));
+        assert_eq!((earned.eac, earned.etc, earned.vac), (2000., 1200., -1000.));
+    }
+
+    #[test]
+    fn without_actuals_the_remaining_work_costs_what_was_planned() {
+        let earned = values(1000., 0., 0., 0.).earned_value();
+        assert_eq!((earned.spi, earned.cpi), (None, None));
+        assert_eq!((earned.eac, earned.etc, earned.vac), (1000., 1000., 0.));
+
+        // Cost without anything earned leaves the CPI at 0, which cannot scale the budget.
+        let earned = values(1000., 500., 0., 300.).earned_value();
+        assert_eq!(earned.cpi, Some(0.));
+        assert_eq!(earned.eac, 1300.);
+    }
+
+    #[test]
+    fn subtrees_add_up_every_task_once() {
+        let mut child = task(2, 0, 10);
+        child.parent = Some(1);
+        let mut grandchild = task(3, 0, 10);
+        grandchild.parent = Some(2);
+        let plan = Plan::new(
+            vec![task(1, 0, 10), child, grandchild, task(4, 0, 10)],
+            &[],
+            HashMap::new(),
+            HashMap::new(),
+            chrono_tz::UTC,
+        );
+        let task_values = HashMap::from([
+            (1, values(1., 0., 0., 0.)),
+            (2, values(10., 0., 0., 0.)),
+            (3, values(100., 0., 0., 0.)),
+            (4, values(1000., 0., 0., 0.)),
+        ]);
+        let subtree = plan.subtree_values(&task_values);
+        let bac = |task_id| subtree[&task_id].bac;
+        assert_eq!((bac(1), bac(2), bac(3), bac(4)), (111., 110., 100., 1000.));
+    }
+
+    #[test]
+    fn pv_stops_at_the_status_date() {
+        // Wednesday to Saturday: three office days of a resource billed 10 USD per hour.
+        let plan = Plan::new(
+            vec![task(1, 0, 72)],
+            &[allocation(1, 10, 1.)],
+            HashMap::from([(
+                10,
+                resource(10, 10., Some(entity::resources::Frequency::Hourly)),
+            )]),
+            HashMap::from([(10, office_calendar())]),
+            chrono_tz::UTC,
+        );
+        let task_values = plan
+            .values(&ReportingCurrency::default(), day(1), &HashMap::new())
+            .unwrap();
+        assert_eq!((task_values[&1].bac, task_values[&1].pv), (240., 80.));
+    }
+}
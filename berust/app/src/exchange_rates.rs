@@ -0,0 +1,226 @@
+use actix_web::{Result, web};
+use chrono::NaiveDate;
+use communication::costs::ExchangeRate;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+use std::collections::HashMap;
+use tracing::{error, warn};
+
+use crate::MyError;
+use crate::response::Encoded;
+
+pub trait IntoModelExchangeRate {
+    fn into_model_exchange_rate(self) -> ExchangeRate;
+}
+
+impl IntoModelExchangeRate for entity::exchange_rates::Model {
+    fn into_model_exchange_rate(self) -> ExchangeRate {
+        ExchangeRate {
+            exchange_rate_id: self.exchange_rate_id,
+            date: self.date.into(),
+            from_currency: self.from_currency,
+            to_currency: self.to_currency,
+            rate: self.rate,
+        }
+    }
+}
+
+/// Whether `code` looks like an ISO 4217 alphabetic currency code.
+pub fn is_currency_code(code: &str) -> bool {
+    code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase())
+}
+
+/// Converts amounts into the reporting currency a request asked for, or leaves them in the
+/// currency they arose in when it asked for none.
+#[derive(Default)]
+pub struct ReportingCurrency {
+    currency: Option<String>,
+    /// Published rates per (from, to) pair, ordered by date.
+    rates: HashMap<(String, String), Vec<(NaiveDate, f64)>>,
+}
+
+impl ReportingCurrency {
+    /// Reads the optional `currency` query parameter and, when given, the exchange rates.
+    pub async fn from_query<C: ConnectionTrait>(
+        db: &C,
+        query: &HashMap<String, String>,
+    ) -> Result<Self, MyError> {
+        let currency: Option<String> = crate::optional_query_param(query, "currency")?;
+        let mut rates: HashMap<(String, String), Vec<(NaiveDate, f64)>> = HashMap::new();
+        if let Some(currency) = &currency {
+            if !is_currency_code(currency) {
+                warn!("Invalid 'currency' format: {}", currency);
+                return Err(MyError::InvalidQueryParameter("currency".to_string()));
+            }
+            for rate in entity::exchange_rates::Entity::find()
+                .order_by_asc(entity::exchange_rates::Column::Date)
+                .all(db)
+                .await
+                .map_err(|e| {
+                    error!("Database error fetching exchange rates: {}", e);
+                    MyError::DatabaseError
+                })?
+            {
+                rates
+                    .entry((rate.from_currency, rate.to_currency))
+                    .or_default()
+                    .push((rate.date, rate.rate));
+            }
+        }
+        Ok(ReportingCurrency { currency, rates })
+    }
+
+    /// Reports in `currency` with the given published rates, for tests.
+    #[cfg(test)]
+    pub fn new(currency: &str, rates: HashMap<(String, String), Vec<(NaiveDate, f64)>>) -> Self {
+        ReportingCurrency {
+            currency: Some(currency.to_string()),
+            rates,
+        }
+    }
+
+    pub fn currency(&self) -> Option<String> {
+        self.currency.clone()
+    }
+
+    /// Rate from one currency to another on `date`: the last one published on or before it, or
+    /// the first one published when `date` precedes them all. The inverse of the opposite rate is
+    /// used when the pair itself has none.
+    fn rate(&self, from: &str, to: &str, date: NaiveDate) -> Option<f64> {
+        let on = |rates: &Vec<(NaiveDate, f64)>| {
+            let published = rates.partition_point(|(published, _)| *published <= date);
+            rates
+                .get(published.saturating_sub(1))
+                .map(|(_, rate)| *rate)
+        };
+        self.rates
+            .get(&(from.to_string(), to.to_string()))
+            .and_then(on)
+            .or_else(|| {
+                self.rates
+                    .get(&(to.to_string(), from.to_string()))
+                    .and_then(on)
+                    .filter(|rate| *rate != 0.)
+                    .map(|rate| 1. / rate)
+            })
+    }
+
+    /// `amount` of `currency` as it is to be reported, converted at the rate of `date` when a
+    /// reporting currency was asked for.
+    pub fn report(
+        &self,
+        currency: &str,
+        amount: f64,
+        date: NaiveDate,
+    ) -> Result<(String, f64), MyError> {
+        let Some(reporting) = &self.currency else {
+            return Ok((currency.to_string(), amount));
+        };
+        if currency == reporting {
+            return Ok((currency.to_string(), amount));
+        }
+        let rate = self.rate(currency, reporting, date).ok_or_else(|| {
+            MyError::NotFound(format!("exchange rate from {currency} to {reporting}"))
+        })?;
+        Ok((reporting.clone(), amount * rate))
+    }
+}
+
+/// Published exchange rates, optionally only those `from_currency` or `to_currency`, ordered by
+/// date.
+pub async fn get_exchange_rates(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<Encoded<Vec<ExchangeRate>>, MyError> {
+    let from_currency: Option<String> = crate::optional_query_param(&query, "from_currency")?;
+    let to_currency: Option<String> = crate::optional_query_param(&query, "to_currency")?;
+
+    let mut select = entity::exchange_rates::Entity::find();
+    if let Some(from_currency) = from_currency {
+        select = select.filter(entity::exchange_rates::Column::FromCurrency.eq(from_currency));
+    }
+    if let Some(to_currency) = to_currency {
+        select = select.filter(entity::exchange_rates::Column::ToCurrency.eq(to_currency));
+    }
+
+    let exchange_rates = select
+        .order_by_asc(entity::exchange_rates::Column::Date)
+        .order_by_asc(entity::exchange_rates::Column::FromCurrency)
+        .order_by_asc(entity::exchange_rates::Column::ToCurrency)
+        .all(db.get_ref())
+        .await
+        .map_err(|e| {
+            error!("Database error fetching exchange rates: {}", e);
+            MyError::DatabaseError
+        })?
+        .into_iter()
+        .map(|record| record.into_model_exchange_rate())
+        .collect();
+
+    Ok(Encoded::ok(exchange_rates))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, day).unwrap()
+    }
+
+    /// Reports in USD with EUR rates published on the 10th and the 20th.
+    fn usd() -> ReportingCurrency {
+        ReportingCurrency::new(
+            "USD",
+            HashMap::from([(
+                ("EUR".to_string(), "USD".to_string()),
+                vec![(day(10), 1.1), (day(20), 1.2)],
+            )]),
+        )
+    }
+
+    fn reported(reporting: &ReportingCurrency, currency: &str, date: NaiveDate) -> (String, f64) {
+        reporting.report(currency, 100., date).unwrap()
+    }
+
+    #[test]
+    fn amounts_stay_in_their_currency_without_a_reporting_currency() {
+        let reporting = ReportingCurrency {
+            currency: None,
+            rates: HashMap::new(),
+        };
+        assert_eq!(
+            reported(&reporting, "EUR", day(15)),
+            ("EUR".to_string(), 100.)
+        );
+        assert_eq!(reported(&usd(), "USD", day(15)), ("USD".to_string(), 100.));
+    }
+
+    #[test]
+    fn the_last_rate_published_by_the_date_applies() {
+        let (currency, amount) = reported(&usd(), "EUR", day(19));
+        assert_eq!(currency, "USD");
+        assert!((amount - 110.).abs() < 1e-9);
+        assert!((reported(&usd(), "EUR", day(20)).1 - 120.).abs() < 1e-9);
+        // Before any rate was published the first one stands in.
+        assert!((reported(&usd(), "EUR", day(1)).1 - 110.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn the_opposite_rate_is_inverted() {
+        let reporting = ReportingCurrency {
+            currency: Some("EUR".to_string()),
+            ..usd()
+        };
+        assert!((reported(&reporting, "USD", day(25)).1 - 100. / 1.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_missing_rate_is_not_found() {
+        assert!(matches!(
+            usd().report("GBP", 100., day(15)),
+            Err(MyError::NotFound(_))
+        ));
+    }
+}
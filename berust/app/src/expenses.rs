@@ -0,0 +1,76 @@
+use actix_web::{Result, web};
+use chrono::NaiveDate;
+use communication::actuals::Expense;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use std::collections::HashMap;
+use tracing::error;
+
+use crate::MyError;
+use crate::exchange_rates::ReportingCurrency;
+use crate::response::Encoded;
+
+pub trait IntoModelExpense {
+    fn into_model_expense(self) -> Expense;
+}
+
+impl IntoModelExpense for entity::expenses::Model {
+    fn into_model_expense(self) -> Expense {
+        Expense {
+            expense_id: self.expense_id,
+            document_number: self.document_number,
+            date: self.date.into(),
+            description: self.description,
+            amount: self.amount,
+            currency: self.currency,
+            category: self.category,
+            task_id: self.task_id,
+        }
+    }
+}
+
+/// Actual expenses, optionally of one `task_id` or `category` and dated `from` to `to`, both
+/// inclusive, ordered by date. With a reporting `currency` amounts are converted at the rate of
+/// their date.
+pub async fn get_expenses(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<Encoded<Vec<Expense>>, MyError> {
+    let task_id: Option<i64> = crate::optional_query_param(&query, "task_id")?;
+    let category: Option<String> = crate::optional_query_param(&query, "category")?;
+    let from: Option<NaiveDate> = crate::optional_query_param(&query, "from")?;
+    let to: Option<NaiveDate> = crate::optional_query_param(&query, "to")?;
+    let reporting = ReportingCurrency::from_query(db.get_ref(), &query).await?;
+
+    let mut select = entity::expenses::Entity::find();
+    if let Some(task_id) = task_id {
+        select = select.filter(entity::expenses::Column::TaskId.eq(task_id));
+    }
+    if let Some(category) = category {
+        select = select.filter(entity::expenses::Column::Category.eq(category));
+    }
+    if let Some(from) = from {
+        select = select.filter(entity::expenses::Column::Date.gte(from));
+    }
+    if let Some(to) = to {
+        select = select.filter(entity::expenses::Column::Date.lte(to));
+    }
+
+    let mut expenses: Vec<Expense> = select
+        .order_by_asc(entity::expenses::Column::Date)
+        .order_by_asc(entity::expenses::Column::DocumentNumber)
+        .all(db.get_ref())
+        .await
+        .map_err(|e| {
+            error!("Database error fetching expenses: {}", e);
+            MyError::DatabaseError
+        })?
+        .into_iter()
+        .map(|record| record.into_model_expense())
+        .collect();
+    for expense in expenses.iter_mut() {
+        (expense.currency, expense.amount) =
+            reporting.report(&expense.currency, expense.amount, expense.date.naive())?;
+    }
+
+    Ok(Encoded::ok(expenses))
+}
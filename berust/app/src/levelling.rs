@@ -0,0 +1,438 @@
+use actix_web::{Result, web};
+use chrono::TimeDelta;
+use communication::baselines::{
+    BaselineSnapshot, OverAllocation, ResourceAllocation, TaskBaseline, TaskDependency,
+};
+use communication::levelling::{LevellingReport, LevellingRequest, TaskMove};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, QueryFilter, TransactionTrait,
+};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use tracing::{error, info};
+
+use crate::MyError;
+use crate::baselines::{IntoModelBaseline, ensure_baseline_exists, snapshot_baseline};
+use crate::dependencies::find_task_dependencies;
+use crate::overallocations::find_overallocations;
+use crate::resources::{find_resource_allocations, find_resources_by_id};
+use crate::response::{Encoded, Payload};
+use crate::schedule::{children, earliest_start, schedule};
+use crate::tasks::find_task_baselines;
+
+/// Upper bound on the delays tried, so levelling large baselines stays responsive.
+const MAX_MOVES: usize = 1000;
+
+/// Tasks of a baseline together with the links the levelling keeps intact.
+struct Network<'a> {
+    tasks: &'a [TaskBaseline],
+    /// Every task after its predecessors.
+    order: Vec<usize>,
+    /// Links met by the source dates, as (predecessor, link); links the source already breaks are
+    /// not levelling's business.
+    predecessors: Vec<Vec<(usize, &'a TaskDependency)>>,
+    /// Leaves below every summary task, none for the leaves themselves.
+    leaves: Vec<Vec<usize>>,
+}
+
+impl Network<'_> {
+    /// Dates of all tasks when each leaf is delayed by at least `delays` seconds and pushed further
+    /// by its delayed predecessors. Summary tasks span their leaves, as in the schedule.
+    fn dates(&self, delays: &[i64]) -> Vec<TaskBaseline> {
+        let mut levelled = self.tasks.to_vec();
+        for &i in &self.order {
+            let duration = self.tasks[i].finish - self.tasks[i].start;
+            let mut start = self.tasks[i].start.naive() + TimeDelta::seconds(delays[i]);
+            for (p, link) in &self.predecessors[i] {
+                if let Some(allowed) = earliest_start(
+                    link,
+                    levelled[*p].start.naive(),
+                    levelled[*p].finish.naive(),
+                    duration,
+                ) {
+                    start = start.max(allowed);
+                }
+            }
+            levelled[i].start = start.into();
+            levelled[i].finish = (start + duration).into();
+        }
+        for (i, leaves) in self.leaves.iter().enumerate() {
+            let start = leaves.iter().map(|&j| levelled[j].start).min();
+            let finish = leaves.iter().map(|&j| levelled[j].finish).max();
+            if let (Some(start), Some(finish)) = (start, finish) {
+                levelled[i].start = start;
+                levelled[i].finish = finish;
+            }
+        }
+        levelled
+    }
+}
+
+fn delay(before: &TaskBaseline, after: &TaskBaseline) -> i64 {
+    (after.start - before.start).num_seconds()
+}
+
+/// Delays tasks until no resource is allocated beyond its capacity, returning the levelled tasks
+/// and the over-allocations left.
+///
+/// The earliest over-allocation is resolved first by moving one of its leaf tasks to the end of it,
+/// preferring the task with the most room left. A move is only taken when no leaf, including the
+/// successors it pushes, ends up delayed beyond its allowance: `max_delay` when given, otherwise
+/// the gap between its planned and its late start. Summary tasks follow their leaves rather than
+/// move on their own. Over-allocations no move can resolve are left.
+pub fn level(
+    baseline_id: i64,
+    tasks: &[TaskBaseline],
+    dependencies: &[TaskDependency],
+    allocations: &[ResourceAllocation],
+    resources: &HashMap<i64, entity::resources::Model>,
+    max_delay: Option<i64>,
+) -> Result<(Vec<TaskBaseline>, Vec<OverAllocation>), String> {
+    let schedule = schedule(baseline_id, tasks, dependencies)?;
+    let index: HashMap<i64, usize> = tasks
+        .iter()
+        .enumerate()
+        .map(|(i, task)| (task.task_id, i))
+        .collect();
+
+    let mut allowances = vec![0; tasks.len()];
+    for scheduled in &schedule.tasks {
+        let i = index[&scheduled.task_id];
+        allowances[i] = match max_delay {
+            Some(max_delay) => max_delay,
+            None => (scheduled.late_start - tasks[i].start).num_seconds(),
+        }
+        .max(0);
+    }
+
+    // The schedule refuses links of summary tasks, so every link joins two leaves.
+    let mut predecessors = vec![Vec::new(); tasks.len()];
+    for link in dependencies {
+        if let (Some(&from), Some(&to)) = (
+            index.get(&link.predecessor_id),
+            index.get(&link.successor_id),
+        ) {
+            let (predecessor, successor) = (&tasks[from], &tasks[to]);
+            let allowed = earliest_start(
+                link,
+                predecessor.start.naive(),
+                predecessor.finish.naive(),
+                successor.finish - successor.start,
+            );
+            if allowed.is_some_and(|allowed| allowed <= successor.start.naive()) {
+                predecessors[to].push((from, link));
+            }
+        }
+    }
+    let children = children(tasks);
+    let mut leaves = vec![Vec::new(); tasks.len()];
+    for (i, below) in leaves.iter_mut().enumerate() {
+        let mut visited = vec![false; tasks.len()];
+        let mut stack = children[i].clone();
+        while let Some(j) = stack.pop() {
+            if std::mem::replace(&mut visited[j], true) {
+                continue;
+            }
+            if children[j].is_empty() {
+                below.push(j);
+            } else {
+                stack.extend(&children[j]);
+            }
+        }
+    }
+    let is_leaf: Vec<bool> = children.iter().map(Vec::is_empty).collect();
+    let network = Network {
+        tasks,
+        order: schedule
+            .tasks
+            .iter()
+            .map(|task| index[&task.task_id])
+            .collect(),
+        predecessors,
+        leaves,
+    };
+
+    let mut delays = vec![0; tasks.len()];
+    let mut levelled = network.dates(&delays);
+    let mut unresolved: Vec<OverAllocation> = Vec::new();
+    for _ in 0..MAX_MOVES {
+        let Some(conflict) = find_overallocations(&levelled, allocations, resources)
+            .into_iter()
+            .filter(|overallocation| !unresolved.contains(overallocation))
+            .min_by_key(|overallocation| overallocation.start)
+        else {
+            break;
+        };
+
+        let mut candidates: Vec<usize> = conflict
+            .task_ids
+            .iter()
+            .filter_map(|task_id| index.get(task_id).copied())
+            .filter(|&i| is_leaf[i])
+            .collect();
+        candidates.sort_by_key(|&i| {
+            (
+                Reverse(allowances[i] - delay(&tasks[i], &levelled[i])),
+                tasks[i].task_id,
+            )
+        });
+
+        let mut resolved = false;
+        for i in candidates {
+            let mut trial = delays.clone();
+            trial[i] = delay(&tasks[i], &levelled[i])
+                + (conflict.finish - levelled[i].start).num_seconds();
+            let dates = network.dates(&trial);
+            if (0..tasks.len())
+                .filter(|&j| is_leaf[j])
+                .all(|j| delay(&tasks[j], &dates[j]) <= allowances[j])
+            {
+                delays = trial;
+                levelled = dates;
+                resolved = true;
+                break;
+            }
+        }
+        if !resolved {
+            unresolved.push(conflict);
+        }
+    }
+
+    let remaining = find_overallocations(&levelled, allocations, resources);
+    Ok((levelled, remaining))
+}
+
+/// Levels a baseline into a new draft baseline; the source baseline is left untouched.
+pub async fn level_baseline(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    payload: Payload<LevellingRequest>,
+) -> Result<Encoded<LevellingReport>, MyError> {
+    let source_baseline_id = path.into_inner();
+    let request = payload.into_inner();
+    if request.name.trim().is_empty() {
+        return Err(MyError::InvalidField(
+            "name",
+            "name must not be empty".to_string(),
+        ));
+    }
+    if request.max_delay.is_some_and(|max_delay| max_delay < 0) {
+        return Err(MyError::InvalidField(
+            "max_delay",
+            "max_delay must not be negative".to_string(),
+        ));
+    }
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    ensure_baseline_exists(&txn, source_baseline_id).await?;
+    let tasks = find_task_baselines(&txn, source_baseline_id).await?;
+    let dependencies = find_task_dependencies(&txn, source_baseline_id).await?;
+    let allocations = find_resource_allocations(&txn, source_baseline_id).await?;
+    let resources = find_resources_by_id(
+        &txn,
+        allocations.iter().map(|allocation| allocation.resource_id),
+    )
+    .await?;
+
+    let (levelled, remaining) = level(
+        source_baseline_id,
+        &tasks,
+        &dependencies,
+        &allocations,
+        &resources,
+        request.max_delay,
+    )
+    .map_err(MyError::Conflict)?;
+
+    let baseline = snapshot_baseline(
+        &txn,
+        source_baseline_id,
+        BaselineSnapshot {
+            name: request.name,
+            description: request.description,
+            comment: request.comment,
+        },
+    )
+    .await?;
+
+    // A summary task can finish later without starting later, so its row changes unmoved.
+    let changed: HashMap<i64, (&TaskBaseline, &TaskBaseline)> = tasks
+        .iter()
+        .zip(&levelled)
+        .filter(|(before, after)| before.start != after.start || before.finish != after.finish)
+        .map(|(before, after)| (before.task_id, (before, after)))
+        .collect();
+    let mut moves: Vec<TaskMove> = changed
+        .values()
+        .filter(|(before, after)| before.start != after.start)
+        .map(|(before, after)| TaskMove {
+            task_id: before.task_id,
+            task_summary: before.task_summary.clone(),
+            start_before: before.start,
+            start_after: after.start,
+            delay: delay(before, after),
+        })
+        .collect();
+    moves.sort_by_key(|task_move| (task_move.start_before, task_move.task_id));
+
+    let task_baselines = entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::BaselineId.eq(baseline.baseline_id))
+        .all(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task baselines: {}", e);
+            MyError::DatabaseError
+        })?;
+    for record in task_baselines {
+        let Some((_, task)) = changed.get(&record.task_id) else {
+            continue;
+        };
+        let mut record = record.into_active_model();
+        record.start = Set(task.start.naive());
+        record.finish = Set(task.finish.naive());
+        record.update(&txn).await.map_err(|e| {
+            error!("Database error updating task baseline: {}", e);
+            MyError::DatabaseError
+        })?;
+    }
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    info!(
+        "Baseline {} levelled into baseline {}, {} tasks moved",
+        source_baseline_id,
+        baseline.baseline_id,
+        moves.len()
+    );
+
+    Ok(Encoded::created(LevellingReport {
+        source_baseline_id,
+        baseline: baseline.into_model_baseline(),
+        moves,
+        remaining,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{allocation, at, link, resource, task};
+    use communication::baselines::DependencyType;
+
+    const HOUR: i64 = 3600;
+
+    /// Start and finish of every levelled task, in hours after the start of 2025.
+    fn dates(levelled: &[TaskBaseline]) -> Vec<(i64, i64, i64)> {
+        levelled
+            .iter()
+            .map(|task| {
+                (
+                    task.task_id,
+                    (task.start.naive() - at(0)).num_hours(),
+                    (task.finish.naive() - at(0)).num_hours(),
+                )
+            })
+            .collect()
+    }
+
+    fn resources() -> HashMap<i64, entity::resources::Model> {
+        HashMap::from([(10, resource(10, 100., None))])
+    }
+
+    #[test]
+    fn a_task_moves_to_the_end_of_the_overallocation() {
+        let tasks = [task(1, 0, 10), task(2, 0, 10)];
+        let allocations = [allocation(1, 10, 1.), allocation(2, 10, 1.)];
+        let (levelled, remaining) =
+            level(1, &tasks, &[], &allocations, &resources(), Some(20 * HOUR)).unwrap();
+        assert_eq!(dates(&levelled), vec![(1, 10, 20), (2, 0, 10)]);
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn the_task_with_the_most_float_moves() {
+        // 3 keeps the baseline open until hour 30 and 4 cuts the float of 1 to 5 hours.
+        let tasks = [
+            task(1, 0, 10),
+            task(2, 0, 10),
+            task(3, 0, 30),
+            task(4, 10, 25),
+        ];
+        let allocations = [allocation(1, 10, 1.), allocation(2, 10, 1.)];
+        let dependencies = [link(1, 4, DependencyType::FinishToStart, 0)];
+        let (levelled, remaining) =
+            level(1, &tasks, &dependencies, &allocations, &resources(), None).unwrap();
+        assert_eq!(
+            dates(&levelled),
+            vec![(1, 0, 10), (2, 10, 20), (3, 0, 30), (4, 10, 25)]
+        );
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn successors_are_pushed_only_as_far_as_their_links_need() {
+        let tasks = [task(1, 0, 10), task(2, 0, 10), task(3, 12, 15)];
+        let allocations = [allocation(1, 10, 1.), allocation(2, 10, 1.)];
+        let dependencies = [link(1, 3, DependencyType::FinishToStart, 0)];
+        let (levelled, remaining) = level(
+            1,
+            &tasks,
+            &dependencies,
+            &allocations,
+            &resources(),
+            Some(10 * HOUR),
+        )
+        .unwrap();
+        assert_eq!(dates(&levelled), vec![(1, 10, 20), (2, 0, 10), (3, 20, 23)]);
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn overallocations_without_room_to_move_are_left() {
+        let tasks = [task(1, 0, 10), task(2, 0, 10)];
+        let allocations = [allocation(1, 10, 1.), allocation(2, 10, 1.)];
+        let (levelled, remaining) =
+            level(1, &tasks, &[], &allocations, &resources(), None).unwrap();
+        assert_eq!(dates(&levelled), vec![(1, 0, 10), (2, 0, 10)]);
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].task_ids, vec![1, 2]);
+
+        let (_, remaining) =
+            level(1, &tasks, &[], &allocations, &resources(), Some(5 * HOUR)).unwrap();
+        assert_eq!(remaining.len(), 1);
+    }
+
+    #[test]
+    fn summary_tasks_span_their_levelled_subtasks() {
+        let mut first = task(2, 0, 10);
+        first.parent = Some(1);
+        let mut second = task(3, 0, 10);
+        second.parent = Some(1);
+        let tasks = [task(1, 0, 10), first, second];
+        let allocations = [allocation(2, 10, 1.), allocation(3, 10, 1.)];
+        let (levelled, remaining) =
+            level(1, &tasks, &[], &allocations, &resources(), Some(20 * HOUR)).unwrap();
+        assert_eq!(dates(&levelled), vec![(1, 0, 20), (2, 10, 20), (3, 0, 10)]);
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn summary_tasks_do_not_move_on_their_own() {
+        let mut subtask = task(2, 0, 10);
+        subtask.parent = Some(1);
+        let tasks = [task(1, 0, 10), subtask, task(3, 0, 10)];
+        let allocations = [allocation(1, 10, 1.), allocation(3, 10, 1.)];
+        let (levelled, remaining) =
+            level(1, &tasks, &[], &allocations, &resources(), Some(20 * HOUR)).unwrap();
+        assert_eq!(dates(&levelled), vec![(1, 0, 10), (2, 0, 10), (3, 10, 20)]);
+        assert!(remaining.is_empty());
+    }
+}
@@ -4,17 +4,38 @@ use actix_web::{
     App, HttpResponse, HttpServer, Responder, ResponseError, Result, middleware::Logger, web,
 };
 
+use communication::errors::{ApiError, ErrorCode};
 use migration::{Migrator, MigratorTrait};
-use sea_orm::{
-    ColumnTrait, ConnectionTrait, Database, DatabaseConnection, EntityTrait, QueryFilter,
-    QuerySelect, Statement,
-};
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, Statement};
 use serde_json::json;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tracing::{error, info, warn};
 
+mod baselines;
+mod calendars;
+mod config;
+mod costs;
+mod dependencies;
+mod evm;
+mod exchange_rates;
+mod expenses;
+mod levelling;
+mod overallocations;
+mod paging;
+mod progress;
+mod resources;
+mod response;
+mod schedule;
+mod tasks;
+mod team_members;
+#[cfg(test)]
+mod testing;
+mod time_entries;
+mod wbs;
+mod working_time;
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     tracing_subscriber::fmt()
@@ -68,14 +89,213 @@ async fn main() -> std::io::Result<()> {
 
         App::new()
             .app_data(db_data.clone())
+            .app_data(
+                web::PathConfig::default().error_handler(|err, _req| {
+                    MyError::InvalidPathParameter(err.to_string()).into()
+                }),
+            )
             .wrap(cors)
             .wrap(Logger::default())
             .service(web::resource("/health").route(web::get().to(health_check)))
-            .service(web::resource("/tasks").route(web::get().to(get_list_of_tasks)))
-            .service(web::resource("/resources").route(web::get().to(get_resources)))
+            .service(
+                web::resource("/tasks")
+                    .route(web::get().to(tasks::get_list_of_tasks))
+                    .route(web::post().to(tasks::create_task)),
+            )
+            .service(
+                web::resource("/tasks/baselines")
+                    .route(web::post().to(tasks::create_task_baseline)),
+            )
+            .service(
+                web::resource("/tasks/baselines/{task_baseline_id}")
+                    .route(web::patch().to(tasks::update_task_baseline))
+                    .route(web::delete().to(tasks::delete_task_baseline)),
+            )
+            .service(
+                web::resource("/tasks/dependencies")
+                    .route(web::get().to(dependencies::get_task_dependencies))
+                    .route(web::post().to(dependencies::create_task_dependency)),
+            )
+            .service(
+                web::resource("/tasks/dependencies/{task_dependency_id}")
+                    .route(web::patch().to(dependencies::update_task_dependency))
+                    .route(web::delete().to(dependencies::delete_task_dependency)),
+            )
+            .service(
+                web::resource("/tasks/{task_id}")
+                    .route(web::patch().to(tasks::update_task))
+                    .route(web::delete().to(tasks::delete_task)),
+            )
+            .service(
+                web::resource("/tasks/{task_id}/status")
+                    .route(web::post().to(tasks::change_task_status)),
+            )
+            .service(
+                web::resource("/task_progress")
+                    .route(web::get().to(progress::get_task_progress))
+                    .route(web::post().to(progress::create_task_progress)),
+            )
+            .service(
+                web::resource("/task_progress/{task_progress_id}")
+                    .route(web::patch().to(progress::update_task_progress))
+                    .route(web::delete().to(progress::delete_task_progress)),
+            )
+            .service(
+                web::resource("/task_status_history")
+                    .route(web::get().to(tasks::get_task_status_history)),
+            )
+            .service(
+                web::resource("/config")
+                    .route(web::get().to(config::get_config))
+                    .route(web::put().to(config::update_config)),
+            )
+            .service(web::resource("/baselines").route(web::get().to(baselines::get_baselines)))
+            .service(
+                web::resource("/baselines/compare")
+                    .route(web::get().to(baselines::compare_baselines)),
+            )
+            .service(
+                web::resource("/baselines/{baseline_id}/snapshot")
+                    .route(web::post().to(baselines::create_baseline_snapshot)),
+            )
+            .service(
+                web::resource("/baselines/{baseline_id}/approve")
+                    .route(web::post().to(baselines::approve_baseline)),
+            )
+            .service(
+                web::resource("/baselines/{baseline_id}/lock")
+                    .route(web::post().to(baselines::lock_baseline)),
+            )
+            .service(
+                web::resource("/baselines/{baseline_id}/promote")
+                    .route(web::post().to(baselines::promote_baseline)),
+            )
+            .service(
+                web::resource("/baselines/{baseline_id}/schedule")
+                    .route(web::get().to(baselines::get_schedule))
+                    .route(web::post().to(baselines::apply_schedule)),
+            )
+            .service(
+                web::resource("/baselines/{baseline_id}/milestones")
+                    .route(web::get().to(tasks::get_milestones)),
+            )
+            .service(
+                web::resource("/baselines/{baseline_id}/costs")
+                    .route(web::get().to(costs::get_costs)),
+            )
+            .service(
+                web::resource("/baselines/{baseline_id}/earned_value")
+                    .route(web::get().to(evm::get_earned_value)),
+            )
+            .service(
+                web::resource("/baselines/{baseline_id}/earned_value/series")
+                    .route(web::get().to(evm::get_earned_value_series)),
+            )
+            .service(
+                web::resource("/baselines/{baseline_id}/level")
+                    .route(web::post().to(levelling::level_baseline)),
+            )
+            .service(
+                web::resource("/baselines/{baseline_id}/overallocations")
+                    .route(web::get().to(overallocations::get_overallocations)),
+            )
+            .service(
+                web::resource("/baselines/{baseline_id}/wbs").route(web::get().to(wbs::get_wbs)),
+            )
+            .service(
+                web::resource("/baselines/{baseline_id}/wbs/{task_id}")
+                    .route(web::get().to(wbs::get_wbs_subtree)),
+            )
+            .service(
+                web::resource("/baselines/{baseline_id}/wbs/{task_id}/ancestors")
+                    .route(web::get().to(wbs::get_wbs_ancestors)),
+            )
+            .service(
+                web::resource("/resources")
+                    .route(web::get().to(resources::get_resources))
+                    .route(web::post().to(resources::create_resource)),
+            )
+            .service(
+                web::resource("/resource_types")
+                    .route(web::post().to(resources::create_resource_type)),
+            )
+            .service(
+                web::resource("/resource_types/{resource_type_id}")
+                    .route(web::patch().to(resources::update_resource_type))
+                    .route(web::delete().to(resources::deactivate_resource_type)),
+            )
             .service(
                 web::resource("/resources/allocation")
-                    .route(web::get().to(get_resource_allocation)),
+                    .route(web::get().to(resources::get_resource_allocation))
+                    .route(web::post().to(resources::create_resource_allocation)),
+            )
+            .service(
+                web::resource("/resources/allocation/{resource_baseline_id}")
+                    .route(web::patch().to(resources::update_resource_allocation))
+                    .route(web::delete().to(resources::delete_resource_allocation)),
+            )
+            .service(
+                web::resource("/resources/{resource_id}")
+                    .route(web::patch().to(resources::update_resource))
+                    .route(web::delete().to(resources::deactivate_resource)),
+            )
+            .service(
+                web::resource("/resources/{resource_id}/working_time")
+                    .route(web::get().to(calendars::get_resource_working_time)),
+            )
+            .service(
+                web::resource("/calendars")
+                    .route(web::get().to(calendars::get_calendars))
+                    .route(web::post().to(calendars::create_calendar)),
+            )
+            .service(
+                web::resource("/calendars/exceptions/{calendar_exception_id}")
+                    .route(web::patch().to(calendars::update_calendar_exception))
+                    .route(web::delete().to(calendars::delete_calendar_exception)),
+            )
+            .service(
+                web::resource("/calendars/{calendar_id}")
+                    .route(web::patch().to(calendars::update_calendar))
+                    .route(web::delete().to(calendars::delete_calendar)),
+            )
+            .service(
+                web::resource("/calendars/{calendar_id}/work_hours")
+                    .route(web::get().to(calendars::get_work_hours))
+                    .route(web::put().to(calendars::replace_work_hours)),
+            )
+            .service(
+                web::resource("/calendars/{calendar_id}/exceptions")
+                    .route(web::get().to(calendars::get_calendar_exceptions))
+                    .route(web::post().to(calendars::create_calendar_exception)),
+            )
+            .service(
+                web::resource("/calendars/{calendar_id}/working_time")
+                    .route(web::get().to(calendars::get_calendar_working_time)),
+            )
+            .service(
+                web::resource("/exchange_rates")
+                    .route(web::get().to(exchange_rates::get_exchange_rates)),
+            )
+            .service(web::resource("/expenses").route(web::get().to(expenses::get_expenses)))
+            .service(
+                web::resource("/team_members")
+                    .route(web::get().to(team_members::get_team_members))
+                    .route(web::post().to(team_members::create_team_member)),
+            )
+            .service(
+                web::resource("/team_members/{user_id}")
+                    .route(web::patch().to(team_members::update_team_member))
+                    .route(web::delete().to(team_members::delete_team_member)),
+            )
+            .service(
+                web::resource("/time_entries")
+                    .route(web::get().to(time_entries::get_time_entries))
+                    .route(web::post().to(time_entries::create_time_entry)),
+            )
+            .service(
+                web::resource("/time_entries/{time_entry_id}")
+                    .route(web::patch().to(time_entries::update_time_entry))
+                    .route(web::delete().to(time_entries::delete_time_entry)),
             )
     })
     .bind(("0.0.0.0", 3333))?
@@ -133,192 +353,100 @@ async fn health_check(db: web::Data<DatabaseConnection>) -> impl Responder {
 
 #[derive(Debug)]
 enum MyError {
-    JsonParserError,
     DatabaseError,
     MissingQueryParameter(String),
     InvalidQueryParameter(String),
+    InvalidPathParameter(String),
+    /// The body could not be decoded at all.
+    MalformedPayload(String),
+    /// A decoded payload field holds an unacceptable value.
+    InvalidField(&'static str, String),
+    NotFound(String),
+    Conflict(String),
 }
 
-impl Display for MyError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+impl MyError {
+    fn api_error(&self) -> ApiError {
         match self {
-            MyError::JsonParserError => write!(f, "Server error: Json parser error"),
-            MyError::DatabaseError => write!(f, "Server error: Database operation failed"),
+            MyError::DatabaseError => {
+                ApiError::new(ErrorCode::Internal, "Database operation failed")
+            }
             MyError::MissingQueryParameter(param) => {
-                write!(f, "Server error: Missing query parameter: {}", param)
+                ApiError::new(ErrorCode::BadRequest, "Missing query parameter").with_field(param)
             }
             MyError::InvalidQueryParameter(param) => {
-                write!(f, "Server error: Invalid query parameter: {}", param)
+                ApiError::new(ErrorCode::BadRequest, "Invalid query parameter").with_field(param)
+            }
+            MyError::InvalidPathParameter(reason) => {
+                ApiError::new(ErrorCode::BadRequest, "Invalid path parameter").with_details(reason)
+            }
+            MyError::MalformedPayload(reason) => {
+                ApiError::new(ErrorCode::BadRequest, "Malformed payload").with_details(reason)
+            }
+            MyError::InvalidField(field, reason) => {
+                ApiError::new(ErrorCode::InvalidPayload, reason).with_field(*field)
             }
+            MyError::NotFound(what) => {
+                ApiError::new(ErrorCode::NotFound, format!("Not found: {what}"))
+            }
+            MyError::Conflict(reason) => ApiError::new(ErrorCode::Conflict, reason),
         }
     }
 }
 
-impl ResponseError for MyError {
-    fn status_code(&self) -> StatusCode {
-        StatusCode::INTERNAL_SERVER_ERROR
-    }
-
-    fn error_response(&self) -> HttpResponse {
-        error!("{}, {}", self.status_code(), self.to_string());
-        HttpResponse::build(self.status_code()).body(self.to_string())
+impl Display for MyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.api_error().fmt(f)
     }
 }
 
-async fn get_list_of_tasks(
-    db: web::Data<DatabaseConnection>,
-    query: web::Query<HashMap<String, String>>,
-) -> Result<HttpResponse, MyError> {
-    let baseline_id_str = query.get("baseline_id").ok_or_else(|| {
-        warn!("Missing 'baseline_id' query parameter");
-        MyError::MissingQueryParameter("baseline_id".to_string())
+/// Reads a required query parameter and parses it into `T`.
+fn query_param<T: std::str::FromStr>(
+    query: &HashMap<String, String>,
+    name: &str,
+) -> Result<T, MyError>
+where
+    T::Err: Display,
+{
+    let value = query.get(name).ok_or_else(|| {
+        warn!("Missing '{}' query parameter", name);
+        MyError::MissingQueryParameter(name.to_string())
     })?;
 
-    let baseline_id: i64 = baseline_id_str.parse().map_err(|e| {
-        warn!("Invalid 'baseline_id' format: {}", e);
-        MyError::InvalidQueryParameter("baseline_id".to_string())
-    })?;
-
-    let task_baselines = entity::tasks_baselines::Entity::find()
-        .filter(entity::tasks_baselines::Column::BaselineId.eq(baseline_id))
-        .column_as(entity::tasks::Column::Summary, "task_summary")
-        .column_as(entity::tasks::Column::Description, "task_description")
-        .column_as(entity::tasks::Column::Comment, "task_comment")
-        .left_join(entity::tasks::Entity)
-        .into_model::<communication::baselines::TaskBaseline>()
-        .all(db.get_ref())
-        .await
-        .map_err(|db_err| {
-            error!("Database error fetching task baselines: {}", db_err);
-            MyError::DatabaseError
-        })?;
-
-    Ok(HttpResponse::Ok().json(json!(task_baselines)))
-}
-
-trait IntoModelFrequency {
-    fn into_model_frequency(self) -> communication::resources::Frequency;
+    value.parse().map_err(|e| {
+        warn!("Invalid '{}' format: {}", name, e);
+        MyError::InvalidQueryParameter(name.to_string())
+    })
 }
 
-impl IntoModelFrequency for entity::resources::Frequency {
-    fn into_model_frequency(self) -> communication::resources::Frequency {
-        match self {
-            entity::resources::Frequency::Monthly => communication::resources::Frequency::Monthly,
-            entity::resources::Frequency::Weekly => communication::resources::Frequency::Weekly,
-            entity::resources::Frequency::Daily => communication::resources::Frequency::Daily,
-            entity::resources::Frequency::Hourly => communication::resources::Frequency::Hourly,
-            entity::resources::Frequency::Minutely => communication::resources::Frequency::Minutely,
-            entity::resources::Frequency::Secondly => communication::resources::Frequency::Secondly,
-            entity::resources::Frequency::Yearly => communication::resources::Frequency::Yearly,
-        }
+/// Reads an optional query parameter and parses it into `T` when present.
+fn optional_query_param<T: std::str::FromStr>(
+    query: &HashMap<String, String>,
+    name: &str,
+) -> Result<Option<T>, MyError>
+where
+    T::Err: Display,
+{
+    if query.contains_key(name) {
+        query_param(query, name).map(Some)
+    } else {
+        Ok(None)
     }
 }
 
-trait IntoModelResource {
-    fn into_model_resource(self) -> communication::resources::Resource;
-}
-
-impl IntoModelResource for entity::resources::Model {
-    fn into_model_resource(self) -> communication::resources::Resource {
-        communication::resources::Resource {
-            resource_id: self.resource_id,
-            name: self.summary,
-            resource_type_id: self.resource_type_id,
-            description: self.description,
-            comment: self.comment,
-            cost: self.cost,
-            cost_currency: self.cost_currency,
-            billing_frequency: self.billing_frequency.map(|f| f.into_model_frequency()),
-            billing_interval: self.billing_interval,
-            availability: self.availability,
-            capacity: self.capacity,
-            capacity_unit: self.capacity_unit,
-            is_active: self.is_active,
-        }
+impl ResponseError for MyError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.api_error().code.status())
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
     }
-}
-
-trait IntoModelResourceType {
-    fn into_model_resource_type(self) -> communication::resources::ResourceType;
-}
 
-impl IntoModelResourceType for entity::resource_types::Model {
-    fn into_model_resource_type(self) -> communication::resources::ResourceType {
-        communication::resources::ResourceType {
-            resource_type_id: self.resource_type_id,
-            name: self.name,
-            description: self.description,
-            comment: self.comment,
+    fn error_response(&self) -> HttpResponse {
+        let api_error = self.api_error();
+        if api_error.code == ErrorCode::Internal {
+            error!("{}, {}", self.status_code(), api_error);
+        } else {
+            warn!("{}, {}", self.status_code(), api_error);
         }
+        HttpResponse::build(self.status_code()).json(api_error)
     }
 }
-
-async fn get_resources(db: web::Data<DatabaseConnection>) -> Result<HttpResponse, MyError> {
-    let resources = entity::resources::Entity::find()
-        .all(db.get_ref())
-        .await
-        .map_err(|e| {
-            error!("Database error fetching resources: {}", e);
-            MyError::DatabaseError
-        })?;
-    let resource_types = entity::resource_types::Entity::find()
-        .all(db.get_ref())
-        .await
-        .map_err(|e| {
-            error!("Database error fetching resource types: {}", e);
-            MyError::DatabaseError
-        })?;
-
-    let resources: Vec<communication::resources::Resource> = resources
-        .into_iter()
-        .map(|record| record.into_model_resource())
-        .collect();
-    let resource_types: Vec<communication::resources::ResourceType> = resource_types
-        .into_iter()
-        .map(|record| record.into_model_resource_type())
-        .collect();
-
-    let encoded = bitcode::encode(&(resources, resource_types));
-    Ok(HttpResponse::Ok()
-        .content_type("application/octet-stream")
-        .body(encoded))
-}
-
-// #[derive(FromQueryResult, Debug)]
-// struct ResourceAllocationORM(communication::baselines::ResourceAllocation);
-
-async fn get_resource_allocation(
-    db: web::Data<DatabaseConnection>,
-    query: web::Query<HashMap<String, String>>,
-) -> Result<HttpResponse, MyError> {
-    let baseline_id_str = query.get("baseline_id").ok_or_else(|| {
-        warn!("Missing 'baseline_id' query parameter");
-        MyError::JsonParserError
-    })?;
-
-    let baseline_id: i64 = baseline_id_str.parse().map_err(|e| {
-        warn!("Invalid 'baseline_id' format: {}", e);
-        MyError::JsonParserError
-    })?;
-
-    let resource_allocations = entity::resources_baselines::Entity::find()
-        .filter(entity::resources_baselines::Column::BaselineId.eq(baseline_id))
-        .column_as(entity::resources::Column::Summary, "resource_summary")
-        .column_as(entity::tasks::Column::Summary, "task_summary")
-        .column_as(entity::resources::Column::Capacity, "capacity")
-        .column_as(entity::resources::Column::CapacityUnit, "capacity_unit")
-        .left_join(entity::resources::Entity)
-        .left_join(entity::tasks::Entity)
-        .into_model::<communication::baselines::ResourceAllocation>()
-        .all(db.get_ref())
-        .await
-        .map_err(|db_err| {
-            error!("Database error fetching resource allocations: {}", db_err);
-            MyError::DatabaseError
-        })?;
-
-    let encoded = bitcode::encode(&resource_allocations);
-    Ok(HttpResponse::Ok()
-        .content_type("application/octet-stream")
-        .body(encoded))
-}
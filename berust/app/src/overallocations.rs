@@ -0,0 +1,203 @@
+use actix_web::{Result, web};
+use communication::baselines::{OverAllocation, ResourceAllocation, TaskBaseline};
+use sea_orm::DatabaseConnection;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::MyError;
+use crate::baselines::ensure_baseline_exists;
+use crate::resources::{find_resource_allocations, find_resources_by_id};
+use crate::response::Encoded;
+use crate::tasks::find_task_baselines;
+
+/// Allocations exceeding the capacity by less than this are rounding noise.
+const TOLERANCE: f64 = 1e-9;
+
+/// Sums the allocated capacity of every resource over the dates of its tasks and returns each
+/// period in which it exceeds the capacity of the resource.
+///
+/// Periods are split wherever a task starts or finishes, and merged again when the same tasks run on
+/// both sides. Resources without a capacity are never over-allocated.
+pub fn find_overallocations(
+    tasks: &[TaskBaseline],
+    allocations: &[ResourceAllocation],
+    resources: &HashMap<i64, entity::resources::Model>,
+) -> Vec<OverAllocation> {
+    let tasks: HashMap<i64, &TaskBaseline> =
+        tasks.iter().map(|task| (task.task_id, task)).collect();
+
+    let mut loads_of: BTreeMap<i64, Vec<(&TaskBaseline, f64)>> = BTreeMap::new();
+    for allocation in allocations {
+        if let Some(task) = tasks.get(&allocation.task_id) {
+            loads_of
+                .entry(allocation.resource_id)
+                .or_default()
+                .push((task, allocation.capacity_allocated.unwrap_or(0.)));
+        }
+    }
+
+    let mut overallocations: Vec<OverAllocation> = Vec::new();
+    for (resource_id, loads) in loads_of {
+        let Some((resource, capacity)) = resources
+            .get(&resource_id)
+            .and_then(|resource| resource.capacity.map(|capacity| (resource, capacity)))
+        else {
+            continue;
+        };
+
+        let mut instants: Vec<_> = loads
+            .iter()
+            .flat_map(|(task, _)| [task.start, task.finish])
+            .collect();
+        instants.sort_unstable();
+        instants.dedup();
+
+        for period in instants.windows(2) {
+            let (start, finish) = (period[0], period[1]);
+            // Tasks start and finish on instants, so each covers a period entirely or not at all.
+            let running: Vec<&(&TaskBaseline, f64)> = loads
+                .iter()
+                .filter(|(task, _)| task.start <= start && finish <= task.finish)
+                .collect();
+            let capacity_allocated: f64 = running.iter().map(|(_, load)| load).sum();
+            if capacity_allocated <= capacity + TOLERANCE {
+                continue;
+            }
+            let mut task_ids: Vec<i64> = running.iter().map(|(task, _)| task.task_id).collect();
+            task_ids.sort_unstable();
+            task_ids.dedup();
+
+            if let Some(last) = overallocations.last_mut()
+                && last.resource_id == resource_id
+                && last.finish == start
+                && last.task_ids == task_ids
+            {
+                last.finish = finish;
+                continue;
+            }
+            overallocations.push(OverAllocation {
+                resource_id,
+                resource_summary: resource.summary.clone(),
+                start,
+                finish,
+                capacity,
+                capacity_allocated,
+                task_ids,
+            });
+        }
+    }
+    overallocations
+}
+
+/// Periods in which resources of a baseline are over-allocated, optionally for one `resource_id`.
+pub async fn get_overallocations(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<Encoded<Vec<OverAllocation>>, MyError> {
+    let baseline_id = path.into_inner();
+    let resource_id: Option<i64> = crate::optional_query_param(&query, "resource_id")?;
+    ensure_baseline_exists(db.get_ref(), baseline_id).await?;
+
+    let tasks = find_task_baselines(db.get_ref(), baseline_id).await?;
+    let allocations: Vec<ResourceAllocation> = find_resource_allocations(db.get_ref(), baseline_id)
+        .await?
+        .into_iter()
+        .filter(|allocation| resource_id.is_none_or(|id| allocation.resource_id == id))
+        .collect();
+    let resources = find_resources_by_id(
+        db.get_ref(),
+        allocations.iter().map(|allocation| allocation.resource_id),
+    )
+    .await?;
+
+    Ok(Encoded::ok(find_overallocations(
+        &tasks,
+        &allocations,
+        &resources,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{allocation, at, resource, task};
+
+    fn resources(capacity: Option<f64>) -> HashMap<i64, entity::resources::Model> {
+        let mut resource = resource(10, 100., None);
+        resource.capacity = capacity;
+        HashMap::from([(10, resource)])
+    }
+
+    fn periods(overallocations: &[OverAllocation]) -> Vec<(i64, i64, Vec<i64>)> {
+        overallocations
+            .iter()
+            .map(|overallocation| {
+                (
+                    (overallocation.start.naive() - at(0)).num_hours(),
+                    (overallocation.finish.naive() - at(0)).num_hours(),
+                    overallocation.task_ids.clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn only_the_overlap_of_tasks_is_overallocated() {
+        // 3 starts when 2 finishes, so the two never add up.
+        let tasks = [task(1, 0, 10), task(2, 5, 15), task(3, 15, 20)];
+        let allocations = [
+            allocation(1, 10, 0.6),
+            allocation(2, 10, 0.6),
+            allocation(3, 10, 1.),
+        ];
+        let overallocations = find_overallocations(&tasks, &allocations, &resources(Some(1.)));
+        assert_eq!(periods(&overallocations), vec![(5, 10, vec![1, 2])]);
+        assert!((overallocations[0].capacity_allocated - 1.2).abs() < 1e-9);
+        assert_eq!(overallocations[0].capacity, 1.);
+    }
+
+    #[test]
+    fn periods_with_the_same_tasks_are_merged() {
+        // The milestone at hour 4 splits the period without running in it.
+        let mut milestone = task(3, 4, 4);
+        milestone.milestone = true;
+        let tasks = [task(1, 0, 10), task(2, 0, 10), milestone];
+        let allocations = [
+            allocation(1, 10, 0.6),
+            allocation(2, 10, 0.6),
+            allocation(3, 10, 1.),
+        ];
+        let overallocations = find_overallocations(&tasks, &allocations, &resources(Some(1.)));
+        assert_eq!(periods(&overallocations), vec![(0, 10, vec![1, 2])]);
+    }
+
+    #[test]
+    fn periods_with_other_tasks_stay_apart() {
+        let tasks = [task(1, 0, 10), task(2, 0, 10), task(3, 5, 10)];
+        let allocations = [
+            allocation(1, 10, 0.6),
+            allocation(2, 10, 0.6),
+            allocation(3, 10, 0.1),
+        ];
+        let overallocations = find_overallocations(&tasks, &allocations, &resources(Some(1.)));
+        assert_eq!(
+            periods(&overallocations),
+            vec![(0, 5, vec![1, 2]), (5, 10, vec![1, 2, 3])]
+        );
+    }
+
+    #[test]
+    fn allocations_up_to_the_capacity_are_fine() {
+        let tasks = [task(1, 0, 10), task(2, 0, 10)];
+        let allocations = [allocation(1, 10, 0.7), allocation(2, 10, 0.3)];
+        assert!(find_overallocations(&tasks, &allocations, &resources(Some(1.))).is_empty());
+    }
+
+    #[test]
+    fn resources_without_a_capacity_are_never_overallocated() {
+        let tasks = [task(1, 0, 10), task(2, 0, 10)];
+        let allocations = [allocation(1, 10, 5.), allocation(2, 10, 5.)];
+        assert!(find_overallocations(&tasks, &allocations, &resources(None)).is_empty());
+        assert!(find_overallocations(&tasks, &allocations, &HashMap::new()).is_empty());
+    }
+}
@@ -0,0 +1,75 @@
+use communication::paging::Page;
+use std::collections::HashMap;
+
+use crate::{MyError, optional_query_param};
+
+const DEFAULT_LIMIT: u64 = 100;
+const MAX_LIMIT: u64 = 1000;
+
+/// The `cursor`, `limit` and `sort` query parameters shared by all paged list endpoints.
+///
+/// The cursor is the primary key of the last row of the previous page; rows are read after it in
+/// the requested order, with the primary key breaking ties, so pages stay stable while rows change.
+pub struct PageRequest<'a> {
+    pub cursor: Option<i64>,
+    pub limit: u64,
+    pub sort: &'a str,
+    pub descending: bool,
+}
+
+impl<'a> PageRequest<'a> {
+    /// `sort` is one of `sort_keys`, prefixed with `-` for descending order; the first key is the default.
+    pub fn from_query(
+        query: &HashMap<String, String>,
+        sort_keys: &[&'a str],
+    ) -> Result<Self, MyError> {
+        let limit = optional_query_param(query, "limit")?.unwrap_or(DEFAULT_LIMIT);
+        if limit == 0 || limit > MAX_LIMIT {
+            return Err(MyError::InvalidQueryParameter("limit".to_string()));
+        }
+
+        let (sort, descending) = match query.get("sort") {
+            Some(sort) => match sort.strip_prefix('-') {
+                Some(sort) => (sort, true),
+                None => (sort.as_str(), false),
+            },
+            None => (sort_keys[0], false),
+        };
+        let sort = sort_keys
+            .iter()
+            .find(|key| **key == sort)
+            .ok_or_else(|| MyError::InvalidQueryParameter("sort".to_string()))?;
+
+        Ok(PageRequest {
+            cursor: optional_query_param(query, "cursor")?,
+            limit,
+            sort,
+            descending,
+        })
+    }
+
+    /// Builds the page from rows fetched with a limit of `self.limit + 1`; the extra row only tells
+    /// whether another page follows.
+    pub fn page<T>(&self, mut items: Vec<T>, total: u64, id: impl Fn(&T) -> i64) -> Page<T> {
+        let next_cursor = if items.len() as u64 > self.limit {
+            items.truncate(self.limit as usize);
+            items.last().map(|item| id(item).to_string())
+        } else {
+            None
+        };
+
+        Page {
+            items,
+            next_cursor,
+            total,
+        }
+    }
+}
+
+/// Escapes the `LIKE` wildcards in user input, so it only matches literally.
+pub fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
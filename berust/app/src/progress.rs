@@ -0,0 +1,404 @@
+use actix_web::{HttpResponse, Result, web};
+use chrono::NaiveDate;
+use communication::actuals::TaskProgress;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder,
+};
+use std::collections::HashMap;
+use tracing::error;
+
+use crate::MyError;
+use crate::baselines::current_baseline_id;
+use crate::exchange_rates::is_currency_code;
+use crate::response::{Encoded, Payload};
+
+pub trait IntoModelTaskProgress {
+    fn into_model_task_progress(self) -> TaskProgress;
+}
+
+impl IntoModelTaskProgress for entity::task_progress::Model {
+    fn into_model_task_progress(self) -> TaskProgress {
+        TaskProgress {
+            task_progress_id: self.task_progress_id,
+            task_id: self.task_id,
+            status_date: self.status_date.into(),
+            percent_complete: self.percent_complete,
+            actual_start: self.actual_start.map(|actual_start| actual_start.into()),
+            actual_finish: self.actual_finish.map(|actual_finish| actual_finish.into()),
+            remaining_work: self.remaining_work,
+            actual_cost: self.actual_cost,
+            cost_currency: self.cost_currency,
+        }
+    }
+}
+
+async fn find_progress<C: ConnectionTrait>(
+    db: &C,
+    task_progress_id: i64,
+) -> Result<entity::task_progress::Model, MyError> {
+    entity::task_progress::Entity::find_by_id(task_progress_id)
+        .one(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task progress: {}", e);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("task progress {task_progress_id}")))
+}
+
+/// Progress assessments made on or before `status_date`, or all of them without one, ordered by
+/// status date.
+pub async fn find_progress_history<C: ConnectionTrait>(
+    db: &C,
+    status_date: Option<NaiveDate>,
+) -> Result<Vec<TaskProgress>, MyError> {
+    let mut select = entity::task_progress::Entity::find();
+    if let Some(status_date) = status_date {
+        select = select.filter(entity::task_progress::Column::StatusDate.lte(status_date));
+    }
+    Ok(select
+        .order_by_asc(entity::task_progress::Column::StatusDate)
+        .all(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task progress: {}", e);
+            MyError::DatabaseError
+        })?
+        .into_iter()
+        .map(|record| record.into_model_task_progress())
+        .collect())
+}
+
+/// The latest assessment of every task in `history` made on or before `status_date`, by task id.
+pub fn latest_progress(
+    history: &[TaskProgress],
+    status_date: NaiveDate,
+) -> HashMap<i64, &TaskProgress> {
+    // History is chronological, so later assessments replace earlier ones.
+    history
+        .iter()
+        .take_while(|progress| progress.status_date.naive() <= status_date)
+        .map(|progress| (progress.task_id, progress))
+        .collect()
+}
+
+/// The latest progress of every task assessed on or before `status_date`, or the latest of all
+/// without one, by task id.
+pub async fn find_latest_progress<C: ConnectionTrait>(
+    db: &C,
+    status_date: Option<NaiveDate>,
+) -> Result<HashMap<i64, TaskProgress>, MyError> {
+    let history = find_progress_history(db, status_date).await?;
+    Ok(
+        latest_progress(&history, status_date.unwrap_or(NaiveDate::MAX))
+            .into_iter()
+            .map(|(task_id, progress)| (task_id, progress.clone()))
+            .collect(),
+    )
+}
+
+/// Progress assessments, optionally of one `task_id` and with status dates `from` to `to`, both
+/// inclusive, ordered by status date.
+pub async fn get_task_progress(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<Encoded<Vec<TaskProgress>>, MyError> {
+    let task_id: Option<i64> = crate::optional_query_param(&query, "task_id")?;
+    let from: Option<NaiveDate> = crate::optional_query_param(&query, "from")?;
+    let to: Option<NaiveDate> = crate::optional_query_param(&query, "to")?;
+
+    let mut select = entity::task_progress::Entity::find();
+    if let Some(task_id) = task_id {
+        select = select.filter(entity::task_progress::Column::TaskId.eq(task_id));
+    }
+    if let Some(from) = from {
+        select = select.filter(entity::task_progress::Column::StatusDate.gte(from));
+    }
+    if let Some(to) = to {
+        select = select.filter(entity::task_progress::Column::StatusDate.lte(to));
+    }
+
+    let progress = select
+        .order_by_asc(entity::task_progress::Column::StatusDate)
+        .order_by_asc(entity::task_progress::Column::TaskId)
+        .all(db.get_ref())
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task progress: {}", e);
+            MyError::DatabaseError
+        })?
+        .into_iter()
+        .map(|record| record.into_model_task_progress())
+        .collect();
+
+    Ok(Encoded::ok(progress))
+}
+
+/// Checks that the figures are consistent with each other and with the status date, that the task
+/// is a leaf task planned in the current baseline and that it has no other assessment on the same
+/// day. Summary tasks take their progress from their subtasks.
+async fn validate_progress<C: ConnectionTrait>(
+    db: &C,
+    task_progress_id: Option<i64>,
+    progress: &TaskProgress,
+) -> Result<(), MyError> {
+    if !(0. ..=100.).contains(&progress.percent_complete) {
+        return Err(MyError::InvalidField(
+            "percent_complete",
+            "percent_complete must be between 0 and 100".to_string(),
+        ));
+    }
+    if progress
+        .remaining_work
+        .is_some_and(|remaining| remaining.is_nan() || remaining < 0.)
+    {
+        return Err(MyError::InvalidField(
+            "remaining_work",
+            "remaining_work must not be negative".to_string(),
+        ));
+    }
+    match progress.actual_start {
+        Some(actual_start) if actual_start > progress.status_date => {
+            return Err(MyError::InvalidField(
+                "actual_start",
+                "actual_start must not be after the status date".to_string(),
+            ));
+        }
+        None if progress.percent_complete > 0. => {
+            return Err(MyError::InvalidField(
+                "actual_start",
+                "a task with progress must have an actual_start".to_string(),
+            ));
+        }
+        _ => {}
+    }
+    match progress.actual_finish {
+        Some(actual_finish)
+            if progress
+                .actual_start
+                .is_none_or(|actual_start| actual_finish < actual_start)
+                || actual_finish > progress.status_date =>
+        {
+            return Err(MyError::InvalidField(
+                "actual_finish",
+                "actual_finish must be between actual_start and the status date".to_string(),
+            ));
+        }
+        Some(_) if progress.percent_complete < 100. => {
+            return Err(MyError::InvalidField(
+                "actual_finish",
+                "only a task 100 percent complete has an actual_finish".to_string(),
+            ));
+        }
+        Some(_)
+            if progress
+                .remaining_work
+                .is_some_and(|remaining| remaining > 0.) =>
+        {
+            return Err(MyError::InvalidField(
+                "remaining_work",
+                "a finished task has no remaining_work".to_string(),
+            ));
+        }
+        None if progress.percent_complete == 100. => {
+            return Err(MyError::InvalidField(
+                "actual_finish",
+                "a task 100 percent complete must have an actual_finish".to_string(),
+            ));
+        }
+        _ => {}
+    }
+    match (progress.actual_cost, &progress.cost_currency) {
+        (Some(actual_cost), Some(cost_currency)) => {
+            if actual_cost.is_nan() || actual_cost < 0. {
+                return Err(MyError::InvalidField(
+                    "actual_cost",
+                    "actual_cost must not be negative".to_string(),
+                ));
+            }
+            if !is_currency_code(cost_currency) {
+                return Err(MyError::InvalidField(
+                    "cost_currency",
+                    format!("invalid cost_currency: {cost_currency}"),
+                ));
+            }
+        }
+        (None, None) => {}
+        _ => {
+            return Err(MyError::InvalidField(
+                "cost_currency",
+                "actual_cost and cost_currency must be given together".to_string(),
+            ));
+        }
+    }
+
+    let baseline_id = current_baseline_id(db).await?;
+    let planned = entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::BaselineId.eq(baseline_id))
+        .filter(entity::tasks_baselines::Column::TaskId.eq(progress.task_id))
+        .count(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task baseline: {}", e);
+            MyError::DatabaseError
+        })?;
+    if planned == 0 {
+        return Err(MyError::InvalidField(
+            "task_id",
+            format!(
+                "task {} is not planned in the current baseline {}",
+                progress.task_id, baseline_id
+            ),
+        ));
+    }
+    let subtasks = entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::BaselineId.eq(baseline_id))
+        .filter(entity::tasks_baselines::Column::Parent.eq(progress.task_id))
+        .filter(entity::tasks_baselines::Column::TaskId.ne(progress.task_id))
+        .count(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching subtasks: {}", e);
+            MyError::DatabaseError
+        })?;
+    if subtasks > 0 {
+        return Err(MyError::InvalidField(
+            "task_id",
+            format!(
+                "task {} is a summary task, its progress comes from its subtasks",
+                progress.task_id
+            ),
+        ));
+    }
+
+    let mut same_date = entity::task_progress::Entity::find()
+        .filter(entity::task_progress::Column::TaskId.eq(progress.task_id))
+        .filter(entity::task_progress::Column::StatusDate.eq(progress.status_date.naive()));
+    if let Some(task_progress_id) = task_progress_id {
+        same_date =
+            same_date.filter(entity::task_progress::Column::TaskProgressId.ne(task_progress_id));
+    }
+    let same_date = same_date.count(db).await.map_err(|e| {
+        error!("Database error fetching task progress: {}", e);
+        MyError::DatabaseError
+    })?;
+    if same_date > 0 {
+        return Err(MyError::Conflict(format!(
+            "task {} already has progress on {}",
+            progress.task_id, progress.status_date
+        )));
+    }
+    Ok(())
+}
+
+pub async fn create_task_progress(
+    db: web::Data<DatabaseConnection>,
+    payload: Payload<TaskProgress>,
+) -> Result<Encoded<TaskProgress>, MyError> {
+    let progress = payload.into_inner();
+    validate_progress(db.get_ref(), None, &progress).await?;
+
+    let inserted = entity::task_progress::ActiveModel {
+        task_id: Set(progress.task_id),
+        status_date: Set(progress.status_date.naive()),
+        percent_complete: Set(progress.percent_complete),
+        actual_start: Set(progress
+            .actual_start
+            .map(|actual_start| actual_start.naive())),
+        actual_finish: Set(progress
+            .actual_finish
+            .map(|actual_finish| actual_finish.naive())),
+        remaining_work: Set(progress.remaining_work),
+        actual_cost: Set(progress.actual_cost),
+        cost_currency: Set(progress.cost_currency),
+        ..Default::default()
+    }
+    .insert(db.get_ref())
+    .await
+    .map_err(|e| {
+        error!("Database error inserting task progress: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::created(inserted.into_model_task_progress()))
+}
+
+pub async fn update_task_progress(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    payload: Payload<TaskProgress>,
+) -> Result<Encoded<TaskProgress>, MyError> {
+    let task_progress_id = path.into_inner();
+    let progress = payload.into_inner();
+
+    let record = find_progress(db.get_ref(), task_progress_id).await?;
+    validate_progress(db.get_ref(), Some(task_progress_id), &progress).await?;
+
+    let mut record = record.into_active_model();
+    record.task_id = Set(progress.task_id);
+    record.status_date = Set(progress.status_date.naive());
+    record.percent_complete = Set(progress.percent_complete);
+    record.actual_start = Set(progress
+        .actual_start
+        .map(|actual_start| actual_start.naive()));
+    record.actual_finish = Set(progress
+        .actual_finish
+        .map(|actual_finish| actual_finish.naive()));
+    record.remaining_work = Set(progress.remaining_work);
+    record.actual_cost = Set(progress.actual_cost);
+    record.cost_currency = Set(progress.cost_currency);
+
+    let updated = record.update(db.get_ref()).await.map_err(|e| {
+        error!("Database error updating task progress: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::ok(updated.into_model_task_progress()))
+}
+
+pub async fn delete_task_progress(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, MyError> {
+    let task_progress_id = path.into_inner();
+    find_progress(db.get_ref(), task_progress_id).await?;
+
+    entity::task_progress::Entity::delete_by_id(task_progress_id)
+        .exec(db.get_ref())
+        .await
+        .map_err(|e| {
+            error!("Database error deleting task progress: {}", e);
+            MyError::DatabaseError
+        })?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{day, progress};
+
+    #[test]
+    fn the_latest_assessment_by_the_status_date_counts() {
+        let history = [
+            progress(1, 5, 10.),
+            progress(2, 5, 50.),
+            progress(1, 10, 40.),
+            progress(1, 15, 90.),
+        ];
+        let percent = |status_date| {
+            let mut percent: Vec<(i64, f64)> = latest_progress(&history, status_date)
+                .into_iter()
+                .map(|(task_id, progress)| (task_id, progress.percent_complete))
+                .collect();
+            percent.sort_by_key(|(task_id, _)| *task_id);
+            percent
+        };
+        assert_eq!(percent(day(4)), Vec::new());
+        assert_eq!(percent(day(10)), vec![(1, 40.), (2, 50.)]);
+        assert_eq!(percent(day(14)), vec![(1, 40.), (2, 50.)]);
+        assert_eq!(percent(day(31)), vec![(1, 90.), (2, 50.)]);
+    }
+}
@@ -0,0 +1,799 @@
+use actix_web::{HttpResponse, Result, web};
+use communication::paging::Page;
+use sea_orm::sea_query::{Expr, extension::postgres::PgExpr};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter, QuerySelect, TransactionTrait,
+};
+use std::collections::HashMap;
+use tracing::error;
+
+use crate::MyError;
+use crate::baselines::ensure_baseline_editable;
+use crate::calendars::ensure_calendar_exists;
+use crate::exchange_rates::is_currency_code;
+use crate::paging::{PageRequest, escape_like};
+use crate::response::{Encoded, Payload};
+use crate::team_members::ensure_retype_allowed;
+
+pub trait IntoModelFrequency {
+    fn into_model_frequency(self) -> communication::resources::Frequency;
+}
+
+impl IntoModelFrequency for entity::resources::Frequency {
+    fn into_model_frequency(self) -> communication::resources::Frequency {
+        match self {
+            entity::resources::Frequency::Monthly => communication::resources::Frequency::Monthly,
+            entity::resources::Frequency::Weekly => communication::resources::Frequency::Weekly,
+            entity::resources::Frequency::Daily => communication::resources::Frequency::Daily,
+            entity::resources::Frequency::Hourly => communication::resources::Frequency::Hourly,
+            entity::resources::Frequency::Minutely => communication::resources::Frequency::Minutely,
+            entity::resources::Frequency::Secondly => communication::resources::Frequency::Secondly,
+            entity::resources::Frequency::Yearly => communication::resources::Frequency::Yearly,
+        }
+    }
+}
+
+pub trait IntoEntityFrequency {
+    fn into_entity_frequency(self) -> entity::resources::Frequency;
+}
+
+impl IntoEntityFrequency for communication::resources::Frequency {
+    fn into_entity_frequency(self) -> entity::resources::Frequency {
+        match self {
+            communication::resources::Frequency::Monthly => entity::resources::Frequency::Monthly,
+            communication::resources::Frequency::Weekly => entity::resources::Frequency::Weekly,
+            communication::resources::Frequency::Daily => entity::resources::Frequency::Daily,
+            communication::resources::Frequency::Hourly => entity::resources::Frequency::Hourly,
+            communication::resources::Frequency::Minutely => entity::resources::Frequency::Minutely,
+            communication::resources::Frequency::Secondly => entity::resources::Frequency::Secondly,
+            communication::resources::Frequency::Yearly => entity::resources::Frequency::Yearly,
+        }
+    }
+}
+
+pub trait IntoModelResource {
+    fn into_model_resource(self) -> communication::resources::Resource;
+}
+
+impl IntoModelResource for entity::resources::Model {
+    fn into_model_resource(self) -> communication::resources::Resource {
+        communication::resources::Resource {
+            resource_id: self.resource_id,
+            name: self.summary,
+            resource_type_id: self.resource_type_id,
+            description: self.description,
+            comment: self.comment,
+            cost: self.cost,
+            cost_currency: self.cost_currency,
+            billing_frequency: self.billing_frequency.map(|f| f.into_model_frequency()),
+            billing_interval: self.billing_interval,
+            calendar_id: self.calendar_id,
+            capacity: self.capacity,
+            capacity_unit: self.capacity_unit,
+            is_active: self.is_active,
+        }
+    }
+}
+
+pub trait IntoModelResourceType {
+    fn into_model_resource_type(self) -> communication::resources::ResourceType;
+}
+
+impl IntoModelResourceType for entity::resource_types::Model {
+    fn into_model_resource_type(self) -> communication::resources::ResourceType {
+        communication::resources::ResourceType {
+            resource_type_id: self.resource_type_id,
+            name: self.name,
+            description: self.description,
+            comment: self.comment,
+            is_active: self.is_active,
+        }
+    }
+}
+
+/// Lists resources one page at a time, together with all resource types to resolve them against.
+///
+/// Optional filters: `resource_type_id`, `is_active` and `q` (case-insensitive text in the name).
+/// Sort keys are `name` and `resource_id`.
+pub async fn get_resources(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<
+    Encoded<(
+        Page<communication::resources::Resource>,
+        Vec<communication::resources::ResourceType>,
+    )>,
+    MyError,
+> {
+    let page_request = PageRequest::from_query(&query, &["name", "resource_id"])?;
+
+    let mut select = entity::resources::Entity::find();
+    if let Some(resource_type_id) = crate::optional_query_param::<i64>(&query, "resource_type_id")?
+    {
+        select = select.filter(entity::resources::Column::ResourceTypeId.eq(resource_type_id));
+    }
+    if let Some(is_active) = crate::optional_query_param::<bool>(&query, "is_active")? {
+        select = select.filter(entity::resources::Column::IsActive.eq(is_active));
+    }
+    if let Some(text) = crate::optional_query_param::<String>(&query, "q")? {
+        select = select.filter(
+            Expr::col((
+                entity::resources::Entity,
+                entity::resources::Column::Summary,
+            ))
+            .ilike(format!("%{}%", escape_like(&text))),
+        );
+    }
+
+    let total = select.clone().count(db.get_ref()).await.map_err(|e| {
+        error!("Database error counting resources: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let after =
+        match page_request.cursor {
+            Some(cursor) => Some(find_resource(db.get_ref(), cursor).await.map_err(
+                |e| match e {
+                    MyError::NotFound(_) => MyError::InvalidQueryParameter("cursor".to_string()),
+                    e => e,
+                },
+            )?),
+            None => None,
+        };
+
+    let mut cursor = match page_request.sort {
+        "resource_id" => {
+            let mut cursor = select.cursor_by(entity::resources::Column::ResourceId);
+            if let Some(after) = &after {
+                cursor.after(after.resource_id);
+            }
+            cursor
+        }
+        _ => {
+            let mut cursor = select.cursor_by((
+                entity::resources::Column::Summary,
+                entity::resources::Column::ResourceId,
+            ));
+            if let Some(after) = &after {
+                cursor.after((after.summary.clone(), after.resource_id));
+            }
+            cursor
+        }
+    };
+    if page_request.descending {
+        cursor.desc();
+    }
+    cursor.first(page_request.limit + 1);
+
+    let resources = cursor.all(db.get_ref()).await.map_err(|e| {
+        error!("Database error fetching resources: {}", e);
+        MyError::DatabaseError
+    })?;
+    let resource_types = entity::resource_types::Entity::find()
+        .all(db.get_ref())
+        .await
+        .map_err(|e| {
+            error!("Database error fetching resource types: {}", e);
+            MyError::DatabaseError
+        })?;
+
+    let resources = page_request.page(
+        resources
+            .into_iter()
+            .map(|record| record.into_model_resource())
+            .collect(),
+        total,
+        |resource| resource.resource_id,
+    );
+    let resource_types: Vec<communication::resources::ResourceType> = resource_types
+        .into_iter()
+        .map(|record| record.into_model_resource_type())
+        .collect();
+
+    Ok(Encoded::ok((resources, resource_types)))
+}
+
+// #[derive(FromQueryResult, Debug)]
+// struct ResourceAllocationORM(communication::baselines::ResourceAllocation);
+
+pub async fn get_resource_allocation(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<Encoded<Vec<communication::baselines::ResourceAllocation>>, MyError> {
+    let baseline_id: i64 = crate::query_param(&query, "baseline_id")?;
+
+    let resource_allocations = find_resource_allocations(db.get_ref(), baseline_id).await?;
+
+    Ok(Encoded::ok(resource_allocations))
+}
+
+async fn find_resource_allocation<C: ConnectionTrait>(
+    db: &C,
+    resource_baseline_id: i64,
+) -> Result<communication::baselines::ResourceAllocation, MyError> {
+    entity::resources_baselines::Entity::find_by_id(resource_baseline_id)
+        .column_as(entity::resources::Column::Summary, "resource_summary")
+        .column_as(entity::tasks::Column::Summary, "task_summary")
+        .column_as(entity::resources::Column::Capacity, "capacity")
+        .column_as(entity::resources::Column::CapacityUnit, "capacity_unit")
+        .left_join(entity::resources::Entity)
+        .left_join(entity::tasks::Entity)
+        .into_model::<communication::baselines::ResourceAllocation>()
+        .one(db)
+        .await
+        .map_err(|db_err| {
+            error!("Database error fetching resource allocation: {}", db_err);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("resource allocation {resource_baseline_id}")))
+}
+
+pub async fn find_resource_allocations<C: ConnectionTrait>(
+    db: &C,
+    baseline_id: i64,
+) -> Result<Vec<communication::baselines::ResourceAllocation>, MyError> {
+    entity::resources_baselines::Entity::find()
+        .filter(entity::resources_baselines::Column::BaselineId.eq(baseline_id))
+        .column_as(entity::resources::Column::Summary, "resource_summary")
+        .column_as(entity::tasks::Column::Summary, "task_summary")
+        .column_as(entity::resources::Column::Capacity, "capacity")
+        .column_as(entity::resources::Column::CapacityUnit, "capacity_unit")
+        .left_join(entity::resources::Entity)
+        .left_join(entity::tasks::Entity)
+        .into_model::<communication::baselines::ResourceAllocation>()
+        .all(db)
+        .await
+        .map_err(|db_err| {
+            error!("Database error fetching resource allocations: {}", db_err);
+            MyError::DatabaseError
+        })
+}
+
+/// Reads the given resources keyed by id.
+pub async fn find_resources_by_id<C: ConnectionTrait>(
+    db: &C,
+    resource_ids: impl IntoIterator<Item = i64>,
+) -> Result<HashMap<i64, entity::resources::Model>, MyError> {
+    Ok(entity::resources::Entity::find()
+        .filter(entity::resources::Column::ResourceId.is_in(resource_ids))
+        .all(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching resources: {}", e);
+            MyError::DatabaseError
+        })?
+        .into_iter()
+        .map(|resource| (resource.resource_id, resource))
+        .collect())
+}
+
+fn validate_resource(resource: &communication::resources::Resource) -> Result<(), MyError> {
+    if resource.name.trim().is_empty() {
+        return Err(MyError::InvalidField(
+            "name",
+            "name must not be empty".to_string(),
+        ));
+    }
+    if !is_currency_code(&resource.cost_currency) {
+        return Err(MyError::InvalidField(
+            "cost_currency",
+            format!("invalid cost_currency: {}", resource.cost_currency),
+        ));
+    }
+    if resource.cost.is_some_and(|cost| cost < 0.) {
+        return Err(MyError::InvalidField(
+            "cost",
+            "cost must not be negative".to_string(),
+        ));
+    }
+    if let Some(billing_interval) = resource.billing_interval {
+        if resource.billing_frequency.is_none() {
+            return Err(MyError::InvalidField(
+                "billing_interval",
+                "billing_interval requires billing_frequency".to_string(),
+            ));
+        }
+        if billing_interval <= 0 {
+            return Err(MyError::InvalidField(
+                "billing_interval",
+                "billing_interval must be positive".to_string(),
+            ));
+        }
+    }
+    if resource.capacity.is_some_and(|capacity| capacity < 0.) {
+        return Err(MyError::InvalidField(
+            "capacity",
+            "capacity must not be negative".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+async fn ensure_resource_type_active<C: ConnectionTrait>(
+    db: &C,
+    resource_type_id: i64,
+) -> Result<(), MyError> {
+    let resource_type = entity::resource_types::Entity::find_by_id(resource_type_id)
+        .one(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching resource type: {}", e);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| {
+            MyError::InvalidField(
+                "resource_type_id",
+                format!("unknown resource_type_id: {resource_type_id}"),
+            )
+        })?;
+    if !resource_type.is_active {
+        return Err(MyError::InvalidField(
+            "resource_type_id",
+            format!("resource type {resource_type_id} is not active"),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn find_resource<C: ConnectionTrait>(
+    db: &C,
+    resource_id: i64,
+) -> Result<entity::resources::Model, MyError> {
+    entity::resources::Entity::find_by_id(resource_id)
+        .one(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching resource: {}", e);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("resource {resource_id}")))
+}
+
+pub async fn find_resource_type<C: ConnectionTrait>(
+    db: &C,
+    resource_type_id: i64,
+) -> Result<entity::resource_types::Model, MyError> {
+    entity::resource_types::Entity::find_by_id(resource_type_id)
+        .one(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching resource type: {}", e);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("resource type {resource_type_id}")))
+}
+
+pub async fn create_resource(
+    db: web::Data<DatabaseConnection>,
+    payload: Payload<communication::resources::Resource>,
+) -> Result<Encoded<communication::resources::Resource>, MyError> {
+    let resource = payload.into_inner();
+    validate_resource(&resource)?;
+    ensure_resource_type_active(db.get_ref(), resource.resource_type_id).await?;
+    if let Some(calendar_id) = resource.calendar_id {
+        ensure_calendar_exists(db.get_ref(), calendar_id, "calendar_id").await?;
+    }
+
+    let inserted = entity::resources::ActiveModel {
+        summary: Set(resource.name),
+        resource_type_id: Set(resource.resource_type_id),
+        description: Set(resource.description),
+        comment: Set(resource.comment),
+        cost: Set(resource.cost),
+        cost_currency: Set(resource.cost_currency),
+        billing_frequency: Set(resource
+            .billing_frequency
+            .map(|f| f.into_entity_frequency())),
+        billing_interval: Set(resource.billing_interval),
+        calendar_id: Set(resource.calendar_id),
+        capacity: Set(resource.capacity),
+        capacity_unit: Set(resource.capacity_unit),
+        is_active: Set(resource.is_active),
+        ..Default::default()
+    }
+    .insert(db.get_ref())
+    .await
+    .map_err(|e| {
+        error!("Database error inserting resource: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::created(inserted.into_model_resource()))
+}
+
+pub async fn update_resource(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    payload: Payload<communication::resources::Resource>,
+) -> Result<Encoded<communication::resources::Resource>, MyError> {
+    let resource_id = path.into_inner();
+    let resource = payload.into_inner();
+    validate_resource(&resource)?;
+
+    let mut record = find_resource(db.get_ref(), resource_id)
+        .await?
+        .into_active_model();
+    if record.resource_type_id.as_ref() != &resource.resource_type_id {
+        ensure_resource_type_active(db.get_ref(), resource.resource_type_id).await?;
+        ensure_retype_allowed(db.get_ref(), resource_id, resource.resource_type_id).await?;
+    }
+    if let Some(calendar_id) = resource.calendar_id {
+        ensure_calendar_exists(db.get_ref(), calendar_id, "calendar_id").await?;
+    }
+
+    record.summary = Set(resource.name);
+    record.resource_type_id = Set(resource.resource_type_id);
+    record.description = Set(resource.description);
+    record.comment = Set(resource.comment);
+    record.cost = Set(resource.cost);
+    record.cost_currency = Set(resource.cost_currency);
+    record.billing_frequency = Set(resource
+        .billing_frequency
+        .map(|f| f.into_entity_frequency()));
+    record.billing_interval = Set(resource.billing_interval);
+    record.calendar_id = Set(resource.calendar_id);
+    record.capacity = Set(resource.capacity);
+    record.capacity_unit = Set(resource.capacity_unit);
+    record.is_active = Set(resource.is_active);
+
+    let updated = record.update(db.get_ref()).await.map_err(|e| {
+        error!("Database error updating resource: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::ok(updated.into_model_resource()))
+}
+
+/// Resources stay referenced by baselines, so they are deactivated rather than deleted.
+pub async fn deactivate_resource(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+) -> Result<Encoded<communication::resources::Resource>, MyError> {
+    let resource_id = path.into_inner();
+
+    let mut record = find_resource(db.get_ref(), resource_id)
+        .await?
+        .into_active_model();
+    record.is_active = Set(false);
+
+    let updated = record.update(db.get_ref()).await.map_err(|e| {
+        error!("Database error deactivating resource: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::ok(updated.into_model_resource()))
+}
+
+pub async fn create_resource_type(
+    db: web::Data<DatabaseConnection>,
+    payload: Payload<communication::resources::ResourceType>,
+) -> Result<Encoded<communication::resources::ResourceType>, MyError> {
+    let resource_type = payload.into_inner();
+    if resource_type.name.trim().is_empty() {
+        return Err(MyError::InvalidField(
+            "name",
+            "name must not be empty".to_string(),
+        ));
+    }
+
+    let inserted = entity::resource_types::ActiveModel {
+        name: Set(resource_type.name),
+        description: Set(resource_type.description),
+        comment: Set(resource_type.comment),
+        is_active: Set(resource_type.is_active),
+        ..Default::default()
+    }
+    .insert(db.get_ref())
+    .await
+    .map_err(|e| {
+        error!("Database error inserting resource type: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::created(inserted.into_model_resource_type()))
+}
+
+pub async fn update_resource_type(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    payload: Payload<communication::resources::ResourceType>,
+) -> Result<Encoded<communication::resources::ResourceType>, MyError> {
+    let resource_type_id = path.into_inner();
+    let resource_type = payload.into_inner();
+    if resource_type.name.trim().is_empty() {
+        return Err(MyError::InvalidField(
+            "name",
+            "name must not be empty".to_string(),
+        ));
+    }
+    let record = find_resource_type(db.get_ref(), resource_type_id).await?;
+    if !resource_type.is_active {
+        ensure_resource_type_unused(db.get_ref(), resource_type_id).await?;
+    }
+
+    let mut record = record.into_active_model();
+    record.name = Set(resource_type.name);
+    record.description = Set(resource_type.description);
+    record.comment = Set(resource_type.comment);
+    record.is_active = Set(resource_type.is_active);
+
+    let updated = record.update(db.get_ref()).await.map_err(|e| {
+        error!("Database error updating resource type: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::ok(updated.into_model_resource_type()))
+}
+
+async fn ensure_resource_type_unused<C: ConnectionTrait>(
+    db: &C,
+    resource_type_id: i64,
+) -> Result<(), MyError> {
+    let active_resources = entity::resources::Entity::find()
+        .filter(entity::resources::Column::ResourceTypeId.eq(resource_type_id))
+        .filter(entity::resources::Column::IsActive.eq(true))
+        .count(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching resources: {}", e);
+            MyError::DatabaseError
+        })?;
+    if active_resources > 0 {
+        return Err(MyError::Conflict(format!(
+            "resource type {resource_type_id} is used by {active_resources} active resources"
+        )));
+    }
+    Ok(())
+}
+
+/// Resource types stay referenced by resources, so they are deactivated rather than deleted.
+pub async fn deactivate_resource_type(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+) -> Result<Encoded<communication::resources::ResourceType>, MyError> {
+    let resource_type_id = path.into_inner();
+
+    let mut record = find_resource_type(db.get_ref(), resource_type_id)
+        .await?
+        .into_active_model();
+    ensure_resource_type_unused(db.get_ref(), resource_type_id).await?;
+    record.is_active = Set(false);
+
+    let updated = record.update(db.get_ref()).await.map_err(|e| {
+        error!("Database error deactivating resource type: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::ok(updated.into_model_resource_type()))
+}
+
+/// Checks that the task is planned in the baseline without subtasks, the resource is active, the
+/// resource is not yet allocated to the task and the allocated capacity fits into the capacity of
+/// the resource.
+async fn validate_resource_allocation<C: ConnectionTrait>(
+    db: &C,
+    allocation: &communication::baselines::ResourceAllocation,
+    resource_baseline_id: Option<i64>,
+) -> Result<(), MyError> {
+    let planned = entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::BaselineId.eq(allocation.baseline_id))
+        .filter(entity::tasks_baselines::Column::TaskId.eq(allocation.task_id))
+        .count(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task baselines: {}", e);
+            MyError::DatabaseError
+        })?;
+    if planned == 0 {
+        return Err(MyError::InvalidField(
+            "task_id",
+            format!(
+                "task {} is not part of baseline {}",
+                allocation.task_id, allocation.baseline_id
+            ),
+        ));
+    }
+
+    // Summary tasks cost what their subtasks cost; allocating to them would count resources twice.
+    let subtasks = entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::BaselineId.eq(allocation.baseline_id))
+        .filter(entity::tasks_baselines::Column::Parent.eq(allocation.task_id))
+        .count(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task baselines: {}", e);
+            MyError::DatabaseError
+        })?;
+    if subtasks > 0 {
+        return Err(MyError::InvalidField(
+            "task_id",
+            format!(
+                "task {} has subtasks in baseline {}",
+                allocation.task_id, allocation.baseline_id
+            ),
+        ));
+    }
+
+    let resource = match find_resource(db, allocation.resource_id).await {
+        Err(MyError::NotFound(_)) => {
+            return Err(MyError::InvalidField(
+                "resource_id",
+                format!("resource {} does not exist", allocation.resource_id),
+            ));
+        }
+        result => result?,
+    };
+    if !resource.is_active {
+        return Err(MyError::InvalidField(
+            "resource_id",
+            format!("resource {} is not active", allocation.resource_id),
+        ));
+    }
+
+    if let Some(capacity_allocated) = allocation.capacity_allocated {
+        if capacity_allocated < 0. {
+            return Err(MyError::InvalidField(
+                "capacity_allocated",
+                "capacity_allocated must not be negative".to_string(),
+            ));
+        }
+        if let Some(capacity) = resource.capacity
+            && capacity_allocated > capacity
+        {
+            return Err(MyError::InvalidField(
+                "capacity_allocated",
+                format!(
+                    "capacity_allocated {capacity_allocated} exceeds capacity {capacity} of resource {}",
+                    allocation.resource_id
+                ),
+            ));
+        }
+    }
+
+    let mut duplicates = entity::resources_baselines::Entity::find()
+        .filter(entity::resources_baselines::Column::BaselineId.eq(allocation.baseline_id))
+        .filter(entity::resources_baselines::Column::TaskId.eq(allocation.task_id))
+        .filter(entity::resources_baselines::Column::ResourceId.eq(allocation.resource_id));
+    if let Some(resource_baseline_id) = resource_baseline_id {
+        duplicates = duplicates.filter(
+            entity::resources_baselines::Column::ResourceBaselineId.ne(resource_baseline_id),
+        );
+    }
+    let duplicates = duplicates.count(db).await.map_err(|e| {
+        error!("Database error fetching resource allocations: {}", e);
+        MyError::DatabaseError
+    })?;
+    if duplicates > 0 {
+        return Err(MyError::Conflict(format!(
+            "resource {} is already allocated to task {} in baseline {}",
+            allocation.resource_id, allocation.task_id, allocation.baseline_id
+        )));
+    }
+
+    Ok(())
+}
+
+/// Assigns a resource to a task of a baseline.
+pub async fn create_resource_allocation(
+    db: web::Data<DatabaseConnection>,
+    payload: Payload<communication::baselines::ResourceAllocation>,
+) -> Result<Encoded<communication::baselines::ResourceAllocation>, MyError> {
+    let allocation = payload.into_inner();
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    ensure_baseline_editable(&txn, allocation.baseline_id).await?;
+    validate_resource_allocation(&txn, &allocation, None).await?;
+
+    let inserted = entity::resources_baselines::ActiveModel {
+        resource_id: Set(allocation.resource_id),
+        baseline_id: Set(allocation.baseline_id),
+        task_id: Set(allocation.task_id),
+        capacity_allocated: Set(allocation.capacity_allocated),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|e| {
+        error!("Database error inserting resource allocation: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let stored = find_resource_allocation(&txn, inserted.resource_baseline_id).await?;
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::created(stored))
+}
+
+/// Re-assigns an allocation to another resource or task of the same baseline, or changes its capacity.
+pub async fn update_resource_allocation(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    payload: Payload<communication::baselines::ResourceAllocation>,
+) -> Result<Encoded<communication::baselines::ResourceAllocation>, MyError> {
+    let resource_baseline_id = path.into_inner();
+    let mut allocation = payload.into_inner();
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let record = entity::resources_baselines::Entity::find_by_id(resource_baseline_id)
+        .one(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching resource allocation: {}", e);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("resource allocation {resource_baseline_id}")))?;
+
+    allocation.baseline_id = record.baseline_id;
+    ensure_baseline_editable(&txn, record.baseline_id).await?;
+    validate_resource_allocation(&txn, &allocation, Some(resource_baseline_id)).await?;
+
+    let mut record = record.into_active_model();
+    record.resource_id = Set(allocation.resource_id);
+    record.task_id = Set(allocation.task_id);
+    record.capacity_allocated = Set(allocation.capacity_allocated);
+    record.update(&txn).await.map_err(|e| {
+        error!("Database error updating resource allocation: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let stored = find_resource_allocation(&txn, resource_baseline_id).await?;
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::ok(stored))
+}
+
+/// Unassigns a resource from a task.
+pub async fn delete_resource_allocation(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, MyError> {
+    let resource_baseline_id = path.into_inner();
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let record = entity::resources_baselines::Entity::find_by_id(resource_baseline_id)
+        .one(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching resource allocation: {}", e);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("resource allocation {resource_baseline_id}")))?;
+
+    ensure_baseline_editable(&txn, record.baseline_id).await?;
+
+    entity::resources_baselines::Entity::delete_by_id(resource_baseline_id)
+        .exec(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error deleting resource allocation: {}", e);
+            MyError::DatabaseError
+        })?;
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,101 @@
+use actix_web::body::BoxBody;
+use actix_web::http::StatusCode;
+use actix_web::http::header::{self, Accept, ContentType};
+use actix_web::{FromRequest, HttpMessage, HttpRequest, HttpResponse, Responder, dev, web};
+use serde::{Serialize, de::DeserializeOwned};
+use std::future::Future;
+use std::pin::Pin;
+use tracing::warn;
+
+use crate::MyError;
+
+const BITCODE: &str = "application/octet-stream";
+const JSON: &str = "application/json";
+
+/// Whether the client prefers bitcode over JSON; JSON is the default when it does not say.
+fn accepts_bitcode(req: &HttpRequest) -> bool {
+    req.get_header::<Accept>()
+        .map(|accept| {
+            accept
+                .ranked()
+                .into_iter()
+                .find(|mime| [BITCODE, JSON, "*/*"].contains(&mime.essence_str()))
+                .is_some_and(|mime| mime.essence_str() == BITCODE)
+        })
+        .unwrap_or(false)
+}
+
+/// A response body encoded as JSON or bitcode depending on the `Accept` header of the request.
+pub struct Encoded<T> {
+    status: StatusCode,
+    value: T,
+}
+
+impl<T> Encoded<T> {
+    pub fn ok(value: T) -> Self {
+        Encoded {
+            status: StatusCode::OK,
+            value,
+        }
+    }
+
+    pub fn created(value: T) -> Self {
+        Encoded {
+            status: StatusCode::CREATED,
+            value,
+        }
+    }
+}
+
+impl<T: Serialize + bitcode::Encode> Responder for Encoded<T> {
+    type Body = BoxBody;
+
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        if accepts_bitcode(req) {
+            HttpResponse::build(self.status)
+                .content_type(ContentType::octet_stream())
+                .body(bitcode::encode(&self.value))
+        } else {
+            HttpResponse::build(self.status).json(&self.value)
+        }
+    }
+}
+
+/// A request body decoded from bitcode when sent as `application/octet-stream`, otherwise from JSON.
+pub struct Payload<T>(pub T);
+
+impl<T> Payload<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: DeserializeOwned + Serialize + bitcode::DecodeOwned + 'static> FromRequest for Payload<T> {
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut dev::Payload) -> Self::Future {
+        let is_bitcode = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with(BITCODE));
+        let bytes = web::Bytes::from_request(req, payload);
+
+        Box::pin(async move {
+            let bytes = bytes.await?;
+            let value = if is_bitcode {
+                communication::datetime::decode(&bytes).map_err(|e| {
+                    warn!("Failed to decode bitcode payload: {}", e);
+                    MyError::MalformedPayload(e)
+                })?
+            } else {
+                serde_json::from_slice(&bytes).map_err(|e| {
+                    warn!("Failed to decode json payload: {}", e);
+                    MyError::MalformedPayload(e.to_string())
+                })?
+            };
+            Ok(Payload(value))
+        })
+    }
+}
@@ -0,0 +1,443 @@
+use chrono::{NaiveDateTime, TimeDelta};
+use communication::baselines::{DependencyType, TaskBaseline, TaskDependency};
+use communication::schedule::{Schedule, ScheduledTask};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Earliest start of the successor allowed by one link, given the early dates of the predecessor.
+/// None when the lag takes the date out of range.
+pub fn earliest_start(
+    link: &TaskDependency,
+    predecessor_start: NaiveDateTime,
+    predecessor_finish: NaiveDateTime,
+    successor_duration: TimeDelta,
+) -> Option<NaiveDateTime> {
+    let lag = TimeDelta::try_seconds(link.lag)?;
+    match link.dependency_type {
+        DependencyType::FinishToStart => predecessor_finish.checked_add_signed(lag),
+        DependencyType::StartToStart => predecessor_start.checked_add_signed(lag),
+        DependencyType::FinishToFinish => predecessor_finish
+            .checked_add_signed(lag)?
+            .checked_sub_signed(successor_duration),
+        DependencyType::StartToFinish => predecessor_start
+            .checked_add_signed(lag)?
+            .checked_sub_signed(successor_duration),
+    }
+}
+
+/// Latest finish of the predecessor allowed by one link, given the late dates of the successor.
+/// None when the lag takes the date out of range.
+fn latest_finish(
+    link: &TaskDependency,
+    successor_start: NaiveDateTime,
+    successor_finish: NaiveDateTime,
+    predecessor_duration: TimeDelta,
+) -> Option<NaiveDateTime> {
+    let lag = TimeDelta::try_seconds(link.lag)?;
+    match link.dependency_type {
+        DependencyType::FinishToStart => successor_start.checked_sub_signed(lag),
+        DependencyType::StartToStart => successor_start
+            .checked_sub_signed(lag)?
+            .checked_add_signed(predecessor_duration),
+        DependencyType::FinishToFinish => successor_finish.checked_sub_signed(lag),
+        DependencyType::StartToFinish => successor_finish
+            .checked_sub_signed(lag)?
+            .checked_add_signed(predecessor_duration),
+    }
+}
+
+fn lag_out_of_range(link: &TaskDependency) -> String {
+    format!(
+        "lag of the link from task {} to task {} is out of range",
+        link.predecessor_id, link.successor_id
+    )
+}
+
+/// Children of every task in `tasks`, by position. A task with children is a summary task.
+pub fn children(tasks: &[TaskBaseline]) -> Vec<Vec<usize>> {
+    let index: HashMap<i64, usize> = tasks
+        .iter()
+        .enumerate()
+        .map(|(i, task)| (task.task_id, i))
+        .collect();
+    let mut children = vec![Vec::new(); tasks.len()];
+    for (i, task) in tasks.iter().enumerate() {
+        if let Some(&parent) = task
+            .parent
+            .and_then(|parent| index.get(&parent))
+            .filter(|&&parent| parent != i)
+        {
+            children[parent].push(i);
+        }
+    }
+    children
+}
+
+/// Runs the critical path forward and backward pass over `tasks` linked by `dependencies`.
+///
+/// Durations are the planned `finish - start` of each task. A task without predecessors starts at
+/// its planned start; every other task starts as early as its links allow. Links to tasks missing
+/// from `tasks` are ignored. Fails when the links form a cycle or join a summary task.
+///
+/// Summary tasks are left out of the pass and span the tasks below them instead, with the least
+/// float of those. They are never on the critical path.
+pub fn schedule(
+    baseline_id: i64,
+    tasks: &[TaskBaseline],
+    dependencies: &[TaskDependency],
+) -> Result<Schedule, String> {
+    let children = children(tasks);
+    let is_leaf: Vec<bool> = children.iter().map(Vec::is_empty).collect();
+    let index: HashMap<i64, usize> = tasks
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| is_leaf[*i])
+        .map(|(i, task)| (task.task_id, i))
+        .collect();
+    let durations: Vec<TimeDelta> = tasks.iter().map(|task| task.finish - task.start).collect();
+
+    // Links are only created between leaves, so one on a summary task predates its subtasks.
+    let summaries: HashSet<i64> = tasks
+        .iter()
+        .zip(&is_leaf)
+        .filter(|(_, is_leaf)| !**is_leaf)
+        .map(|(task, _)| task.task_id)
+        .collect();
+    if let Some(link) = dependencies.iter().find(|link| {
+        summaries.contains(&link.predecessor_id) || summaries.contains(&link.successor_id)
+    }) {
+        return Err(format!(
+            "the link from task {} to task {} joins a summary task",
+            link.predecessor_id, link.successor_id
+        ));
+    }
+
+    // (linked task, link) for both directions
+    let mut predecessors: Vec<Vec<(usize, &TaskDependency)>> = vec![Vec::new(); tasks.len()];
+    let mut successors: Vec<Vec<(usize, &TaskDependency)>> = vec![Vec::new(); tasks.len()];
+    for link in dependencies {
+        if let (Some(&from), Some(&to)) = (
+            index.get(&link.predecessor_id),
+            index.get(&link.successor_id),
+        ) {
+            predecessors[to].push((from, link));
+            successors[from].push((to, link));
+        }
+    }
+
+    let mut in_degree: Vec<usize> = predecessors.iter().map(Vec::len).collect();
+    let mut ready: VecDeque<usize> = (0..tasks.len())
+        .filter(|i| is_leaf[*i] && in_degree[*i] == 0)
+        .collect();
+    let mut order = Vec::with_capacity(tasks.len());
+    while let Some(i) = ready.pop_front() {
+        order.push(i);
+        for (successor, _) in &successors[i] {
+            in_degree[*successor] -= 1;
+            if in_degree[*successor] == 0 {
+                ready.push_back(*successor);
+            }
+        }
+    }
+    if order.len() < index.len() {
+        let cyclic: Vec<i64> = (0..tasks.len())
+            .filter(|i| in_degree[*i] > 0)
+            .map(|i| tasks[i].task_id)
+            .collect();
+        return Err(format!("dependencies of tasks {cyclic:?} form a cycle"));
+    }
+
+    let mut early_start: Vec<NaiveDateTime> = tasks.iter().map(|task| task.start.naive()).collect();
+    let mut early_finish: Vec<NaiveDateTime> = vec![NaiveDateTime::MIN; tasks.len()];
+    for &i in &order {
+        let mut start: Option<NaiveDateTime> = None;
+        for (p, link) in &predecessors[i] {
+            let allowed = earliest_start(link, early_start[*p], early_finish[*p], durations[i])
+                .ok_or_else(|| lag_out_of_range(link))?;
+            start = Some(start.map_or(allowed, |start| start.max(allowed)));
+        }
+        if let Some(start) = start {
+            early_start[i] = start;
+        }
+        early_finish[i] = early_start[i] + durations[i];
+    }
+
+    let start = order
+        .iter()
+        .map(|&i| early_start[i])
+        .min()
+        .unwrap_or_default();
+    let finish = order
+        .iter()
+        .map(|&i| early_finish[i])
+        .max()
+        .unwrap_or_default();
+
+    let mut late_start: Vec<NaiveDateTime> = vec![NaiveDateTime::MAX; tasks.len()];
+    let mut late_finish: Vec<NaiveDateTime> = vec![finish; tasks.len()];
+    for &i in order.iter().rev() {
+        let mut latest: Option<NaiveDateTime> = None;
+        for (s, link) in &successors[i] {
+            let allowed = latest_finish(link, late_start[*s], late_finish[*s], durations[i])
+                .ok_or_else(|| lag_out_of_range(link))?;
+            latest = Some(latest.map_or(allowed, |latest| latest.min(allowed)));
+        }
+        if let Some(latest) = latest {
+            late_finish[i] = latest;
+        }
+        late_start[i] = late_finish[i] - durations[i];
+    }
+
+    let mut scheduled: Vec<ScheduledTask> = order
+        .iter()
+        .map(|&i| {
+            let total_float = (late_start[i] - early_start[i]).num_seconds();
+            // Slack to the earliest start each successor would allow, measured on its early start.
+            // The forward pass already computed every allowed start, so none is out of range.
+            let free_float = successors[i]
+                .iter()
+                .filter_map(|(s, link)| {
+                    let allowed =
+                        earliest_start(link, early_start[i], early_finish[i], durations[*s])?;
+                    Some((early_start[*s] - allowed).num_seconds())
+                })
+                .min()
+                .unwrap_or_else(|| (finish - early_finish[i]).num_seconds());
+            ScheduledTask {
+                task_id: tasks[i].task_id,
+                early_start: early_start[i].into(),
+                early_finish: early_finish[i].into(),
+                late_start: late_start[i].into(),
+                late_finish: late_finish[i].into(),
+                total_float,
+                free_float,
+                is_critical: total_float <= 0,
+            }
+        })
+        .collect();
+
+    let mut critical: Vec<&ScheduledTask> =
+        scheduled.iter().filter(|task| task.is_critical).collect();
+    critical.sort_by_key(|task| (task.early_start, task.early_finish));
+    let critical_path = critical.into_iter().map(|task| task.task_id).collect();
+
+    let positions: HashMap<usize, usize> = order
+        .iter()
+        .enumerate()
+        .map(|(position, &i)| (i, position))
+        .collect();
+    let mut summaries = Vec::new();
+    for (i, task) in tasks.iter().enumerate().filter(|(i, _)| !is_leaf[*i]) {
+        let mut leaves = Vec::new();
+        let mut visited = vec![false; tasks.len()];
+        let mut stack = children[i].clone();
+        while let Some(j) = stack.pop() {
+            if std::mem::replace(&mut visited[j], true) {
+                continue;
+            }
+            match positions.get(&j) {
+                Some(&position) => leaves.push(&scheduled[position]),
+                None => stack.extend(&children[j]),
+            }
+        }
+        let Some(early_start) = leaves.iter().map(|leaf| leaf.early_start).min() else {
+            continue;
+        };
+        summaries.push(ScheduledTask {
+            task_id: task.task_id,
+            early_start,
+            early_finish: leaves
+                .iter()
+                .map(|leaf| leaf.early_finish)
+                .max()
+                .unwrap_or(early_start),
+            late_start: leaves
+                .iter()
+                .map(|leaf| leaf.late_start)
+                .min()
+                .unwrap_or(early_start),
+            late_finish: leaves
+                .iter()
+                .map(|leaf| leaf.late_finish)
+                .max()
+                .unwrap_or(early_start),
+            total_float: leaves
+                .iter()
+                .map(|leaf| leaf.total_float)
+                .min()
+                .unwrap_or_default(),
+            free_float: leaves
+                .iter()
+                .map(|leaf| leaf.free_float)
+                .min()
+                .unwrap_or_default(),
+            is_critical: false,
+        });
+    }
+    scheduled.extend(summaries);
+
+    Ok(Schedule {
+        baseline_id,
+        start: start.into(),
+        finish: finish.into(),
+        tasks: scheduled,
+        critical_path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{at, link, task};
+
+    fn scheduled(schedule: &Schedule, task_id: i64) -> &ScheduledTask {
+        schedule
+            .tasks
+            .iter()
+            .find(|task| task.task_id == task_id)
+            .unwrap()
+    }
+
+    /// Early start and finish of task 2 when linked to task 1 by one link.
+    fn successor_dates(
+        predecessor: TaskBaseline,
+        dependency_type: DependencyType,
+        lag_hours: i64,
+    ) -> (NaiveDateTime, NaiveDateTime) {
+        let tasks = [predecessor, task(2, 0, 5)];
+        let schedule = schedule(1, &tasks, &[link(1, 2, dependency_type, lag_hours)]).unwrap();
+        let successor = scheduled(&schedule, 2);
+        (
+            successor.early_start.naive(),
+            successor.early_finish.naive(),
+        )
+    }
+
+    #[test]
+    fn finish_to_start_follows_the_predecessor_finish() {
+        let lag = successor_dates(task(1, 0, 10), DependencyType::FinishToStart, 2);
+        assert_eq!(lag, (at(12), at(17)));
+        let lead = successor_dates(task(1, 0, 10), DependencyType::FinishToStart, -3);
+        assert_eq!(lead, (at(7), at(12)));
+    }
+
+    #[test]
+    fn start_to_start_follows_the_predecessor_start() {
+        let lag = successor_dates(task(1, 10, 20), DependencyType::StartToStart, 4);
+        assert_eq!(lag, (at(14), at(19)));
+        let lead = successor_dates(task(1, 10, 20), DependencyType::StartToStart, -2);
+        assert_eq!(lead, (at(8), at(13)));
+    }
+
+    #[test]
+    fn finish_to_finish_aligns_the_finishes() {
+        let lag = successor_dates(task(1, 0, 10), DependencyType::FinishToFinish, 1);
+        assert_eq!(lag, (at(6), at(11)));
+        let lead = successor_dates(task(1, 0, 10), DependencyType::FinishToFinish, -1);
+        assert_eq!(lead, (at(4), at(9)));
+    }
+
+    #[test]
+    fn start_to_finish_finishes_after_the_predecessor_start() {
+        let lag = successor_dates(task(1, 10, 20), DependencyType::StartToFinish, 2);
+        assert_eq!(lag, (at(7), at(12)));
+        let lead = successor_dates(task(1, 10, 20), DependencyType::StartToFinish, -2);
+        assert_eq!(lead, (at(3), at(8)));
+    }
+
+    /// 1 -> 3 is the long branch, 2 -> 4 -> 3 the short one; 5 is not linked at all.
+    fn network() -> Schedule {
+        let tasks = [
+            task(1, 0, 10),
+            task(2, 0, 4),
+            task(3, 0, 2),
+            task(4, 0, 2),
+            task(5, 0, 3),
+        ];
+        let dependencies = [
+            link(1, 3, DependencyType::FinishToStart, 0),
+            link(2, 4, DependencyType::FinishToStart, 0),
+            link(4, 3, DependencyType::FinishToStart, 0),
+        ];
+        schedule(1, &tasks, &dependencies).unwrap()
+    }
+
+    #[test]
+    fn floats_measure_the_slack_to_the_project_and_to_the_successors() {
+        let schedule = network();
+        assert_eq!(schedule.finish.naive(), at(12));
+
+        let long = scheduled(&schedule, 1);
+        assert_eq!((long.total_float, long.free_float), (0, 0));
+        // 2 can only slip by pushing 4, which has the room for both of them.
+        let first = scheduled(&schedule, 2);
+        assert_eq!((first.total_float, first.free_float), (4 * 3600, 0));
+        let second = scheduled(&schedule, 4);
+        assert_eq!(
+            (second.early_start.naive(), second.late_start.naive()),
+            (at(4), at(8))
+        );
+        assert_eq!(
+            (second.total_float, second.free_float),
+            (4 * 3600, 4 * 3600)
+        );
+        let unlinked = scheduled(&schedule, 5);
+        assert_eq!(
+            (unlinked.total_float, unlinked.free_float),
+            (9 * 3600, 9 * 3600)
+        );
+    }
+
+    #[test]
+    fn critical_path_follows_the_tasks_without_float() {
+        let schedule = network();
+        assert_eq!(schedule.critical_path, vec![1, 3]);
+        for task_id in [2, 4, 5] {
+            assert!(!scheduled(&schedule, task_id).is_critical);
+        }
+    }
+
+    #[test]
+    fn cycles_are_rejected() {
+        let tasks = [task(1, 0, 1), task(2, 0, 1), task(3, 0, 1)];
+        let dependencies = [
+            link(1, 2, DependencyType::FinishToStart, 0),
+            link(2, 3, DependencyType::StartToStart, 0),
+            link(3, 1, DependencyType::FinishToFinish, 0),
+        ];
+        let error = schedule(1, &tasks, &dependencies).unwrap_err();
+        assert!(error.contains("cycle"), "{error}");
+    }
+
+    #[test]
+    fn summary_tasks_span_their_subtasks() {
+        let mut first = task(2, 0, 4);
+        first.parent = Some(1);
+        let mut second = task(3, 0, 2);
+        second.parent = Some(1);
+        let tasks = [task(1, 0, 1), first, second];
+        let schedule =
+            schedule(1, &tasks, &[link(2, 3, DependencyType::FinishToStart, 0)]).unwrap();
+
+        let summary = scheduled(&schedule, 1);
+        assert_eq!(
+            (summary.early_start.naive(), summary.early_finish.naive()),
+            (at(0), at(6))
+        );
+        assert!(!summary.is_critical);
+        assert_eq!(schedule.critical_path, vec![2, 3]);
+    }
+
+    #[test]
+    fn links_of_summary_tasks_are_refused() {
+        let mut subtask = task(2, 0, 4);
+        subtask.parent = Some(1);
+        let tasks = [task(1, 0, 4), subtask, task(3, 0, 2)];
+        for dependencies in [
+            [link(1, 3, DependencyType::FinishToStart, 0)],
+            [link(3, 1, DependencyType::StartToStart, 0)],
+        ] {
+            let error = schedule(1, &tasks, &dependencies).unwrap_err();
+            assert!(error.contains("summary task"), "{error}");
+        }
+    }
+}
@@ -0,0 +1,1022 @@
+use actix_web::{HttpResponse, Result, web};
+use chrono::NaiveDateTime;
+use communication::paging::Page;
+use sea_orm::sea_query::{Expr, Query, extension::postgres::PgExpr};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, ConnectionTrait,
+    DatabaseConnection, EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, TransactionTrait,
+};
+use std::collections::HashMap;
+use tracing::{error, info};
+
+use crate::MyError;
+use crate::baselines::{ensure_baseline_editable, ensure_baseline_exists};
+use crate::paging::{PageRequest, escape_like};
+use crate::response::{Encoded, Payload};
+use crate::wbs::find_ancestors;
+
+pub trait IntoModelTaskStatus {
+    fn into_model_task_status(self) -> communication::tasks::TaskStatus;
+}
+
+impl IntoModelTaskStatus for entity::tasks::TaskStatus {
+    fn into_model_task_status(self) -> communication::tasks::TaskStatus {
+        match self {
+            entity::tasks::TaskStatus::ToDo => communication::tasks::TaskStatus::ToDo,
+            entity::tasks::TaskStatus::InProgress => communication::tasks::TaskStatus::InProgress,
+            entity::tasks::TaskStatus::Done => communication::tasks::TaskStatus::Done,
+            entity::tasks::TaskStatus::Cancelled => communication::tasks::TaskStatus::Cancelled,
+        }
+    }
+}
+
+pub trait IntoEntityTaskStatus {
+    fn into_entity_task_status(self) -> entity::tasks::TaskStatus;
+}
+
+impl IntoEntityTaskStatus for communication::tasks::TaskStatus {
+    fn into_entity_task_status(self) -> entity::tasks::TaskStatus {
+        match self {
+            communication::tasks::TaskStatus::ToDo => entity::tasks::TaskStatus::ToDo,
+            communication::tasks::TaskStatus::InProgress => entity::tasks::TaskStatus::InProgress,
+            communication::tasks::TaskStatus::Done => entity::tasks::TaskStatus::Done,
+            communication::tasks::TaskStatus::Cancelled => entity::tasks::TaskStatus::Cancelled,
+        }
+    }
+}
+
+pub trait IntoModelTask {
+    fn into_model_task(self) -> communication::tasks::Task;
+}
+
+impl IntoModelTask for entity::tasks::Model {
+    fn into_model_task(self) -> communication::tasks::Task {
+        communication::tasks::Task {
+            task_id: self.task_id,
+            name: self.summary,
+            description: self.description,
+            comment: self.comment,
+            status: self.status.into_model_task_status(),
+        }
+    }
+}
+
+pub trait IntoModelTaskStatusChange {
+    fn into_model_task_status_change(self) -> communication::tasks::TaskStatusChange;
+}
+
+impl IntoModelTaskStatusChange for entity::task_status_history::Model {
+    fn into_model_task_status_change(self) -> communication::tasks::TaskStatusChange {
+        communication::tasks::TaskStatusChange {
+            task_status_history_id: self.task_status_history_id,
+            task_id: self.task_id,
+            old_status: self.old_status.into_model_task_status(),
+            new_status: self.new_status.into_model_task_status(),
+            changed_at: self.changed_at.into(),
+            changed_by: self.changed_by,
+        }
+    }
+}
+
+/// Milestones of a baseline, optionally only those `from` to `to`, both inclusive, ordered by date.
+pub async fn get_milestones(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<Encoded<Vec<communication::baselines::TaskBaseline>>, MyError> {
+    let baseline_id = path.into_inner();
+    let from: Option<NaiveDateTime> = crate::optional_query_param(&query, "from")?;
+    let to: Option<NaiveDateTime> = crate::optional_query_param(&query, "to")?;
+    ensure_baseline_exists(db.get_ref(), baseline_id).await?;
+
+    let mut select = entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::BaselineId.eq(baseline_id))
+        .filter(entity::tasks_baselines::Column::Milestone.eq(true))
+        .column_as(entity::tasks::Column::Summary, "task_summary")
+        .column_as(entity::tasks::Column::Description, "task_description")
+        .column_as(entity::tasks::Column::Comment, "task_comment")
+        .left_join(entity::tasks::Entity);
+    if let Some(from) = from {
+        select = select.filter(entity::tasks_baselines::Column::Start.gte(from));
+    }
+    if let Some(to) = to {
+        select = select.filter(entity::tasks_baselines::Column::Start.lte(to));
+    }
+
+    let milestones = select
+        .order_by_asc(entity::tasks_baselines::Column::Start)
+        .order_by_asc(entity::tasks_baselines::Column::Wbs)
+        .into_model::<communication::baselines::TaskBaseline>()
+        .all(db.get_ref())
+        .await
+        .map_err(|db_err| {
+            error!("Database error fetching milestones: {}", db_err);
+            MyError::DatabaseError
+        })?;
+
+    Ok(Encoded::ok(milestones))
+}
+
+/// Lists the tasks of a baseline one page at a time.
+///
+/// Optional filters: `wbs` (prefix), `from` and `to` (tasks overlapping the range), `status`,
+/// `resource_type_id` (tasks with a resource of that type allocated) and `q` (case-insensitive
+/// text in the summary). Sort keys are `wbs`, `start`, `finish` and `task_id`; `wbs` compares
+/// as text, so `1.10` sorts before `1.2`.
+pub async fn get_list_of_tasks(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<Encoded<Page<communication::baselines::TaskBaseline>>, MyError> {
+    let baseline_id: i64 = crate::query_param(&query, "baseline_id")?;
+    let page_request = PageRequest::from_query(&query, &["wbs", "start", "finish", "task_id"])?;
+
+    let mut select = entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::BaselineId.eq(baseline_id))
+        .column_as(entity::tasks::Column::Summary, "task_summary")
+        .column_as(entity::tasks::Column::Description, "task_description")
+        .column_as(entity::tasks::Column::Comment, "task_comment")
+        .left_join(entity::tasks::Entity);
+
+    if let Some(wbs) = crate::optional_query_param::<String>(&query, "wbs")? {
+        select = select
+            .filter(entity::tasks_baselines::Column::Wbs.like(format!("{}%", escape_like(&wbs))));
+    }
+    if let Some(from) = crate::optional_query_param::<NaiveDateTime>(&query, "from")? {
+        select = select.filter(entity::tasks_baselines::Column::Finish.gte(from));
+    }
+    if let Some(to) = crate::optional_query_param::<NaiveDateTime>(&query, "to")? {
+        select = select.filter(entity::tasks_baselines::Column::Start.lte(to));
+    }
+    if let Some(status) =
+        crate::optional_query_param::<communication::tasks::TaskStatus>(&query, "status")?
+    {
+        select = select.filter(entity::tasks::Column::Status.eq(status.into_entity_task_status()));
+    }
+    if let Some(resource_type_id) = crate::optional_query_param::<i64>(&query, "resource_type_id")?
+    {
+        select = select.filter(
+            entity::tasks_baselines::Column::TaskId.in_subquery(
+                Query::select()
+                    .column((
+                        entity::resources_baselines::Entity,
+                        entity::resources_baselines::Column::TaskId,
+                    ))
+                    .from(entity::resources_baselines::Entity)
+                    .inner_join(
+                        entity::resources::Entity,
+                        Expr::col((
+                            entity::resources::Entity,
+                            entity::resources::Column::ResourceId,
+                        ))
+                        .equals((
+                            entity::resources_baselines::Entity,
+                            entity::resources_baselines::Column::ResourceId,
+                        )),
+                    )
+                    .and_where(
+                        Expr::col((
+                            entity::resources_baselines::Entity,
+                            entity::resources_baselines::Column::BaselineId,
+                        ))
+                        .eq(baseline_id),
+                    )
+                    .and_where(
+                        Expr::col((
+                            entity::resources::Entity,
+                            entity::resources::Column::ResourceTypeId,
+                        ))
+                        .eq(resource_type_id),
+                    )
+                    .to_owned(),
+            ),
+        );
+    }
+    if let Some(text) = crate::optional_query_param::<String>(&query, "q")? {
+        select = select.filter(
+            Expr::col((entity::tasks::Entity, entity::tasks::Column::Summary))
+                .ilike(format!("%{}%", escape_like(&text))),
+        );
+    }
+
+    let total = select.clone().count(db.get_ref()).await.map_err(|e| {
+        error!("Database error counting task baselines: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let after = match page_request.cursor {
+        Some(cursor) => Some(
+            entity::tasks_baselines::Entity::find_by_id(cursor)
+                .one(db.get_ref())
+                .await
+                .map_err(|e| {
+                    error!("Database error fetching task baseline: {}", e);
+                    MyError::DatabaseError
+                })?
+                .ok_or_else(|| MyError::InvalidQueryParameter("cursor".to_string()))?,
+        ),
+        None => None,
+    };
+
+    let mut cursor = match page_request.sort {
+        "start" => {
+            let mut cursor = select.cursor_by((
+                entity::tasks_baselines::Column::Start,
+                entity::tasks_baselines::Column::TaskBaselineId,
+            ));
+            if let Some(after) = &after {
+                cursor.after((after.start, after.task_baseline_id));
+            }
+            cursor
+        }
+        "finish" => {
+            let mut cursor = select.cursor_by((
+                entity::tasks_baselines::Column::Finish,
+                entity::tasks_baselines::Column::TaskBaselineId,
+            ));
+            if let Some(after) = &after {
+                cursor.after((after.finish, after.task_baseline_id));
+            }
+            cursor
+        }
+        "task_id" => {
+            let mut cursor = select.cursor_by((
+                entity::tasks_baselines::Column::TaskId,
+                entity::tasks_baselines::Column::TaskBaselineId,
+            ));
+            if let Some(after) = &after {
+                cursor.after((after.task_id, after.task_baseline_id));
+            }
+            cursor
+        }
+        _ => {
+            let mut cursor = select.cursor_by((
+                entity::tasks_baselines::Column::Wbs,
+                entity::tasks_baselines::Column::TaskBaselineId,
+            ));
+            if let Some(after) = &after {
+                cursor.after((after.wbs.clone(), after.task_baseline_id));
+            }
+            cursor
+        }
+    };
+    if page_request.descending {
+        cursor.desc();
+    }
+    cursor.first(page_request.limit + 1);
+
+    let task_baselines = cursor
+        .into_model::<communication::baselines::TaskBaseline>()
+        .all(db.get_ref())
+        .await
+        .map_err(|db_err| {
+            error!("Database error fetching task baselines: {}", db_err);
+            MyError::DatabaseError
+        })?;
+
+    Ok(Encoded::ok(page_request.page(
+        task_baselines,
+        total,
+        |task| task.task_baseline_id,
+    )))
+}
+
+/// Reads all tasks of a baseline joined with their task, the same shape `GET /tasks` returns.
+pub async fn find_task_baselines<C: ConnectionTrait>(
+    db: &C,
+    baseline_id: i64,
+) -> Result<Vec<communication::baselines::TaskBaseline>, MyError> {
+    entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::BaselineId.eq(baseline_id))
+        .column_as(entity::tasks::Column::Summary, "task_summary")
+        .column_as(entity::tasks::Column::Description, "task_description")
+        .column_as(entity::tasks::Column::Comment, "task_comment")
+        .left_join(entity::tasks::Entity)
+        .into_model::<communication::baselines::TaskBaseline>()
+        .all(db)
+        .await
+        .map_err(|db_err| {
+            error!("Database error fetching task baselines: {}", db_err);
+            MyError::DatabaseError
+        })
+}
+
+/// Reads a single task baseline joined with its task, the same shape `GET /tasks` returns.
+async fn find_task_baseline<C: ConnectionTrait>(
+    db: &C,
+    task_baseline_id: i64,
+) -> Result<communication::baselines::TaskBaseline, MyError> {
+    entity::tasks_baselines::Entity::find_by_id(task_baseline_id)
+        .column_as(entity::tasks::Column::Summary, "task_summary")
+        .column_as(entity::tasks::Column::Description, "task_description")
+        .column_as(entity::tasks::Column::Comment, "task_comment")
+        .left_join(entity::tasks::Entity)
+        .into_model::<communication::baselines::TaskBaseline>()
+        .one(db)
+        .await
+        .map_err(|db_err| {
+            error!("Database error fetching task baseline: {}", db_err);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("task baseline {task_baseline_id}")))
+}
+
+fn validate_task_baseline(task: &communication::baselines::TaskBaseline) -> Result<(), MyError> {
+    if task.task_summary.trim().is_empty() {
+        return Err(MyError::InvalidField(
+            "task_summary",
+            "task_summary must not be empty".to_string(),
+        ));
+    }
+    if task.finish < task.start {
+        return Err(MyError::InvalidField(
+            "finish",
+            "finish must not be before start".to_string(),
+        ));
+    }
+    if task.milestone && task.finish != task.start {
+        return Err(MyError::InvalidField(
+            "finish",
+            "a milestone must finish when it starts".to_string(),
+        ));
+    }
+    for timezone in [&task.start_timezone, &task.finish_timezone] {
+        if timezone.parse::<chrono_tz::Tz>().is_err() {
+            return Err(MyError::InvalidField(
+                "timezone",
+                format!("unknown timezone: {timezone}"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The parent of a task is another task that must already be planned in the same baseline without
+/// links or allocations, and not one of the task's own descendants.
+async fn ensure_parent_in_baseline<C: ConnectionTrait>(
+    db: &C,
+    baseline_id: i64,
+    task_id: Option<i64>,
+    parent: Option<i64>,
+) -> Result<(), MyError> {
+    let Some(parent) = parent else {
+        return Ok(());
+    };
+    if Some(parent) == task_id {
+        return Err(MyError::InvalidField(
+            "parent",
+            "task cannot be its own parent".to_string(),
+        ));
+    }
+    let count = entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::BaselineId.eq(baseline_id))
+        .filter(entity::tasks_baselines::Column::TaskId.eq(parent))
+        .count(db)
+        .await
+        .map_err(|db_err| {
+            error!("Database error fetching parent task: {}", db_err);
+            MyError::DatabaseError
+        })?;
+    if count == 0 {
+        return Err(MyError::InvalidField(
+            "parent",
+            format!("parent task {parent} is not part of baseline {baseline_id}"),
+        ));
+    }
+    // Links only join tasks without subtasks, so a linked task cannot become a summary task.
+    let links = entity::task_dependencies::Entity::find()
+        .filter(entity::task_dependencies::Column::BaselineId.eq(baseline_id))
+        .filter(
+            Condition::any()
+                .add(entity::task_dependencies::Column::PredecessorId.eq(parent))
+                .add(entity::task_dependencies::Column::SuccessorId.eq(parent)),
+        )
+        .count(db)
+        .await
+        .map_err(|db_err| {
+            error!("Database error fetching task dependencies: {}", db_err);
+            MyError::DatabaseError
+        })?;
+    if links > 0 {
+        return Err(MyError::InvalidField(
+            "parent",
+            format!("parent task {parent} is linked to other tasks in baseline {baseline_id}"),
+        ));
+    }
+    // Resources are only allocated to tasks without subtasks, so every report plans the same cost.
+    let allocations = entity::resources_baselines::Entity::find()
+        .filter(entity::resources_baselines::Column::BaselineId.eq(baseline_id))
+        .filter(entity::resources_baselines::Column::TaskId.eq(parent))
+        .count(db)
+        .await
+        .map_err(|db_err| {
+            error!("Database error fetching resource allocations: {}", db_err);
+            MyError::DatabaseError
+        })?;
+    if allocations > 0 {
+        return Err(MyError::InvalidField(
+            "parent",
+            format!("parent task {parent} has resources allocated in baseline {baseline_id}"),
+        ));
+    }
+    if let Some(task_id) = task_id {
+        let ancestors = find_ancestors(db, baseline_id, parent).await?;
+        if ancestors.iter().any(|ancestor| ancestor.task_id == task_id) {
+            return Err(MyError::InvalidField(
+                "parent",
+                format!("task {parent} is a subtask of task {task_id}"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+async fn ensure_no_subtasks<C: ConnectionTrait>(
+    db: &C,
+    task_id: i64,
+    baseline_id: Option<i64>,
+) -> Result<(), MyError> {
+    let mut query = entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::Parent.eq(task_id))
+        .filter(entity::tasks_baselines::Column::TaskId.ne(task_id));
+    if let Some(baseline_id) = baseline_id {
+        query = query.filter(entity::tasks_baselines::Column::BaselineId.eq(baseline_id));
+    }
+    let count = query.count(db).await.map_err(|db_err| {
+        error!("Database error fetching subtasks: {}", db_err);
+        MyError::DatabaseError
+    })?;
+    if count > 0 {
+        return Err(MyError::Conflict(format!("task {task_id} has subtasks")));
+    }
+    Ok(())
+}
+
+/// Time, expenses and progress recorded on a task are actuals, so the task cannot be deleted while
+/// it has any.
+async fn ensure_no_actuals<C: ConnectionTrait>(db: &C, task_id: i64) -> Result<(), MyError> {
+    let time_entries = entity::time_entries::Entity::find()
+        .filter(entity::time_entries::Column::TaskId.eq(task_id))
+        .count(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching time entries: {}", e);
+            MyError::DatabaseError
+        })?;
+    if time_entries > 0 {
+        return Err(MyError::Conflict(format!(
+            "task {task_id} has recorded time entries"
+        )));
+    }
+    let expenses = entity::expenses::Entity::find()
+        .filter(entity::expenses::Column::TaskId.eq(task_id))
+        .count(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching expenses: {}", e);
+            MyError::DatabaseError
+        })?;
+    if expenses > 0 {
+        return Err(MyError::Conflict(format!(
+            "task {task_id} has recorded expenses"
+        )));
+    }
+    let progress = entity::task_progress::Entity::find()
+        .filter(entity::task_progress::Column::TaskId.eq(task_id))
+        .count(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task progress: {}", e);
+            MyError::DatabaseError
+        })?;
+    if progress > 0 {
+        return Err(MyError::Conflict(format!(
+            "task {task_id} has recorded progress"
+        )));
+    }
+    Ok(())
+}
+
+/// Deletes a task planned in no baseline any more together with its status history, returning
+/// whether it existed. Fails while the task has actuals.
+async fn delete_unplanned_task<C: ConnectionTrait>(db: &C, task_id: i64) -> Result<bool, MyError> {
+    ensure_no_actuals(db, task_id).await?;
+
+    entity::task_status_history::Entity::delete_many()
+        .filter(entity::task_status_history::Column::TaskId.eq(task_id))
+        .exec(db)
+        .await
+        .map_err(|e| {
+            error!("Database error deleting task status history: {}", e);
+            MyError::DatabaseError
+        })?;
+    let deleted = entity::tasks::Entity::delete_by_id(task_id)
+        .exec(db)
+        .await
+        .map_err(|e| {
+            error!("Database error deleting task: {}", e);
+            MyError::DatabaseError
+        })?;
+    Ok(deleted.rows_affected > 0)
+}
+
+/// Creates a task together with its row in the given baseline.
+pub async fn create_task(
+    db: web::Data<DatabaseConnection>,
+    payload: Payload<communication::baselines::TaskBaseline>,
+) -> Result<Encoded<communication::baselines::TaskBaseline>, MyError> {
+    let task = payload.into_inner();
+    validate_task_baseline(&task)?;
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    ensure_baseline_editable(&txn, task.baseline_id).await?;
+    ensure_parent_in_baseline(&txn, task.baseline_id, None, task.parent).await?;
+
+    let task_inserted = entity::tasks::ActiveModel {
+        summary: Set(task.task_summary),
+        description: Set(task.task_description),
+        comment: Set(task.task_comment),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|e| {
+        error!("Database error inserting task: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let task_baseline_inserted = entity::tasks_baselines::ActiveModel {
+        task_id: Set(task_inserted.task_id),
+        baseline_id: Set(task.baseline_id),
+        wbs: Set(task.wbs),
+        parent: Set(task.parent),
+        start: Set(task.start.naive()),
+        start_timezone: Set(task.start_timezone),
+        finish: Set(task.finish.naive()),
+        finish_timezone: Set(task.finish_timezone),
+        milestone: Set(task.milestone),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|e| {
+        error!("Database error inserting task baseline: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let stored = find_task_baseline(&txn, task_baseline_inserted.task_baseline_id).await?;
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::created(stored))
+}
+
+/// Updates the baseline independent part of a task except its status, which only changes through
+/// [`change_task_status`]. The summary, description and comment are shared by every baseline the
+/// task is planned in, locked ones included.
+pub async fn update_task(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    payload: Payload<communication::tasks::Task>,
+) -> Result<Encoded<communication::tasks::Task>, MyError> {
+    let task_id = path.into_inner();
+    let task = payload.into_inner();
+    if task.name.trim().is_empty() {
+        return Err(MyError::InvalidField(
+            "name",
+            "name must not be empty".to_string(),
+        ));
+    }
+
+    let record = entity::tasks::Entity::find_by_id(task_id)
+        .one(db.get_ref())
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task: {}", e);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("task {task_id}")))?;
+    if record.status.clone().into_model_task_status() != task.status {
+        return Err(MyError::InvalidField(
+            "status",
+            format!("the status of task {task_id} is changed through its status endpoint"),
+        ));
+    }
+
+    let mut record = record.into_active_model();
+    record.summary = Set(task.name);
+    record.description = Set(task.description);
+    record.comment = Set(task.comment);
+
+    let updated = record.update(db.get_ref()).await.map_err(|e| {
+        error!("Database error updating task: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::ok(updated.into_model_task()))
+}
+
+/// Deletes a task from every baseline, including its resource allocations.
+pub async fn delete_task(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, MyError> {
+    let task_id = path.into_inner();
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let baseline_ids: Vec<i64> = entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::TaskId.eq(task_id))
+        .all(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task baselines: {}", e);
+            MyError::DatabaseError
+        })?
+        .into_iter()
+        .map(|record| record.baseline_id)
+        .collect();
+    for baseline_id in baseline_ids {
+        ensure_baseline_editable(&txn, baseline_id).await?;
+    }
+    ensure_no_subtasks(&txn, task_id, None).await?;
+
+    entity::resources_baselines::Entity::delete_many()
+        .filter(entity::resources_baselines::Column::TaskId.eq(task_id))
+        .exec(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error deleting resource allocations: {}", e);
+            MyError::DatabaseError
+        })?;
+    entity::task_dependencies::Entity::delete_many()
+        .filter(
+            Condition::any()
+                .add(entity::task_dependencies::Column::PredecessorId.eq(task_id))
+                .add(entity::task_dependencies::Column::SuccessorId.eq(task_id)),
+        )
+        .exec(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error deleting task dependencies: {}", e);
+            MyError::DatabaseError
+        })?;
+    entity::tasks_baselines::Entity::delete_many()
+        .filter(entity::tasks_baselines::Column::TaskId.eq(task_id))
+        .exec(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error deleting task baselines: {}", e);
+            MyError::DatabaseError
+        })?;
+    if !delete_unplanned_task(&txn, task_id).await? {
+        return Err(MyError::NotFound(format!("task {task_id}")));
+    }
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Moves a task to another status if the workflow allows it, recording who moved it and when.
+pub async fn change_task_status(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    payload: Payload<communication::tasks::TaskStatusUpdate>,
+) -> Result<Encoded<communication::tasks::Task>, MyError> {
+    let task_id = path.into_inner();
+    let update = payload.into_inner();
+    if update.changed_by.trim().is_empty() {
+        return Err(MyError::InvalidField(
+            "changed_by",
+            "changed_by must not be empty".to_string(),
+        ));
+    }
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let task = entity::tasks::Entity::find_by_id(task_id)
+        .one(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task: {}", e);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("task {task_id}")))?;
+    let old_status = task.status.clone().into_model_task_status();
+    if !old_status.can_become(&update.status) {
+        return Err(MyError::Conflict(format!(
+            "task {task_id} cannot change from {old_status} to {}",
+            update.status
+        )));
+    }
+
+    let new_status = update.status.into_entity_task_status();
+    entity::task_status_history::ActiveModel {
+        task_id: Set(task_id),
+        old_status: Set(task.status.clone()),
+        new_status: Set(new_status.clone()),
+        changed_at: Set(chrono::Utc::now()),
+        changed_by: Set(update.changed_by),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|e| {
+        error!("Database error inserting task status history: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let mut task = task.into_active_model();
+    task.status = Set(new_status);
+    let updated = task.update(&txn).await.map_err(|e| {
+        error!("Database error updating task: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    info!(
+        "Task {} moved from {} to {}",
+        task_id,
+        old_status,
+        updated.status.clone().into_model_task_status()
+    );
+
+    Ok(Encoded::ok(updated.into_model_task()))
+}
+
+/// Recorded status changes, optionally of one `task_id` or only those to one `status`, oldest
+/// first. The changes to `InProgress` and `Done` tell when tasks actually started and finished.
+pub async fn get_task_status_history(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<Encoded<Vec<communication::tasks::TaskStatusChange>>, MyError> {
+    let task_id: Option<i64> = crate::optional_query_param(&query, "task_id")?;
+    let status: Option<communication::tasks::TaskStatus> =
+        crate::optional_query_param(&query, "status")?;
+
+    let mut select = entity::task_status_history::Entity::find();
+    if let Some(task_id) = task_id {
+        select = select.filter(entity::task_status_history::Column::TaskId.eq(task_id));
+    }
+    if let Some(status) = status {
+        select = select.filter(
+            entity::task_status_history::Column::NewStatus.eq(status.into_entity_task_status()),
+        );
+    }
+
+    let changes = select
+        .order_by_asc(entity::task_status_history::Column::ChangedAt)
+        .order_by_asc(entity::task_status_history::Column::TaskStatusHistoryId)
+        .all(db.get_ref())
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task status history: {}", e);
+            MyError::DatabaseError
+        })?
+        .into_iter()
+        .map(|record| record.into_model_task_status_change())
+        .collect();
+
+    Ok(Encoded::ok(changes))
+}
+
+/// Plans an existing task in another baseline.
+pub async fn create_task_baseline(
+    db: web::Data<DatabaseConnection>,
+    payload: Payload<communication::baselines::TaskBaseline>,
+) -> Result<Encoded<communication::baselines::TaskBaseline>, MyError> {
+    let task = payload.into_inner();
+    validate_task_baseline(&task)?;
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    ensure_baseline_editable(&txn, task.baseline_id).await?;
+    entity::tasks::Entity::find_by_id(task.task_id)
+        .one(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task: {}", e);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("task {}", task.task_id)))?;
+    let existing = entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::BaselineId.eq(task.baseline_id))
+        .filter(entity::tasks_baselines::Column::TaskId.eq(task.task_id))
+        .count(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task baselines: {}", e);
+            MyError::DatabaseError
+        })?;
+    if existing > 0 {
+        return Err(MyError::Conflict(format!(
+            "task {} is already part of baseline {}",
+            task.task_id, task.baseline_id
+        )));
+    }
+    ensure_parent_in_baseline(&txn, task.baseline_id, Some(task.task_id), task.parent).await?;
+
+    let inserted = entity::tasks_baselines::ActiveModel {
+        task_id: Set(task.task_id),
+        baseline_id: Set(task.baseline_id),
+        wbs: Set(task.wbs),
+        parent: Set(task.parent),
+        start: Set(task.start.naive()),
+        start_timezone: Set(task.start_timezone),
+        finish: Set(task.finish.naive()),
+        finish_timezone: Set(task.finish_timezone),
+        milestone: Set(task.milestone),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|e| {
+        error!("Database error inserting task baseline: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let stored = find_task_baseline(&txn, inserted.task_baseline_id).await?;
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::created(stored))
+}
+
+/// Updates the planned part of a task baseline. The summary, description and comment of its task are
+/// shared by every baseline, so they only change through [`update_task`].
+pub async fn update_task_baseline(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    payload: Payload<communication::baselines::TaskBaseline>,
+) -> Result<Encoded<communication::baselines::TaskBaseline>, MyError> {
+    let task_baseline_id = path.into_inner();
+    let task = payload.into_inner();
+    validate_task_baseline(&task)?;
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let record = entity::tasks_baselines::Entity::find_by_id(task_baseline_id)
+        .one(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task baseline: {}", e);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("task baseline {task_baseline_id}")))?;
+
+    ensure_baseline_editable(&txn, record.baseline_id).await?;
+    ensure_parent_in_baseline(&txn, record.baseline_id, Some(record.task_id), task.parent).await?;
+
+    let shared = entity::tasks::Entity::find_by_id(record.task_id)
+        .one(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task: {}", e);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("task {}", record.task_id)))?;
+    for (field, changed) in [
+        ("task_summary", shared.summary != task.task_summary),
+        (
+            "task_description",
+            shared.description != task.task_description,
+        ),
+        ("task_comment", shared.comment != task.task_comment),
+    ] {
+        if changed {
+            return Err(MyError::InvalidField(
+                field,
+                format!(
+                    "{field} of task {} is shared by every baseline and changed through its task endpoint",
+                    record.task_id
+                ),
+            ));
+        }
+    }
+
+    let mut record = record.into_active_model();
+    record.wbs = Set(task.wbs);
+    record.parent = Set(task.parent);
+    record.start = Set(task.start.naive());
+    record.start_timezone = Set(task.start_timezone);
+    record.finish = Set(task.finish.naive());
+    record.finish_timezone = Set(task.finish_timezone);
+    record.milestone = Set(task.milestone);
+    record.update(&txn).await.map_err(|e| {
+        error!("Database error updating task baseline: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let stored = find_task_baseline(&txn, task_baseline_id).await?;
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::ok(stored))
+}
+
+/// Removes a task from a baseline. A task that is not planned in any other baseline is deleted too.
+pub async fn delete_task_baseline(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, MyError> {
+    let task_baseline_id = path.into_inner();
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let record = entity::tasks_baselines::Entity::find_by_id(task_baseline_id)
+        .one(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task baseline: {}", e);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("task baseline {task_baseline_id}")))?;
+
+    ensure_baseline_editable(&txn, record.baseline_id).await?;
+    ensure_no_subtasks(&txn, record.task_id, Some(record.baseline_id)).await?;
+
+    entity::resources_baselines::Entity::delete_many()
+        .filter(entity::resources_baselines::Column::TaskId.eq(record.task_id))
+        .filter(entity::resources_baselines::Column::BaselineId.eq(record.baseline_id))
+        .exec(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error deleting resource allocations: {}", e);
+            MyError::DatabaseError
+        })?;
+    entity::task_dependencies::Entity::delete_many()
+        .filter(entity::task_dependencies::Column::BaselineId.eq(record.baseline_id))
+        .filter(
+            Condition::any()
+                .add(entity::task_dependencies::Column::PredecessorId.eq(record.task_id))
+                .add(entity::task_dependencies::Column::SuccessorId.eq(record.task_id)),
+        )
+        .exec(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error deleting task dependencies: {}", e);
+            MyError::DatabaseError
+        })?;
+    entity::tasks_baselines::Entity::delete_by_id(task_baseline_id)
+        .exec(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error deleting task baseline: {}", e);
+            MyError::DatabaseError
+        })?;
+
+    let remaining = entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::TaskId.eq(record.task_id))
+        .count(&txn)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task baselines: {}", e);
+            MyError::DatabaseError
+        })?;
+    if remaining == 0 {
+        delete_unplanned_task(&txn, record.task_id).await?;
+    }
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,242 @@
+use actix_web::{HttpResponse, Result, web};
+use communication::resources::TeamMember;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    EntityTrait, IntoActiveModel, QueryFilter, QueryOrder, TransactionTrait,
+};
+use tracing::error;
+
+use crate::MyError;
+use crate::resources::{find_resource, find_resource_type};
+use crate::response::{Encoded, Payload};
+
+/// Name of the resource type team members are linked to.
+const PERSONNEL: &str = "Personnel";
+
+pub trait IntoModelTeamMember {
+    fn into_model_team_member(self) -> TeamMember;
+}
+
+impl IntoModelTeamMember for entity::team_members::Model {
+    fn into_model_team_member(self) -> TeamMember {
+        TeamMember {
+            user_id: self.user_id,
+            user_name: self.user_name,
+            user_last_name: self.user_last_name,
+            position: self.position,
+            comment: self.comment,
+            resource_id: self.resource_id,
+        }
+    }
+}
+
+pub async fn find_team_member<C: ConnectionTrait>(
+    db: &C,
+    user_id: i64,
+) -> Result<entity::team_members::Model, MyError> {
+    entity::team_members::Entity::find_by_id(user_id)
+        .one(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching team member: {}", e);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("team member {user_id}")))
+}
+
+/// The team member linked to a resource, if any.
+pub async fn find_resource_team_member<C: ConnectionTrait>(
+    db: &C,
+    resource_id: i64,
+) -> Result<Option<entity::team_members::Model>, MyError> {
+    entity::team_members::Entity::find()
+        .filter(entity::team_members::Column::ResourceId.eq(resource_id))
+        .one(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching team member: {}", e);
+            MyError::DatabaseError
+        })
+}
+
+async fn is_personnel<C: ConnectionTrait>(db: &C, resource_type_id: i64) -> Result<bool, MyError> {
+    Ok(find_resource_type(db, resource_type_id).await?.name == PERSONNEL)
+}
+
+/// Checks that a resource linked to a team member keeps the Personnel type.
+pub async fn ensure_retype_allowed<C: ConnectionTrait>(
+    db: &C,
+    resource_id: i64,
+    resource_type_id: i64,
+) -> Result<(), MyError> {
+    if is_personnel(db, resource_type_id).await? {
+        return Ok(());
+    }
+    match find_resource_team_member(db, resource_id).await? {
+        Some(team_member) => Err(MyError::Conflict(format!(
+            "resource {resource_id} is linked to team member {} and must stay {PERSONNEL}",
+            team_member.user_id
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Checks the names of the member and that its resource is a Personnel resource not linked to
+/// another member.
+async fn validate_team_member<C: ConnectionTrait>(
+    db: &C,
+    team_member: &TeamMember,
+    user_id: Option<i64>,
+) -> Result<(), MyError> {
+    if team_member.user_name.trim().is_empty() {
+        return Err(MyError::InvalidField(
+            "user_name",
+            "user_name must not be empty".to_string(),
+        ));
+    }
+    if team_member.user_last_name.trim().is_empty() {
+        return Err(MyError::InvalidField(
+            "user_last_name",
+            "user_last_name must not be empty".to_string(),
+        ));
+    }
+
+    let resource = match find_resource(db, team_member.resource_id).await {
+        Err(MyError::NotFound(_)) => {
+            return Err(MyError::InvalidField(
+                "resource_id",
+                format!("resource {} does not exist", team_member.resource_id),
+            ));
+        }
+        result => result?,
+    };
+    if !is_personnel(db, resource.resource_type_id).await? {
+        return Err(MyError::InvalidField(
+            "resource_id",
+            format!(
+                "resource {} is not of type {PERSONNEL}",
+                team_member.resource_id
+            ),
+        ));
+    }
+    if let Some(linked) = find_resource_team_member(db, team_member.resource_id).await?
+        && Some(linked.user_id) != user_id
+    {
+        return Err(MyError::Conflict(format!(
+            "resource {} is already linked to team member {}",
+            team_member.resource_id, linked.user_id
+        )));
+    }
+    Ok(())
+}
+
+/// All team members ordered by last name.
+pub async fn get_team_members(
+    db: web::Data<DatabaseConnection>,
+) -> Result<Encoded<Vec<TeamMember>>, MyError> {
+    let team_members = entity::team_members::Entity::find()
+        .order_by_asc(entity::team_members::Column::UserLastName)
+        .order_by_asc(entity::team_members::Column::UserName)
+        .all(db.get_ref())
+        .await
+        .map_err(|e| {
+            error!("Database error fetching team members: {}", e);
+            MyError::DatabaseError
+        })?
+        .into_iter()
+        .map(|record| record.into_model_team_member())
+        .collect();
+
+    Ok(Encoded::ok(team_members))
+}
+
+pub async fn create_team_member(
+    db: web::Data<DatabaseConnection>,
+    payload: Payload<TeamMember>,
+) -> Result<Encoded<TeamMember>, MyError> {
+    let team_member = payload.into_inner();
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    validate_team_member(&txn, &team_member, None).await?;
+
+    let inserted = entity::team_members::ActiveModel {
+        user_name: Set(team_member.user_name),
+        user_last_name: Set(team_member.user_last_name),
+        position: Set(team_member.position),
+        comment: Set(team_member.comment),
+        resource_id: Set(team_member.resource_id),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|e| {
+        error!("Database error inserting team member: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::created(inserted.into_model_team_member()))
+}
+
+/// Renames a member or links it to another Personnel resource.
+pub async fn update_team_member(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    payload: Payload<TeamMember>,
+) -> Result<Encoded<TeamMember>, MyError> {
+    let user_id = path.into_inner();
+    let team_member = payload.into_inner();
+
+    let txn = db.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    let record = find_team_member(&txn, user_id).await?;
+    validate_team_member(&txn, &team_member, Some(user_id)).await?;
+
+    let mut record = record.into_active_model();
+    record.user_name = Set(team_member.user_name);
+    record.user_last_name = Set(team_member.user_last_name);
+    record.position = Set(team_member.position);
+    record.comment = Set(team_member.comment);
+    record.resource_id = Set(team_member.resource_id);
+    let updated = record.update(&txn).await.map_err(|e| {
+        error!("Database error updating team member: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    txn.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::ok(updated.into_model_team_member()))
+}
+
+/// Removes the member; its resource, allocations and time entries stay.
+pub async fn delete_team_member(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, MyError> {
+    let user_id = path.into_inner();
+    find_team_member(db.get_ref(), user_id).await?;
+
+    entity::team_members::Entity::delete_by_id(user_id)
+        .exec(db.get_ref())
+        .await
+        .map_err(|e| {
+            error!("Database error deleting team member: {}", e);
+            MyError::DatabaseError
+        })?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
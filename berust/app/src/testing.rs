@@ -0,0 +1,130 @@
+//! Builders for the unit tests of the pure planning functions.
+
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+use communication::actuals::TaskProgress;
+use communication::baselines::{DependencyType, ResourceAllocation, TaskBaseline, TaskDependency};
+use communication::calendars::WorkHours;
+
+use crate::working_time::WorkingCalendar;
+
+/// `hours` after the start of 2025, UTC.
+pub fn at(hours: i64) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2025, 1, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        + TimeDelta::hours(hours)
+}
+
+/// A top level task planned from `start` to `finish` hours after the start of 2025.
+pub fn task(task_id: i64, start: i64, finish: i64) -> TaskBaseline {
+    TaskBaseline {
+        task_baseline_id: task_id,
+        task_id,
+        baseline_id: 1,
+        task_summary: format!("task {task_id}"),
+        task_description: None,
+        task_comment: None,
+        wbs: task_id.to_string(),
+        parent: None,
+        start: at(start).into(),
+        start_timezone: "UTC".to_string(),
+        finish: at(finish).into(),
+        finish_timezone: "UTC".to_string(),
+        milestone: false,
+    }
+}
+
+/// Day `day` of January 2025.
+pub fn day(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 1, day).unwrap()
+}
+
+/// Progress of a task assessed on day `on` of January 2025, started on the 1st.
+pub fn progress(task_id: i64, on: u32, percent_complete: f64) -> TaskProgress {
+    TaskProgress {
+        task_progress_id: task_id * 100 + on as i64,
+        task_id,
+        status_date: day(on).into(),
+        percent_complete,
+        actual_start: Some(day(1).into()),
+        actual_finish: None,
+        remaining_work: None,
+        actual_cost: None,
+        cost_currency: None,
+    }
+}
+
+pub fn allocation(task_id: i64, resource_id: i64, capacity_allocated: f64) -> ResourceAllocation {
+    ResourceAllocation {
+        resource_baseline_id: task_id * 1000 + resource_id,
+        baseline_id: 1,
+        resource_id,
+        task_id,
+        resource_summary: Some(format!("resource {resource_id}")),
+        task_summary: Some(format!("task {task_id}")),
+        capacity_allocated: Some(capacity_allocated),
+        capacity: None,
+        capacity_unit: None,
+    }
+}
+
+pub fn link(
+    predecessor_id: i64,
+    successor_id: i64,
+    dependency_type: DependencyType,
+    lag_hours: i64,
+) -> TaskDependency {
+    TaskDependency {
+        task_dependency_id: predecessor_id * 100 + successor_id,
+        baseline_id: 1,
+        predecessor_id,
+        successor_id,
+        dependency_type,
+        lag: lag_hours * 3600,
+    }
+}
+
+/// Work on `weekday`, 0 for Monday, from `start` to `finish` o'clock.
+pub fn work_hours(weekday: u8, start: u16, finish: u16) -> WorkHours {
+    WorkHours {
+        calendar_work_hours_id: weekday as i64,
+        calendar_id: 1,
+        weekday,
+        start_minute: start * 60,
+        finish_minute: finish * 60,
+    }
+}
+
+/// Monday to Friday from 8 to 16 o'clock.
+pub fn office_hours() -> Vec<WorkHours> {
+    (0..5).map(|weekday| work_hours(weekday, 8, 16)).collect()
+}
+
+/// A UTC calendar with office hours and no exceptions.
+pub fn office_calendar() -> WorkingCalendar {
+    WorkingCalendar::inherit(chrono_tz::UTC, &[(office_hours(), Vec::new())])
+}
+
+/// An active resource billed `cost` USD per `billing_frequency`, without a calendar of its own.
+pub fn resource(
+    resource_id: i64,
+    cost: f64,
+    billing_frequency: Option<entity::resources::Frequency>,
+) -> entity::resources::Model {
+    entity::resources::Model {
+        resource_id,
+        summary: format!("resource {resource_id}"),
+        resource_type_id: 1,
+        description: None,
+        comment: None,
+        cost: Some(cost),
+        cost_currency: "USD".to_string(),
+        billing_frequency,
+        billing_interval: Some(1),
+        calendar_id: None,
+        capacity: Some(1.),
+        capacity_unit: None,
+        is_active: true,
+    }
+}
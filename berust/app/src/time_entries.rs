@@ -0,0 +1,215 @@
+use actix_web::{HttpResponse, Result, web};
+use chrono::NaiveDate;
+use communication::actuals::TimeEntry;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder,
+};
+use std::collections::HashMap;
+use tracing::error;
+
+use crate::MyError;
+use crate::response::{Encoded, Payload};
+use crate::team_members::find_team_member;
+
+pub trait IntoModelTimeEntry {
+    fn into_model_time_entry(self) -> TimeEntry;
+}
+
+impl IntoModelTimeEntry for entity::time_entries::Model {
+    fn into_model_time_entry(self) -> TimeEntry {
+        TimeEntry {
+            time_entry_id: self.time_entry_id,
+            resource_id: self.resource_id,
+            task_id: self.task_id,
+            start: self.start.into(),
+            finish: self.finish.into(),
+            hours: self.hours,
+            note: self.note,
+        }
+    }
+}
+
+async fn find_time_entry<C: ConnectionTrait>(
+    db: &C,
+    time_entry_id: i64,
+) -> Result<entity::time_entries::Model, MyError> {
+    entity::time_entries::Entity::find_by_id(time_entry_id)
+        .one(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching time entry: {}", e);
+            MyError::DatabaseError
+        })?
+        .ok_or_else(|| MyError::NotFound(format!("time entry {time_entry_id}")))
+}
+
+/// Time entries, optionally of one `resource_id`, team member `user_id` or `task_id` and
+/// overlapping the days `from` to `to`, ordered by date.
+pub async fn get_time_entries(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<Encoded<Vec<TimeEntry>>, MyError> {
+    let resource_id: Option<i64> = crate::optional_query_param(&query, "resource_id")?;
+    let task_id: Option<i64> = crate::optional_query_param(&query, "task_id")?;
+    let from: Option<NaiveDate> = crate::optional_query_param(&query, "from")?;
+    let to: Option<NaiveDate> = crate::optional_query_param(&query, "to")?;
+    let user_id: Option<i64> = crate::optional_query_param(&query, "user_id")?;
+
+    let mut select = entity::time_entries::Entity::find();
+    if let Some(resource_id) = resource_id {
+        select = select.filter(entity::time_entries::Column::ResourceId.eq(resource_id));
+    }
+    if let Some(user_id) = user_id {
+        let team_member = find_team_member(db.get_ref(), user_id).await?;
+        select =
+            select.filter(entity::time_entries::Column::ResourceId.eq(team_member.resource_id));
+    }
+    if let Some(task_id) = task_id {
+        select = select.filter(entity::time_entries::Column::TaskId.eq(task_id));
+    }
+    if let Some(from) = from {
+        select = select.filter(entity::time_entries::Column::Finish.gte(from));
+    }
+    if let Some(to) = to {
+        select = select.filter(entity::time_entries::Column::Start.lte(to));
+    }
+
+    let time_entries = select
+        .order_by_asc(entity::time_entries::Column::Start)
+        .order_by_asc(entity::time_entries::Column::TimeEntryId)
+        .all(db.get_ref())
+        .await
+        .map_err(|e| {
+            error!("Database error fetching time entries: {}", e);
+            MyError::DatabaseError
+        })?
+        .into_iter()
+        .map(|record| record.into_model_time_entry())
+        .collect();
+
+    Ok(Encoded::ok(time_entries))
+}
+
+/// Checks the period and hours of the entry and that its resource and task exist.
+async fn validate_time_entry<C: ConnectionTrait>(
+    db: &C,
+    time_entry: &TimeEntry,
+) -> Result<(), MyError> {
+    if time_entry.finish < time_entry.start {
+        return Err(MyError::InvalidField(
+            "finish",
+            "finish must not be before start".to_string(),
+        ));
+    }
+    let days = (time_entry.finish.naive() - time_entry.start.naive()).num_days() + 1;
+    if !(time_entry.hours > 0. && time_entry.hours <= 24. * days as f64) {
+        return Err(MyError::InvalidField(
+            "hours",
+            format!(
+                "hours must be positive and at most {} for the period",
+                24 * days
+            ),
+        ));
+    }
+
+    let resources = entity::resources::Entity::find_by_id(time_entry.resource_id)
+        .count(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching resource: {}", e);
+            MyError::DatabaseError
+        })?;
+    if resources == 0 {
+        return Err(MyError::InvalidField(
+            "resource_id",
+            format!("resource {} does not exist", time_entry.resource_id),
+        ));
+    }
+    let tasks = entity::tasks::Entity::find_by_id(time_entry.task_id)
+        .count(db)
+        .await
+        .map_err(|e| {
+            error!("Database error fetching task: {}", e);
+            MyError::DatabaseError
+        })?;
+    if tasks == 0 {
+        return Err(MyError::InvalidField(
+            "task_id",
+            format!("task {} does not exist", time_entry.task_id),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn create_time_entry(
+    db: web::Data<DatabaseConnection>,
+    payload: Payload<TimeEntry>,
+) -> Result<Encoded<TimeEntry>, MyError> {
+    let time_entry = payload.into_inner();
+    validate_time_entry(db.get_ref(), &time_entry).await?;
+
+    let inserted = entity::time_entries::ActiveModel {
+        resource_id: Set(time_entry.resource_id),
+        task_id: Set(time_entry.task_id),
+        start: Set(time_entry.start.naive()),
+        finish: Set(time_entry.finish.naive()),
+        hours: Set(time_entry.hours),
+        note: Set(time_entry.note),
+        ..Default::default()
+    }
+    .insert(db.get_ref())
+    .await
+    .map_err(|e| {
+        error!("Database error inserting time entry: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::created(inserted.into_model_time_entry()))
+}
+
+/// Corrects an entry. An imported entry is overwritten again by the next import of its source.
+pub async fn update_time_entry(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    payload: Payload<TimeEntry>,
+) -> Result<Encoded<TimeEntry>, MyError> {
+    let time_entry_id = path.into_inner();
+    let time_entry = payload.into_inner();
+
+    let record = find_time_entry(db.get_ref(), time_entry_id).await?;
+    validate_time_entry(db.get_ref(), &time_entry).await?;
+
+    let mut record = record.into_active_model();
+    record.resource_id = Set(time_entry.resource_id);
+    record.task_id = Set(time_entry.task_id);
+    record.start = Set(time_entry.start.naive());
+    record.finish = Set(time_entry.finish.naive());
+    record.hours = Set(time_entry.hours);
+    record.note = Set(time_entry.note);
+
+    let updated = record.update(db.get_ref()).await.map_err(|e| {
+        error!("Database error updating time entry: {}", e);
+        MyError::DatabaseError
+    })?;
+
+    Ok(Encoded::ok(updated.into_model_time_entry()))
+}
+
+pub async fn delete_time_entry(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, MyError> {
+    let time_entry_id = path.into_inner();
+    find_time_entry(db.get_ref(), time_entry_id).await?;
+
+    entity::time_entries::Entity::delete_by_id(time_entry_id)
+        .exec(db.get_ref())
+        .await
+        .map_err(|e| {
+            error!("Database error deleting time entry: {}", e);
+            MyError::DatabaseError
+        })?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
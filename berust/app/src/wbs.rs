@@ -0,0 +1,651 @@
+use actix_web::{Result, web};
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use communication::actuals::TaskProgress;
+use communication::baselines::{ResourceAllocation, TaskBaseline};
+use communication::wbs::{WbsNode, WbsTree};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, EntityTrait, FromQueryResult,
+    PaginatorTrait, QueryFilter, Statement, Value,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tracing::error;
+
+use crate::MyError;
+use crate::baselines::ensure_baseline_exists;
+use crate::calendars::find_resource_calendars;
+use crate::costs::{default_timezone, report_month, spread_cost};
+use crate::exchange_rates::ReportingCurrency;
+use crate::progress::find_latest_progress;
+use crate::resources::{find_resource_allocations, find_resources_by_id};
+use crate::response::Encoded;
+use crate::working_time::WorkingCalendar;
+
+/// Columns of `TaskBaseline`, selected from `tasks_baselines tb` joined with `tasks t`.
+const TASK_BASELINE_COLUMNS: &str = "tb.task_baseline_id, tb.task_id, tb.baseline_id, \
+    t.summary AS task_summary, t.description AS task_description, t.comment AS task_comment, \
+    tb.wbs, tb.parent, tb.start, tb.start_timezone, tb.finish, tb.finish_timezone, tb.milestone";
+
+/// Top level tasks have no parent planned in the same baseline.
+const TOP_LEVEL: &str = "(tb.parent IS NULL OR tb.parent = tb.task_id OR NOT EXISTS (\
+    SELECT 1 FROM tasks_baselines p WHERE p.baseline_id = $1 AND p.task_id = tb.parent))";
+
+async fn find_task_baselines_by_statement<C: ConnectionTrait>(
+    db: &C,
+    sql: String,
+    values: Vec<Value>,
+) -> Result<Vec<TaskBaseline>, MyError> {
+    TaskBaseline::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        sql,
+        values,
+    ))
+    .all(db)
+    .await
+    .map_err(|db_err| {
+        error!("Database error fetching wbs: {}", db_err);
+        MyError::DatabaseError
+    })
+}
+
+/// Reads `root` and all tasks below it, or every task of the baseline without a root.
+///
+/// The path of each row guards the recursion against cycles in `parent`.
+async fn find_subtree<C: ConnectionTrait>(
+    db: &C,
+    baseline_id: i64,
+    root: Option<i64>,
+) -> Result<Vec<TaskBaseline>, MyError> {
+    let anchor = match root {
+        Some(_) => "tb.task_id = $2",
+        None => TOP_LEVEL,
+    };
+    let sql = format!(
+        "WITH RECURSIVE subtree (task_id, path) AS ( \
+            SELECT tb.task_id, ARRAY[tb.task_id] \
+            FROM tasks_baselines tb \
+            WHERE tb.baseline_id = $1 AND {anchor} \
+          UNION ALL \
+            SELECT tb.task_id, s.path || tb.task_id \
+            FROM tasks_baselines tb \
+            JOIN subtree s ON tb.parent = s.task_id \
+            WHERE tb.baseline_id = $1 AND tb.task_id <> ALL(s.path) \
+        ) \
+        SELECT {TASK_BASELINE_COLUMNS} \
+        FROM subtree s \
+        JOIN tasks_baselines tb ON tb.baseline_id = $1 AND tb.task_id = s.task_id \
+        LEFT JOIN tasks t ON t.task_id = tb.task_id \
+        ORDER BY tb.wbs, tb.task_baseline_id"
+    );
+    let mut values = vec![baseline_id.into()];
+    values.extend(root.map(Value::from));
+
+    find_task_baselines_by_statement(db, sql, values).await
+}
+
+/// Reads the ancestors of a task, from its top level task down to its parent.
+pub async fn find_ancestors<C: ConnectionTrait>(
+    db: &C,
+    baseline_id: i64,
+    task_id: i64,
+) -> Result<Vec<TaskBaseline>, MyError> {
+    let sql = format!(
+        "WITH RECURSIVE ancestors (task_id, parent, depth, path) AS ( \
+            SELECT tb.task_id, tb.parent, 0, ARRAY[tb.task_id] \
+            FROM tasks_baselines tb \
+            WHERE tb.baseline_id = $1 AND tb.task_id = $2 \
+          UNION ALL \
+            SELECT tb.task_id, tb.parent, a.depth + 1, a.path || tb.task_id \
+            FROM tasks_baselines tb \
+            JOIN ancestors a ON tb.task_id = a.parent \
+            WHERE tb.baseline_id = $1 AND tb.task_id <> ALL(a.path) \
+        ) \
+        SELECT {TASK_BASELINE_COLUMNS} \
+        FROM ancestors a \
+        JOIN tasks_baselines tb ON tb.baseline_id = $1 AND tb.task_id = a.task_id \
+        LEFT JOIN tasks t ON t.task_id = tb.task_id \
+        WHERE a.depth > 0 \
+        ORDER BY a.depth DESC"
+    );
+
+    find_task_baselines_by_statement(db, sql, vec![baseline_id.into(), task_id.into()]).await
+}
+
+async fn ensure_task_in_baseline<C: ConnectionTrait>(
+    db: &C,
+    baseline_id: i64,
+    task_id: i64,
+) -> Result<(), MyError> {
+    let count = entity::tasks_baselines::Entity::find()
+        .filter(entity::tasks_baselines::Column::BaselineId.eq(baseline_id))
+        .filter(entity::tasks_baselines::Column::TaskId.eq(task_id))
+        .count(db)
+        .await
+        .map_err(|db_err| {
+            error!("Database error fetching task baseline: {}", db_err);
+            MyError::DatabaseError
+        })?;
+    if count == 0 {
+        return Err(MyError::NotFound(format!(
+            "task {task_id} in baseline {baseline_id}"
+        )));
+    }
+    Ok(())
+}
+
+/// Arranges `tasks` into a tree below `root`, or below the top level tasks without one, and rolls
+/// dates, allocated capacity and cost up from the leaves. Costs are spread over the months of
+/// `timezone` and converted to the reporting currency like in the cost report.
+///
+/// Returns the indexes of the top level nodes together with all nodes, depth first.
+pub fn build_tree(
+    root: Option<i64>,
+    tasks: Vec<TaskBaseline>,
+    allocations: &[ResourceAllocation],
+    resources: &HashMap<i64, entity::resources::Model>,
+    calendars: &HashMap<i64, WorkingCalendar>,
+    timezone: Tz,
+    reporting: &ReportingCurrency,
+) -> Result<(Vec<u32>, Vec<WbsNode>), MyError> {
+    let task_ids: HashSet<i64> = tasks.iter().map(|task| task.task_id).collect();
+    let is_root = |task: &TaskBaseline| match root {
+        Some(root) => task.task_id == root,
+        None => task
+            .parent
+            .is_none_or(|parent| parent == task.task_id || !task_ids.contains(&parent)),
+    };
+
+    let mut top_level = Vec::new();
+    let mut children_of: HashMap<i64, Vec<TaskBaseline>> = HashMap::new();
+    for task in tasks {
+        if is_root(&task) {
+            top_level.push(task);
+        } else if let Some(parent) = task.parent {
+            children_of.entry(parent).or_default().push(task);
+        }
+    }
+
+    let mut allocations_of: HashMap<i64, Vec<&ResourceAllocation>> = HashMap::new();
+    for allocation in allocations {
+        allocations_of
+            .entry(allocation.task_id)
+            .or_default()
+            .push(allocation);
+    }
+
+    let mut roots = Vec::new();
+    let mut nodes: Vec<WbsNode> = Vec::new();
+    let mut parents: Vec<Option<usize>> = Vec::new();
+    // Depth first, children pushed in reverse so they are visited in wbs order.
+    let mut stack: Vec<(TaskBaseline, u32, Option<usize>)> = top_level
+        .into_iter()
+        .rev()
+        .map(|task| (task, 0, None))
+        .collect();
+    while let Some((task, depth, parent)) = stack.pop() {
+        let index = nodes.len();
+        match parent {
+            Some(parent) => nodes[parent].children.push(index as u32),
+            None => roots.push(index as u32),
+        }
+
+        let mut capacity_allocated = 0.;
+        let mut cost = BTreeMap::new();
+        for allocation in allocations_of.get(&task.task_id).into_iter().flatten() {
+            let capacity = allocation.capacity_allocated.unwrap_or(0.);
+            capacity_allocated += capacity;
+            if let (Some(resource), Some(calendar)) = (
+                resources.get(&allocation.resource_id),
+                calendars.get(&allocation.resource_id),
+            ) {
+                for (month, amount) in spread_cost(
+                    resource,
+                    capacity,
+                    task.start.utc(),
+                    task.finish.utc(),
+                    calendar,
+                    timezone,
+                ) {
+                    let (currency, amount) =
+                        report_month(reporting, &resource.cost_currency, month, amount)?;
+                    *cost.entry(currency).or_insert(0.) += amount;
+                }
+            }
+        }
+
+        for child in children_of
+            .remove(&task.task_id)
+            .into_iter()
+            .flatten()
+            .rev()
+        {
+            stack.push((child, depth + 1, Some(index)));
+        }
+        nodes.push(WbsNode {
+            start: task.start,
+            finish: task.finish,
+            task,
+            depth,
+            children: Vec::new(),
+            capacity_allocated,
+            cost,
+            percent_complete: 0.,
+            actual_start: None,
+            actual_finish: None,
+            remaining_work: None,
+        });
+        parents.push(parent);
+    }
+
+    // Parents precede their children, so walking backwards folds every subtree before its parent.
+    for index in (0..nodes.len()).rev() {
+        let Some(parent) = parents[index] else {
+            continue;
+        };
+        let (head, tail) = nodes.split_at_mut(index);
+        let (node, parent) = (&tail[0], &mut head[parent]);
+        parent.start = parent.start.min(node.start);
+        parent.finish = parent.finish.max(node.finish);
+        parent.capacity_allocated += node.capacity_allocated;
+        for (currency, amount) in &node.cost {
+            *parent.cost.entry(currency.clone()).or_insert(0.) += amount;
+        }
+    }
+
+    Ok((roots, nodes))
+}
+
+/// Fills in the progress of every node from the latest assessment of its task. Leaves keep their
+/// own progress, summary tasks combine the leaves below them.
+pub fn roll_up_progress(tree: &mut WbsTree, progress: &HashMap<i64, TaskProgress>) {
+    // Planned seconds of the leaves below each node and how many of them are complete.
+    let mut planned = vec![0.; tree.nodes.len()];
+    let mut complete = vec![0.; tree.nodes.len()];
+    // Children follow their parents, so walking backwards finishes every child first.
+    for index in (0..tree.nodes.len()).rev() {
+        let node = &tree.nodes[index];
+        if node.children.is_empty() {
+            let duration = (node.finish - node.start).num_seconds().max(0) as f64;
+            let assessed = progress.get(&node.task.task_id);
+            let node = &mut tree.nodes[index];
+            if let Some(assessed) = assessed {
+                node.percent_complete = assessed.percent_complete;
+                node.actual_start = assessed.actual_start;
+                node.actual_finish = assessed.actual_finish;
+                node.remaining_work = assessed.remaining_work;
+            }
+            planned[index] = duration;
+            complete[index] = duration * node.percent_complete / 100.;
+            continue;
+        }
+
+        let children: Vec<usize> = node.children.iter().map(|child| *child as usize).collect();
+        planned[index] = children.iter().map(|child| planned[*child]).sum();
+        complete[index] = children.iter().map(|child| complete[*child]).sum();
+        let percent_complete = if planned[index] > 0. {
+            complete[index] / planned[index] * 100.
+        } else {
+            // Only milestones below, which count alike
+            children
+                .iter()
+                .map(|child| tree.nodes[*child].percent_complete)
+                .sum::<f64>()
+                / children.len() as f64
+        };
+        let actual_start = children
+            .iter()
+            .filter_map(|child| tree.nodes[*child].actual_start)
+            .min();
+        let actual_finish = children
+            .iter()
+            .map(|child| tree.nodes[*child].actual_finish)
+            .collect::<Option<Vec<_>>>()
+            .and_then(|finishes| finishes.into_iter().max());
+        let remaining_work = children
+            .iter()
+            .filter_map(|child| tree.nodes[*child].remaining_work)
+            .reduce(|sum, remaining| sum + remaining);
+
+        let node = &mut tree.nodes[index];
+        node.percent_complete = percent_complete;
+        node.actual_start = actual_start;
+        node.actual_finish = actual_finish;
+        node.remaining_work = remaining_work;
+    }
+}
+
+async fn find_wbs_tree<C: ConnectionTrait>(
+    db: &C,
+    baseline_id: i64,
+    root: Option<i64>,
+    reporting: &ReportingCurrency,
+    status_date: Option<NaiveDate>,
+) -> Result<WbsTree, MyError> {
+    let tasks = find_subtree(db, baseline_id, root).await?;
+    let allocations = find_resource_allocations(db, baseline_id).await?;
+    let resources = find_resources_by_id(
+        db,
+        allocations.iter().map(|allocation| allocation.resource_id),
+    )
+    .await?;
+    let calendars = find_resource_calendars(db, &resources).await?;
+
+    let timezone = default_timezone(db).await?;
+    let progress = find_latest_progress(db, status_date).await?;
+
+    let (roots, nodes) = build_tree(
+        root,
+        tasks,
+        &allocations,
+        &resources,
+        &calendars,
+        timezone,
+        reporting,
+    )?;
+    let mut tree = WbsTree {
+        baseline_id,
+        currency: reporting.currency(),
+        status_date: status_date.map(|status_date| status_date.into()),
+        roots,
+        nodes,
+    };
+    roll_up_progress(&mut tree, &progress);
+    Ok(tree)
+}
+
+/// The whole WBS of a baseline, with costs optionally converted to one reporting `currency` and
+/// progress as of an optional `status_date`.
+pub async fn get_wbs(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i64>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<Encoded<WbsTree>, MyError> {
+    let baseline_id = path.into_inner();
+    let reporting = ReportingCurrency::from_query(db.get_ref(), &query).await?;
+    let status_date: Option<NaiveDate> = crate::optional_query_param(&query, "status_date")?;
+    ensure_baseline_exists(db.get_ref(), baseline_id).await?;
+
+    Ok(Encoded::ok(
+        find_wbs_tree(db.get_ref(), baseline_id, None, &reporting, status_date).await?,
+    ))
+}
+
+/// The subtree of a task, with the task as its only root, costs optionally converted to one
+/// reporting `currency` and progress as of an optional `status_date`.
+pub async fn get_wbs_subtree(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<(i64, i64)>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<Encoded<WbsTree>, MyError> {
+    let (baseline_id, task_id) = path.into_inner();
+    let reporting = ReportingCurrency::from_query(db.get_ref(), &query).await?;
+    let status_date: Option<NaiveDate> = crate::optional_query_param(&query, "status_date")?;
+    ensure_baseline_exists(db.get_ref(), baseline_id).await?;
+    ensure_task_in_baseline(db.get_ref(), baseline_id, task_id).await?;
+
+    Ok(Encoded::ok(
+        find_wbs_tree(
+            db.get_ref(),
+            baseline_id,
+            Some(task_id),
+            &reporting,
+            status_date,
+        )
+        .await?,
+    ))
+}
+
+/// The ancestors of a task, from its top level task down to its parent.
+pub async fn get_wbs_ancestors(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<(i64, i64)>,
+) -> Result<Encoded<Vec<TaskBaseline>>, MyError> {
+    let (baseline_id, task_id) = path.into_inner();
+    ensure_baseline_exists(db.get_ref(), baseline_id).await?;
+    ensure_task_in_baseline(db.get_ref(), baseline_id, task_id).await?;
+
+    Ok(Encoded::ok(
+        find_ancestors(db.get_ref(), baseline_id, task_id).await?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{allocation, at, day, office_calendar, progress, resource, task};
+
+    fn child(task_id: i64, parent: i64, start: i64, finish: i64) -> TaskBaseline {
+        TaskBaseline {
+            parent: Some(parent),
+            wbs: format!("{parent}.{task_id}"),
+            ..task(task_id, start, finish)
+        }
+    }
+
+    fn wbs(
+        root: Option<i64>,
+        tasks: Vec<TaskBaseline>,
+        allocations: &[ResourceAllocation],
+    ) -> WbsTree {
+        let mut euro = resource(20, 10., None);
+        euro.cost_currency = "EUR".to_string();
+        let resources = HashMap::from([(10, resource(10, 100., None)), (20, euro)]);
+        let calendars = HashMap::from([(10, office_calendar()), (20, office_calendar())]);
+        let (roots, nodes) = build_tree(
+            root,
+            tasks,
+            allocations,
+            &resources,
+            &calendars,
+            chrono_tz::UTC,
+            &ReportingCurrency::default(),
+        )
+        .unwrap();
+        WbsTree {
+            baseline_id: 1,
+            currency: None,
+            status_date: None,
+            roots,
+            nodes,
+        }
+    }
+
+    /// Task id and depth of every node, depth first.
+    fn outline(tree: &WbsTree) -> Vec<(i64, u32)> {
+        tree.nodes
+            .iter()
+            .map(|node| (node.task.task_id, node.depth))
+            .collect()
+    }
+
+    #[test]
+    fn dates_and_capacity_roll_up_to_the_parents() {
+        let tasks = vec![
+            task(1, 5, 6),
+            child(2, 1, 0, 10),
+            child(3, 1, 4, 20),
+            child(4, 3, 8, 30),
+            task(5, 40, 50),
+        ];
+        let allocations = [
+            allocation(1, 10, 0.1),
+            allocation(2, 10, 0.5),
+            allocation(3, 10, 0.25),
+            allocation(4, 10, 0.125),
+        ];
+        let tree = wbs(None, tasks, &allocations);
+        assert_eq!(outline(&tree), vec![(1, 0), (2, 1), (3, 1), (4, 2), (5, 0)]);
+        assert_eq!(tree.roots, vec![0, 4]);
+        assert_eq!(tree.nodes[0].children, vec![1, 2]);
+
+        let span = |index: usize| {
+            let node = &tree.nodes[index];
+            (
+                node.start.naive(),
+                node.finish.naive(),
+                node.capacity_allocated,
+            )
+        };
+        assert_eq!(span(0), (at(0), at(30), 0.975));
+        assert_eq!(span(2), (at(4), at(30), 0.375));
+        assert_eq!(span(4), (at(40), at(50), 0.));
+    }
+
+    #[test]
+    fn a_subtree_root_keeps_its_parent_out() {
+        let tasks = vec![child(2, 1, 0, 10), child(3, 2, 5, 15)];
+        let tree = wbs(Some(2), tasks.clone(), &[]);
+        assert_eq!(outline(&tree), vec![(2, 0), (3, 1)]);
+        assert_eq!(tree.nodes[0].finish.naive(), at(15));
+
+        // Without a root, tasks whose parent is missing are top level.
+        let tree = wbs(None, tasks, &[]);
+        assert_eq!(outline(&tree), vec![(2, 0), (3, 1)]);
+    }
+
+    #[test]
+    fn costs_stay_apart_per_currency() {
+        let tasks = vec![task(1, 0, 10), child(2, 1, 0, 10), child(3, 1, 0, 10)];
+        let allocations = [
+            allocation(2, 10, 0.5),
+            allocation(3, 10, 1.),
+            allocation(3, 20, 2.),
+        ];
+        let tree = wbs(None, tasks, &allocations);
+        assert_eq!(
+            tree.nodes[0].cost,
+            BTreeMap::from([("EUR".to_string(), 20.), ("USD".to_string(), 150.)])
+        );
+        assert_eq!(
+            tree.nodes[1].cost,
+            BTreeMap::from([("USD".to_string(), 50.)])
+        );
+    }
+
+    #[test]
+    fn converted_costs_match_the_cost_report() {
+        // Monthly EUR billing over January and February, with the rate changing on 1 February.
+        let mut euro = resource(20, 3000., Some(entity::resources::Frequency::Monthly));
+        euro.cost_currency = "EUR".to_string();
+        let resources = HashMap::from([(20, euro)]);
+        let calendars = HashMap::from([(20, office_calendar())]);
+        let day = |month, day| NaiveDate::from_ymd_opt(2025, month, day).unwrap();
+        let reporting = ReportingCurrency::new(
+            "USD",
+            HashMap::from([(
+                ("EUR".to_string(), "USD".to_string()),
+                vec![(day(1, 1), 1.1), (day(2, 1), 1.2)],
+            )]),
+        );
+        let tasks = vec![task(1, 15 * 24, 46 * 24)];
+        let allocations = [allocation(1, 20, 1.)];
+
+        let (_, nodes) = build_tree(
+            None,
+            tasks.clone(),
+            &allocations,
+            &resources,
+            &calendars,
+            chrono_tz::UTC,
+            &reporting,
+        )
+        .unwrap();
+        let report = crate::costs::cost_report(
+            1,
+            chrono_tz::UTC,
+            &tasks,
+            &allocations,
+            &resources,
+            &calendars,
+            &reporting,
+        )
+        .unwrap();
+        let (wbs, total) = (nodes[0].cost["USD"], report.total["USD"]);
+        assert!((wbs - total).abs() < 1e-9, "{wbs} != {total}");
+        assert_eq!(nodes[0].cost.len(), 1);
+    }
+
+    /// Percent complete, actual start and finish and remaining work of a node.
+    type Progress = (f64, Option<NaiveDate>, Option<NaiveDate>, Option<f64>);
+
+    /// Rolled up progress of every node, depth first.
+    fn rolled_up(tasks: Vec<TaskBaseline>, progress: &[TaskProgress]) -> Vec<Progress> {
+        let mut tree = wbs(None, tasks, &[]);
+        let progress: HashMap<i64, TaskProgress> = progress
+            .iter()
+            .map(|progress| (progress.task_id, progress.clone()))
+            .collect();
+        roll_up_progress(&mut tree, &progress);
+        tree.nodes
+            .iter()
+            .map(|node| {
+                (
+                    node.percent_complete,
+                    node.actual_start.map(|date| date.naive()),
+                    node.actual_finish.map(|date| date.naive()),
+                    node.remaining_work,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn percent_complete_is_weighted_by_planned_duration() {
+        // 5 of the 10 hours of 2 and 3 of the 30 hours of 3 are done, 4 is not started.
+        let tasks = vec![
+            task(1, 0, 40),
+            child(2, 1, 0, 10),
+            child(3, 1, 10, 40),
+            task(4, 0, 10),
+        ];
+        let progress = [progress(2, 5, 50.), progress(3, 5, 10.)];
+        let nodes = rolled_up(tasks, &progress);
+        let percent: Vec<f64> = nodes.iter().map(|node| node.0).collect();
+        assert_eq!(percent, vec![20., 50., 10., 0.]);
+        assert_eq!(nodes[3].1, None);
+    }
+
+    #[test]
+    fn milestones_alone_count_alike() {
+        let tasks = vec![task(1, 0, 10), child(2, 1, 5, 5), child(3, 1, 10, 10)];
+        let nodes = rolled_up(tasks, &[progress(2, 5, 100.)]);
+        assert_eq!(nodes[0].0, 50.);
+    }
+
+    #[test]
+    fn summary_tasks_finish_with_their_last_subtask() {
+        let tasks = vec![task(1, 0, 20), child(2, 1, 0, 10), child(3, 1, 10, 20)];
+        let finished = |task_id, on| TaskProgress {
+            actual_finish: Some(day(on).into()),
+            ..progress(task_id, on, 100.)
+        };
+        let started = TaskProgress {
+            actual_start: Some(day(3).into()),
+            remaining_work: Some(4.),
+            ..progress(3, 5, 50.)
+        };
+
+        let nodes = rolled_up(tasks.clone(), &[finished(2, 4), started]);
+        assert_eq!(nodes[0], (75., Some(day(1)), None, Some(4.)));
+
+        let nodes = rolled_up(tasks, &[finished(2, 4), finished(3, 9)]);
+        assert_eq!(nodes[0], (100., Some(day(1)), Some(day(9)), None));
+    }
+
+    #[test]
+    fn remaining_work_adds_up_over_the_estimated_subtasks() {
+        let tasks = vec![
+            task(1, 0, 30),
+            child(2, 1, 0, 10),
+            child(3, 1, 10, 20),
+            child(4, 1, 20, 30),
+        ];
+        let estimated = |task_id, remaining_work| TaskProgress {
+            remaining_work: Some(remaining_work),
+            ..progress(task_id, 5, 0.)
+        };
+        let nodes = rolled_up(tasks, &[estimated(2, 4.), estimated(3, 2.5)]);
+        assert_eq!(nodes[0].3, Some(6.5));
+        assert_eq!(nodes[3].3, None);
+    }
+}
@@ -0,0 +1,211 @@
+use chrono::{DateTime, Datelike, NaiveDate, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+use communication::calendars::{CalendarException, CalendarExceptionKind, WorkHours};
+use std::collections::HashMap;
+
+/// Working periods of one day, in minutes since local midnight.
+type Periods = Vec<(u16, u16)>;
+
+/// Work hours and exceptions of a calendar merged with those it inherits, ready to answer how much
+/// working time lies between two instants.
+#[derive(Clone, Debug)]
+pub struct WorkingCalendar {
+    pub timezone: Tz,
+    /// Periods of each weekday, Monday first.
+    week: [Periods; 7],
+    exceptions: HashMap<NaiveDate, Periods>,
+}
+
+impl WorkingCalendar {
+    /// Merges a calendar with its ancestors, given from the calendar itself up to the topmost one.
+    ///
+    /// The weekly pattern comes from the nearest calendar that defines any work hours. Exceptions of
+    /// all calendars apply, the nearest one winning when several fall on the same date; holidays are
+    /// days off whatever hours they carry. Dates are read in `timezone`, the timezone of the
+    /// calendar itself.
+    pub fn inherit(timezone: Tz, chain: &[(Vec<WorkHours>, Vec<CalendarException>)]) -> Self {
+        let mut week: [Periods; 7] = Default::default();
+        if let Some((work_hours, _)) = chain.iter().find(|(work_hours, _)| !work_hours.is_empty()) {
+            for hours in work_hours {
+                if let Some(periods) = week.get_mut(hours.weekday as usize) {
+                    periods.push((hours.start_minute, hours.finish_minute));
+                }
+            }
+        }
+        for periods in &mut week {
+            periods.sort_unstable();
+        }
+
+        let mut exceptions = HashMap::new();
+        for (_, calendar_exceptions) in chain.iter().rev() {
+            for exception in calendar_exceptions {
+                let periods = match (
+                    exception.kind,
+                    exception.start_minute,
+                    exception.finish_minute,
+                ) {
+                    (CalendarExceptionKind::Exception, Some(start), Some(finish)) => {
+                        vec![(start, finish)]
+                    }
+                    _ => Vec::new(),
+                };
+                exceptions.insert(exception.date.naive(), periods);
+            }
+        }
+
+        WorkingCalendar {
+            timezone,
+            week,
+            exceptions,
+        }
+    }
+
+    fn periods(&self, date: NaiveDate) -> &[(u16, u16)] {
+        self.exceptions
+            .get(&date)
+            .unwrap_or(&self.week[date.weekday().num_days_from_monday() as usize])
+    }
+
+    /// The instant of a local minute. Minutes skipped by a DST change fall an hour later, repeated
+    /// ones resolve to their first occurrence.
+    fn instant(&self, date: NaiveDate, minute: u16) -> DateTime<Utc> {
+        let local =
+            date.and_hms_opt(0, 0, 0).unwrap_or_default() + TimeDelta::minutes(minute as i64);
+        self.timezone
+            .from_local_datetime(&local)
+            .earliest()
+            .or_else(|| {
+                self.timezone
+                    .from_local_datetime(&(local + TimeDelta::hours(1)))
+                    .earliest()
+            })
+            .map(|instant| instant.with_timezone(&Utc))
+            .unwrap_or_else(|| local.and_utc())
+    }
+
+    /// Working time between two instants; zero unless `from` precedes `to`.
+    pub fn working_time(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> TimeDelta {
+        let mut total = TimeDelta::zero();
+        if from >= to {
+            return total;
+        }
+
+        let last = to.with_timezone(&self.timezone).date_naive();
+        for date in from
+            .with_timezone(&self.timezone)
+            .date_naive()
+            .iter_days()
+            .take_while(|date| *date <= last)
+        {
+            for (start, finish) in self.periods(date) {
+                let start = self.instant(date, *start).max(from);
+                let finish = self.instant(date, *finish).min(to);
+                if start < finish {
+                    total += finish - start;
+                }
+            }
+        }
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{office_calendar, office_hours, work_hours};
+    use chrono::NaiveTime;
+    use communication::calendars::CalendarExceptionKind;
+
+    fn utc(date: &str, hour: u32) -> DateTime<Utc> {
+        date.parse::<NaiveDate>()
+            .unwrap()
+            .and_time(NaiveTime::from_hms_opt(hour, 0, 0).unwrap())
+            .and_utc()
+    }
+
+    fn exception(
+        date: &str,
+        kind: CalendarExceptionKind,
+        hours: Option<(u16, u16)>,
+    ) -> CalendarException {
+        CalendarException {
+            calendar_exception_id: 1,
+            calendar_id: 1,
+            date: date.parse::<NaiveDate>().unwrap().into(),
+            name: kind.to_string(),
+            kind,
+            start_minute: hours.map(|(start, _)| start * 60),
+            finish_minute: hours.map(|(_, finish)| finish * 60),
+        }
+    }
+
+    #[test]
+    fn office_hours_count_on_working_days_only() {
+        // Friday 2025-01-03 to Monday 2025-01-06, noon to noon.
+        let working = office_calendar().working_time(utc("2025-01-03", 12), utc("2025-01-06", 12));
+        assert_eq!(working, TimeDelta::hours(8));
+    }
+
+    #[test]
+    fn days_of_a_dst_change_are_shorter_or_longer() {
+        let around_the_clock = vec![work_hours(6, 0, 24)];
+        let calendar =
+            WorkingCalendar::inherit(chrono_tz::Europe::Berlin, &[(around_the_clock, Vec::new())]);
+        // Sunday 2025-03-30 loses an hour, Sunday 2025-10-26 gains one.
+        let spring = calendar.working_time(utc("2025-03-29", 23), utc("2025-03-30", 22));
+        assert_eq!(spring, TimeDelta::hours(23));
+        let autumn = calendar.working_time(utc("2025-10-25", 22), utc("2025-10-26", 23));
+        assert_eq!(autumn, TimeDelta::hours(25));
+    }
+
+    #[test]
+    fn holidays_are_days_off_even_with_hours() {
+        // Monday 2025-01-06.
+        let (from, to) = (utc("2025-01-06", 0), utc("2025-01-07", 0));
+        let holiday = exception("2025-01-06", CalendarExceptionKind::Holiday, Some((10, 12)));
+        let calendar = WorkingCalendar::inherit(chrono_tz::UTC, &[(office_hours(), vec![holiday])]);
+        assert_eq!(calendar.working_time(from, to), TimeDelta::zero());
+
+        let short_day = exception(
+            "2025-01-06",
+            CalendarExceptionKind::Exception,
+            Some((10, 12)),
+        );
+        let calendar =
+            WorkingCalendar::inherit(chrono_tz::UTC, &[(office_hours(), vec![short_day])]);
+        assert_eq!(calendar.working_time(from, to), TimeDelta::hours(2));
+    }
+
+    #[test]
+    fn a_calendar_without_hours_works_like_its_parent() {
+        let (from, to) = (utc("2025-01-06", 0), utc("2025-01-08", 0));
+        let parent_day_off = exception("2025-01-07", CalendarExceptionKind::Holiday, None);
+        let calendar = WorkingCalendar::inherit(
+            chrono_tz::UTC,
+            &[
+                (Vec::new(), Vec::new()),
+                (office_hours(), vec![parent_day_off]),
+            ],
+        );
+        assert_eq!(calendar.working_time(from, to), TimeDelta::hours(8));
+    }
+
+    #[test]
+    fn the_nearest_exception_wins() {
+        let (from, to) = (utc("2025-01-07", 0), utc("2025-01-08", 0));
+        let child_short_day = exception(
+            "2025-01-07",
+            CalendarExceptionKind::Exception,
+            Some((8, 11)),
+        );
+        let parent_day_off = exception("2025-01-07", CalendarExceptionKind::Holiday, None);
+        let calendar = WorkingCalendar::inherit(
+            chrono_tz::UTC,
+            &[
+                (Vec::new(), vec![child_short_day]),
+                (office_hours(), vec![parent_day_off]),
+            ],
+        );
+        assert_eq!(calendar.working_time(from, to), TimeDelta::hours(3));
+    }
+}
@@ -0,0 +1,97 @@
+use struct_field_names_as_array::FieldNamesAsArray;
+
+use crate::datetime::Date;
+
+/// Hours a resource reported on a task over a day or a longer period such as a month.
+#[derive(
+    bitcode::Encode,
+    bitcode::Decode,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    PartialEq,
+    Debug,
+    FieldNamesAsArray,
+)]
+pub struct TimeEntry {
+    pub time_entry_id: i64,
+    pub resource_id: i64,
+    pub task_id: i64,
+    /// First day of the period the hours were worked in.
+    pub start: Date,
+    /// Last day of the period, inclusive; equal to `start` for a single day.
+    pub finish: Date,
+    pub hours: f64,
+    pub note: Option<String>,
+}
+
+impl TimeEntry {
+    pub fn fields() -> [&'static str; 7] {
+        TimeEntry::FIELD_NAMES_AS_ARRAY
+    }
+}
+
+/// An expense booked from an invoice or other document, optionally charged to a task.
+#[derive(
+    bitcode::Encode,
+    bitcode::Decode,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    PartialEq,
+    Debug,
+    FieldNamesAsArray,
+)]
+pub struct Expense {
+    pub expense_id: i64,
+    /// Unique number of the document the expense was booked from.
+    pub document_number: String,
+    pub date: Date,
+    pub description: Option<String>,
+    pub amount: f64,
+    pub currency: String,
+    /// Cost type the expense is reported under.
+    pub category: String,
+    pub task_id: Option<i64>,
+}
+
+impl Expense {
+    pub fn fields() -> [&'static str; 8] {
+        Expense::FIELD_NAMES_AS_ARRAY
+    }
+}
+
+/// How far a task had got by a status date.
+#[derive(
+    bitcode::Encode,
+    bitcode::Decode,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    PartialEq,
+    Debug,
+    FieldNamesAsArray,
+)]
+pub struct TaskProgress {
+    pub task_progress_id: i64,
+    pub task_id: i64,
+    /// Day the progress was assessed on.
+    pub status_date: Date,
+    /// From 0 to 100.
+    pub percent_complete: f64,
+    pub actual_start: Option<Date>,
+    /// Set once the task is complete.
+    pub actual_finish: Option<Date>,
+    /// Hours of work still expected, when estimated.
+    pub remaining_work: Option<f64>,
+    /// Cost incurred on the task up to the status date, when known.
+    pub actual_cost: Option<f64>,
+    /// Currency of `actual_cost`, given together with it.
+    pub cost_currency: Option<String>,
+}
+
+impl TaskProgress {
+    pub fn fields() -> [&'static str; 9] {
+        TaskProgress::FIELD_NAMES_AS_ARRAY
+    }
+}
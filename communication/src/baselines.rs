@@ -1,6 +1,16 @@
-use chrono::NaiveDateTime;
 use struct_field_names_as_array::FieldNamesAsArray;
 
+use crate::datetime::Timestamp;
+
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub enum BaselineStatus {
+    Draft,
+    Approved,
+    Locked,
+}
+
 #[derive(
     bitcode::Encode,
     bitcode::Decode,
@@ -17,14 +27,35 @@ pub struct Baseline {
     pub description: Option<String>,
     pub comment: Option<String>,
     pub is_active: bool,
+    pub status: BaselineStatus,
+    pub approved_by: Option<String>,
+    pub approved_at: Option<Timestamp>,
 }
 
 impl Baseline {
-    pub fn fields() -> [&'static str; 5] {
+    pub fn fields() -> [&'static str; 8] {
         Baseline::FIELD_NAMES_AS_ARRAY
     }
 }
 
+/// Who approves a draft baseline; the approval time is recorded by the server.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct BaselineApproval {
+    pub approved_by: String,
+}
+
+/// Name and notes of the baseline created by copying another one.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct BaselineSnapshot {
+    pub name: String,
+    pub description: Option<String>,
+    pub comment: Option<String>,
+}
+
 #[derive(
     bitcode::Encode,
     bitcode::Decode,
@@ -54,9 +85,86 @@ impl ResourceAllocation {
     }
 }
 
+/// A period in which the allocations of a resource add up to more than its capacity.
+#[derive(
+    bitcode::Encode,
+    bitcode::Decode,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    PartialEq,
+    Debug,
+    FieldNamesAsArray,
+)]
+pub struct OverAllocation {
+    pub resource_id: i64,
+    pub resource_summary: String,
+    pub start: Timestamp,
+    pub finish: Timestamp,
+    pub capacity: f64,
+    pub capacity_allocated: f64,
+    /// Tasks the resource is allocated to throughout the period, ordered by id.
+    pub task_ids: Vec<i64>,
+}
+
+impl OverAllocation {
+    pub fn fields() -> [&'static str; 7] {
+        OverAllocation::FIELD_NAMES_AS_ARRAY
+    }
+}
+
+/// How the dates of two linked tasks constrain each other, named predecessor date to successor date.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub enum DependencyType {
+    FinishToStart,
+    StartToStart,
+    FinishToFinish,
+    StartToFinish,
+}
+
+impl std::fmt::Display for DependencyType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DependencyType::FinishToStart => write!(f, "FS"),
+            DependencyType::StartToStart => write!(f, "SS"),
+            DependencyType::FinishToFinish => write!(f, "FF"),
+            DependencyType::StartToFinish => write!(f, "SF"),
+        }
+    }
+}
+
+/// A link between two tasks planned in the same baseline.
+#[derive(
+    bitcode::Encode,
+    bitcode::Decode,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    PartialEq,
+    Debug,
+    FieldNamesAsArray,
+)]
+pub struct TaskDependency {
+    pub task_dependency_id: i64,
+    pub baseline_id: i64,
+    pub predecessor_id: i64,
+    pub successor_id: i64,
+    pub dependency_type: DependencyType,
+    /// Seconds between the linked dates; negative for a lead.
+    pub lag: i64,
+}
+
+impl TaskDependency {
+    pub fn fields() -> [&'static str; 6] {
+        TaskDependency::FIELD_NAMES_AS_ARRAY
+    }
+}
+
 #[derive(
-    // bitcode::Encode,
-    // bitcode::Decode,
+    bitcode::Encode,
+    bitcode::Decode,
     serde::Deserialize,
     serde::Serialize,
     Clone,
@@ -74,14 +182,106 @@ pub struct TaskBaseline {
     pub task_comment: Option<String>,
     pub wbs: String,
     pub parent: Option<i64>,
-    pub start: NaiveDateTime,
+    pub start: Timestamp,
     pub start_timezone: String,
-    pub finish: NaiveDateTime,
+    pub finish: Timestamp,
     pub finish_timezone: String,
+    /// A zero-duration task marking a deliverable date, drawn as a diamond.
+    pub milestone: bool,
 }
 
 impl TaskBaseline {
-    pub fn fields() -> [&'static str; 12] {
+    pub fn fields() -> [&'static str; 13] {
         TaskBaseline::FIELD_NAMES_AS_ARRAY
     }
 }
+
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub enum VarianceKind {
+    Added,
+    Removed,
+    Changed,
+    Unchanged,
+}
+
+impl std::fmt::Display for VarianceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VarianceKind::Added => write!(f, "Added"),
+            VarianceKind::Removed => write!(f, "Removed"),
+            VarianceKind::Changed => write!(f, "Changed"),
+            VarianceKind::Unchanged => write!(f, "Unchanged"),
+        }
+    }
+}
+
+/// Difference of the capacity a resource has allocated on a task between two baselines.
+#[derive(
+    bitcode::Encode,
+    bitcode::Decode,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    PartialEq,
+    Debug,
+    FieldNamesAsArray,
+)]
+pub struct AllocationVariance {
+    pub resource_id: i64,
+    pub resource_summary: Option<String>,
+    pub kind: VarianceKind,
+    pub capacity_allocated_from: Option<f64>,
+    pub capacity_allocated_to: Option<f64>,
+}
+
+impl AllocationVariance {
+    pub fn fields() -> [&'static str; 5] {
+        AllocationVariance::FIELD_NAMES_AS_ARRAY
+    }
+}
+
+/// Difference of a single task between two baselines. Slips and duration changes are in seconds
+/// and are only present for tasks planned in both baselines.
+#[derive(
+    bitcode::Encode,
+    bitcode::Decode,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    PartialEq,
+    Debug,
+    FieldNamesAsArray,
+)]
+pub struct TaskVariance {
+    pub task_id: i64,
+    pub task_summary: String,
+    pub kind: VarianceKind,
+    pub start_slip: Option<i64>,
+    pub finish_slip: Option<i64>,
+    pub duration_change: Option<i64>,
+    pub wbs_from: Option<String>,
+    pub wbs_to: Option<String>,
+    pub parent_from: Option<i64>,
+    pub parent_to: Option<i64>,
+    pub moved: bool,
+    /// Whether the task became a milestone or stopped being one.
+    pub milestone_changed: bool,
+    pub allocations: Vec<AllocationVariance>,
+}
+
+impl TaskVariance {
+    pub fn fields() -> [&'static str; 13] {
+        TaskVariance::FIELD_NAMES_AS_ARRAY
+    }
+}
+
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct BaselineVariance {
+    pub baseline_id_from: i64,
+    pub baseline_id_to: i64,
+    pub tasks: Vec<TaskVariance>,
+}
@@ -0,0 +1,121 @@
+use struct_field_names_as_array::FieldNamesAsArray;
+
+use crate::datetime::{Date, Timestamp};
+
+/// A working calendar. Calendars without their own work hours or exceptions inherit them from
+/// their parent, usually the project default calendar.
+#[derive(
+    bitcode::Encode,
+    bitcode::Decode,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    PartialEq,
+    Debug,
+    FieldNamesAsArray,
+)]
+pub struct Calendar {
+    pub calendar_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    /// IANA timezone name the work hours and exception dates are given in.
+    pub timezone: String,
+    pub parent_calendar_id: Option<i64>,
+}
+
+impl Calendar {
+    pub fn fields() -> [&'static str; 5] {
+        Calendar::FIELD_NAMES_AS_ARRAY
+    }
+}
+
+/// One working period of a weekday, in minutes since local midnight.
+#[derive(
+    bitcode::Encode,
+    bitcode::Decode,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    PartialEq,
+    Debug,
+    FieldNamesAsArray,
+)]
+pub struct WorkHours {
+    pub calendar_work_hours_id: i64,
+    pub calendar_id: i64,
+    /// Day of the week, 0 for Monday up to 6 for Sunday.
+    pub weekday: u8,
+    pub start_minute: u16,
+    /// Exclusive end, at most 1440 for work until midnight.
+    pub finish_minute: u16,
+}
+
+impl WorkHours {
+    pub fn fields() -> [&'static str; 5] {
+        WorkHours::FIELD_NAMES_AS_ARRAY
+    }
+}
+
+#[derive(
+    bitcode::Encode,
+    bitcode::Decode,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Debug,
+)]
+pub enum CalendarExceptionKind {
+    Holiday,
+    Exception,
+}
+
+impl std::fmt::Display for CalendarExceptionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalendarExceptionKind::Holiday => write!(f, "Holiday"),
+            CalendarExceptionKind::Exception => write!(f, "Exception"),
+        }
+    }
+}
+
+/// A date whose working hours differ from the weekly pattern. A holiday, or an exception without
+/// hours, takes the whole day off; otherwise the hours replace those of the weekday.
+#[derive(
+    bitcode::Encode,
+    bitcode::Decode,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    PartialEq,
+    Debug,
+    FieldNamesAsArray,
+)]
+pub struct CalendarException {
+    pub calendar_exception_id: i64,
+    pub calendar_id: i64,
+    pub date: Date,
+    pub name: String,
+    pub kind: CalendarExceptionKind,
+    pub start_minute: Option<u16>,
+    pub finish_minute: Option<u16>,
+}
+
+impl CalendarException {
+    pub fn fields() -> [&'static str; 7] {
+        CalendarException::FIELD_NAMES_AS_ARRAY
+    }
+}
+
+/// Working time of a calendar between two instants.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct WorkingTime {
+    pub calendar_id: i64,
+    pub from: Timestamp,
+    pub to: Timestamp,
+    pub working_seconds: i64,
+    pub working_hours: f64,
+}
@@ -33,3 +33,114 @@ impl Config {
         self.config_value.as_ref().and_then(|v| v.parse::<T>().ok())
     }
 }
+
+#[derive(
+    bitcode::Encode,
+    bitcode::Decode,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Debug,
+)]
+pub enum ConfigType {
+    /// Id of an existing baseline; whether it exists can only be checked by the server.
+    BaselineId,
+    /// IANA timezone name known to chrono-tz, e.g. `Europe/Warsaw`.
+    Timezone,
+    /// Id of an existing calendar; whether it exists can only be checked by the server.
+    CalendarId,
+}
+
+/// A config key known to the application together with its type and default value.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ConfigKey {
+    pub key: &'static str,
+    pub value_type: ConfigType,
+    pub default: &'static str,
+    pub description: &'static str,
+}
+
+pub const BASELINE_ID_DEFAULT: ConfigKey = ConfigKey {
+    key: "baseline_id_default",
+    value_type: ConfigType::BaselineId,
+    default: "1",
+    description: "Baseline the project plan is imported into",
+};
+
+pub const BASELINE_ID_CURRENT: ConfigKey = ConfigKey {
+    key: "baseline_id_current",
+    value_type: ConfigType::BaselineId,
+    default: "2",
+    description: "Baseline the project is currently executed against",
+};
+
+pub const TIMEZONE_DEFAULT: ConfigKey = ConfigKey {
+    key: "timezone_default",
+    value_type: ConfigType::Timezone,
+    default: "Europe/Warsaw",
+    description: "Timezone used when a date comes without one",
+};
+
+pub const CALENDAR_ID_DEFAULT: ConfigKey = ConfigKey {
+    key: "calendar_id_default",
+    value_type: ConfigType::CalendarId,
+    default: "1",
+    description: "Working calendar of the project and of resources without their own",
+};
+
+pub const REGISTRY: [ConfigKey; 4] = [
+    BASELINE_ID_DEFAULT,
+    BASELINE_ID_CURRENT,
+    TIMEZONE_DEFAULT,
+    CALENDAR_ID_DEFAULT,
+];
+
+impl ConfigKey {
+    /// Looks a key up in the registry.
+    pub fn find(key: &str) -> Option<&'static ConfigKey> {
+        REGISTRY.iter().find(|config_key| config_key.key == key)
+    }
+
+    /// Parses the value as the type of the key. Baseline and calendar ids are only checked to be
+    /// numbers, the caller has to verify that they exist.
+    pub fn validate(&self, value: &str) -> Result<ConfigValue, String> {
+        match self.value_type {
+            ConfigType::BaselineId => value
+                .parse::<i64>()
+                .map(ConfigValue::BaselineId)
+                .map_err(|e| format!("{} must be a baseline id: {e}", self.key)),
+            ConfigType::CalendarId => value
+                .parse::<i64>()
+                .map(ConfigValue::CalendarId)
+                .map_err(|e| format!("{} must be a calendar id: {e}", self.key)),
+            ConfigType::Timezone => value
+                .parse::<chrono_tz::Tz>()
+                .map(ConfigValue::Timezone)
+                .map_err(|e| format!("{} must be a timezone name: {e}", self.key)),
+        }
+    }
+}
+
+/// A config value parsed as the type of its key.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ConfigValue {
+    BaselineId(i64),
+    Timezone(chrono_tz::Tz),
+    CalendarId(i64),
+}
+
+/// A config key as listed to clients: its type and default from the registry together with the
+/// stored value. Keys stored but unknown to the registry come without a type and default.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct ConfigEntry {
+    pub config_key: String,
+    pub value_type: Option<ConfigType>,
+    pub default_value: Option<String>,
+    /// Stored value, `None` while the default applies.
+    pub config_value: Option<String>,
+    pub description: Option<String>,
+}
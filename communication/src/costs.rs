@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+use struct_field_names_as_array::FieldNamesAsArray;
+
+use crate::datetime::Date;
+
+/// Planned cost of one task over all resources allocated to it.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct TaskCost {
+    pub task_id: i64,
+    pub task_summary: String,
+    /// Amount per currency code.
+    pub cost: BTreeMap<String, f64>,
+}
+
+/// Planned cost of one resource over all tasks it is allocated to.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct ResourceCost {
+    pub resource_id: i64,
+    pub resource_summary: String,
+    pub cost: BTreeMap<String, f64>,
+}
+
+/// Planned cost falling into one calendar month.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct MonthCost {
+    pub year: i32,
+    /// 1 for January up to 12 for December.
+    pub month: u32,
+    pub cost: BTreeMap<String, f64>,
+}
+
+/// Planned cost of a baseline broken down by task, by resource and by month.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct CostReport {
+    pub baseline_id: i64,
+    /// Timezone the months are delimited in.
+    pub timezone: String,
+    /// Currency all amounts were converted to; without one each amount stays in the currency it
+    /// arose in.
+    pub currency: Option<String>,
+    pub total: BTreeMap<String, f64>,
+    /// Ordered by task id.
+    pub tasks: Vec<TaskCost>,
+    /// Ordered by resource id.
+    pub resources: Vec<ResourceCost>,
+    /// Chronological, only months with any cost.
+    pub months: Vec<MonthCost>,
+}
+
+/// Rate of one currency against another, published for a day.
+#[derive(
+    bitcode::Encode,
+    bitcode::Decode,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    PartialEq,
+    Debug,
+    FieldNamesAsArray,
+)]
+pub struct ExchangeRate {
+    pub exchange_rate_id: i64,
+    pub date: Date,
+    pub from_currency: String,
+    pub to_currency: String,
+    /// Units of `to_currency` one unit of `from_currency` buys.
+    pub rate: f64,
+}
+
+impl ExchangeRate {
+    pub fn fields() -> [&'static str; 5] {
+        ExchangeRate::FIELD_NAMES_AS_ARRAY
+    }
+}
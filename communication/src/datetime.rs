@@ -0,0 +1,256 @@
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, TimeDelta, Utc};
+use serde::ser::Error;
+
+/// A timestamp or date outside what chrono can represent. Only a malformed bitcode payload carries
+/// one, since bitcode decodes the raw fields of both without looking at them.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct OutOfRange;
+
+impl std::fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "timestamp or date out of range")
+    }
+}
+
+impl std::error::Error for OutOfRange {}
+
+/// Decodes a bitcode payload and checks every timestamp and date in it by serializing it, which
+/// fails on values chrono cannot represent.
+pub fn decode<T: bitcode::DecodeOwned + serde::Serialize>(bytes: &[u8]) -> Result<T, String> {
+    let value: T = bitcode::decode(bytes).map_err(|e| e.to_string())?;
+    serde_json::to_writer(std::io::sink(), &value).map_err(|e| e.to_string())?;
+    Ok(value)
+}
+
+/// A chrono `NaiveDateTime` in a shape bitcode can derive for: seconds since the Unix epoch and
+/// the nanoseconds within that second. It is serialized with serde exactly like `NaiveDateTime`,
+/// so JSON clients see the same ISO 8601 strings as before. Values decoded from bitcode are checked
+/// with [`decode`].
+#[derive(
+    bitcode::Encode, bitcode::Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug,
+)]
+pub struct Timestamp {
+    secs: i64,
+    nanos: u32,
+}
+
+impl Timestamp {
+    pub fn naive(self) -> NaiveDateTime {
+        self.utc().naive_utc()
+    }
+
+    pub fn utc(self) -> DateTime<Utc> {
+        self.checked_utc()
+            .expect("timestamps are built from valid instants or checked after decoding")
+    }
+
+    /// The instant, unless the timestamp was decoded from fields chrono cannot represent.
+    pub fn checked_utc(self) -> Result<DateTime<Utc>, OutOfRange> {
+        DateTime::from_timestamp(self.secs, self.nanos).ok_or(OutOfRange)
+    }
+}
+
+impl From<NaiveDateTime> for Timestamp {
+    fn from(value: NaiveDateTime) -> Self {
+        let utc = value.and_utc();
+        Timestamp {
+            secs: utc.timestamp(),
+            nanos: utc.timestamp_subsec_nanos(),
+        }
+    }
+}
+
+impl From<DateTime<Utc>> for Timestamp {
+    fn from(value: DateTime<Utc>) -> Self {
+        value.naive_utc().into()
+    }
+}
+
+impl From<Timestamp> for NaiveDateTime {
+    fn from(value: Timestamp) -> Self {
+        value.naive()
+    }
+}
+
+impl std::ops::Sub for Timestamp {
+    type Output = TimeDelta;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.naive() - rhs.naive()
+    }
+}
+
+impl std::fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.naive().fmt(f)
+    }
+}
+
+impl serde::Serialize for Timestamp {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.checked_utc()
+            .map_err(S::Error::custom)?
+            .naive_utc()
+            .serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Timestamp {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        NaiveDateTime::deserialize(deserializer).map(Timestamp::from)
+    }
+}
+
+#[cfg(feature = "pmbe")]
+impl sea_orm::TryGetable for Timestamp {
+    fn try_get_by<I: sea_orm::ColIdx>(
+        res: &sea_orm::QueryResult,
+        index: I,
+    ) -> Result<Self, sea_orm::TryGetError> {
+        NaiveDateTime::try_get_by(res, index).map(Timestamp::from)
+    }
+}
+
+/// A chrono `NaiveDate` stored as days since 1 January of year 1, so bitcode can derive for it.
+/// It is serialized with serde exactly like `NaiveDate`. Values decoded from bitcode are checked
+/// with [`decode`].
+#[derive(
+    bitcode::Encode, bitcode::Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug,
+)]
+pub struct Date {
+    days: i32,
+}
+
+impl Date {
+    pub fn naive(self) -> NaiveDate {
+        self.checked_naive()
+            .expect("dates are built from valid days or checked after decoding")
+    }
+
+    /// The day, unless the date was decoded from a number of days chrono cannot represent.
+    pub fn checked_naive(self) -> Result<NaiveDate, OutOfRange> {
+        NaiveDate::from_num_days_from_ce_opt(self.days).ok_or(OutOfRange)
+    }
+}
+
+impl From<NaiveDate> for Date {
+    fn from(value: NaiveDate) -> Self {
+        Date {
+            days: value.num_days_from_ce(),
+        }
+    }
+}
+
+impl From<Date> for NaiveDate {
+    fn from(value: Date) -> Self {
+        value.naive()
+    }
+}
+
+impl std::fmt::Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.naive().fmt(f)
+    }
+}
+
+impl serde::Serialize for Date {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.checked_naive()
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Date {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        NaiveDate::deserialize(deserializer).map(Date::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actuals::TaskProgress;
+
+    fn progress() -> TaskProgress {
+        TaskProgress {
+            task_progress_id: 1,
+            task_id: 2,
+            status_date: NaiveDate::from_ymd_opt(2025, 3, 31).unwrap().into(),
+            percent_complete: 40.,
+            actual_start: Some(NaiveDate::from_ymd_opt(1, 1, 1).unwrap().into()),
+            actual_finish: None,
+            remaining_work: Some(12.5),
+            actual_cost: None,
+            cost_currency: None,
+        }
+    }
+
+    fn timestamp() -> Timestamp {
+        NaiveDate::from_ymd_opt(2025, 3, 30)
+            .unwrap()
+            .and_hms_nano_opt(2, 30, 0, 123_456_789)
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn json_matches_chrono() {
+        assert_eq!(
+            serde_json::to_string(&timestamp()).unwrap(),
+            "\"2025-03-30T02:30:00.123456789\""
+        );
+        let date: Date = serde_json::from_str("\"2025-03-31\"").unwrap();
+        assert_eq!(date, progress().status_date);
+        assert!(serde_json::from_str::<Date>("\"2025-02-30\"").is_err());
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let json = serde_json::to_string(&progress()).unwrap();
+        assert_eq!(
+            serde_json::from_str::<TaskProgress>(&json).unwrap(),
+            progress()
+        );
+        let json = serde_json::to_string(&timestamp()).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Timestamp>(&json).unwrap(),
+            timestamp()
+        );
+    }
+
+    #[test]
+    fn bitcode_round_trips() {
+        let bytes = bitcode::encode(&progress());
+        assert_eq!(bitcode::decode::<TaskProgress>(&bytes).unwrap(), progress());
+        let timestamps = vec![timestamp(), Timestamp::from(DateTime::<Utc>::MIN_UTC)];
+        let bytes = bitcode::encode(&timestamps);
+        assert_eq!(
+            bitcode::decode::<Vec<Timestamp>>(&bytes).unwrap(),
+            timestamps
+        );
+    }
+
+    #[test]
+    fn decoding_rejects_what_chrono_cannot_represent() {
+        let valid = Timestamp::from(DateTime::<Utc>::MAX_UTC);
+        let bytes = bitcode::encode(&vec![(valid.secs, valid.nanos)]);
+        assert_eq!(decode::<Vec<Timestamp>>(&bytes).unwrap(), vec![valid]);
+        let bytes = bitcode::encode(&vec![(valid.secs, valid.nanos), (i64::MAX, 0)]);
+        assert!(decode::<Vec<Timestamp>>(&bytes).is_err());
+        let bytes = bitcode::encode(&(0i64, 2_000_000_000u32));
+        assert_eq!(
+            bitcode::decode::<Timestamp>(&bytes).unwrap().checked_utc(),
+            Err(OutOfRange)
+        );
+        assert!(decode::<Timestamp>(&bytes).is_err());
+        let bytes = bitcode::encode(&Some(i32::MAX));
+        let date = bitcode::decode::<Option<Date>>(&bytes).unwrap().unwrap();
+        assert_eq!(date.checked_naive(), Err(OutOfRange));
+        assert!(decode::<Option<Date>>(&bytes).is_err());
+        let mut progress = bitcode::encode(&progress());
+        assert!(decode::<TaskProgress>(&progress).is_ok());
+        progress.truncate(progress.len() - 1);
+        assert!(decode::<TaskProgress>(&progress).is_err());
+    }
+}
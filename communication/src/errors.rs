@@ -0,0 +1,79 @@
+/// Why a request failed; each code maps to exactly one HTTP status.
+#[derive(
+    bitcode::Encode,
+    bitcode::Decode,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Debug,
+)]
+pub enum ErrorCode {
+    /// The request itself is malformed: missing or unparsable parameters or body.
+    BadRequest,
+    NotFound,
+    /// The request is valid but clashes with the current state, e.g. a locked baseline.
+    Conflict,
+    /// The body was parsed but its values are not acceptable.
+    InvalidPayload,
+    Internal,
+}
+
+impl ErrorCode {
+    pub fn status(self) -> u16 {
+        match self {
+            ErrorCode::BadRequest => 400,
+            ErrorCode::NotFound => 404,
+            ErrorCode::Conflict => 409,
+            ErrorCode::InvalidPayload => 422,
+            ErrorCode::Internal => 500,
+        }
+    }
+}
+
+/// Error body returned by pmbe for every failed request, always as JSON.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct ApiError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// Name of the parameter or payload field at fault, if the error is about a single one.
+    pub field: Option<String>,
+    pub details: Option<String>,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        ApiError {
+            code,
+            message: message.into(),
+            field: None,
+            details: None,
+        }
+    }
+
+    pub fn with_field(mut self, field: impl Into<String>) -> Self {
+        self.field = Some(field.into());
+        self
+    }
+
+    pub fn with_details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(field) = &self.field {
+            write!(f, " ({field})")?;
+        }
+        if let Some(details) = &self.details {
+            write!(f, ": {details}")?;
+        }
+        Ok(())
+    }
+}
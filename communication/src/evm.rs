@@ -0,0 +1,81 @@
+use crate::datetime::Date;
+
+/// Earned value figures at a status date, all in the report currency.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct EarnedValue {
+    /// Budget at completion: the planned cost of all the work.
+    pub bac: f64,
+    /// Planned value: the planned cost of the work scheduled up to the status date.
+    pub pv: f64,
+    /// Earned value: the planned cost of the work completed.
+    pub ev: f64,
+    /// Actual cost of the work completed.
+    pub ac: f64,
+    /// Schedule variance, EV - PV.
+    pub sv: f64,
+    /// Cost variance, EV - AC.
+    pub cv: f64,
+    /// Schedule performance index, EV / PV; none before any value was planned.
+    pub spi: Option<f64>,
+    /// Cost performance index, EV / AC; none before any cost was incurred.
+    pub cpi: Option<f64>,
+    /// Estimate at completion, BAC / CPI, or AC + BAC - EV while there is no CPI.
+    pub eac: f64,
+    /// Estimate to complete, EAC - AC.
+    pub etc: f64,
+    /// Variance at completion, BAC - EAC.
+    pub vac: f64,
+}
+
+/// Earned value of one task of the baseline.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct TaskEarnedValue {
+    pub task_id: i64,
+    pub task_summary: String,
+    pub wbs: String,
+    pub parent: Option<i64>,
+    /// The task on its own.
+    pub task: EarnedValue,
+    /// The task together with its whole WBS subtree.
+    pub subtree: EarnedValue,
+}
+
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct EarnedValueReport {
+    pub baseline_id: i64,
+    pub status_date: Date,
+    pub currency: String,
+    pub project: EarnedValue,
+    /// Ordered by wbs.
+    pub tasks: Vec<TaskEarnedValue>,
+}
+
+/// Project totals at one day of an earned value time series.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct EarnedValuePoint {
+    pub date: Date,
+    pub pv: f64,
+    /// None after the last progress assessment, when nothing has been earned or spent yet.
+    pub ev: Option<f64>,
+    pub ac: Option<f64>,
+}
+
+/// PV, EV and AC of the project at every month end and progress assessment of its schedule.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct EarnedValueSeries {
+    pub baseline_id: i64,
+    pub currency: String,
+    pub bac: f64,
+    /// Chronological.
+    pub points: Vec<EarnedValuePoint>,
+}
@@ -0,0 +1,43 @@
+use crate::baselines::{Baseline, OverAllocation};
+use crate::datetime::Timestamp;
+
+/// Name and notes of the draft baseline a levelling run creates, and how far it may delay tasks.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct LevellingRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub comment: Option<String>,
+    /// Longest a task may be delayed, in seconds. Without it tasks only move within their total
+    /// float, so the levelled baseline finishes when the source does.
+    pub max_delay: Option<i64>,
+}
+
+/// A task the levelling run delayed.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct TaskMove {
+    pub task_id: i64,
+    pub task_summary: String,
+    /// Start in the source baseline.
+    pub start_before: Timestamp,
+    /// Start in the levelled baseline.
+    pub start_after: Timestamp,
+    /// Seconds the task moved by.
+    pub delay: i64,
+}
+
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct LevellingReport {
+    pub source_baseline_id: i64,
+    /// The draft baseline holding the levelled schedule.
+    pub baseline: Baseline,
+    /// Ordered by the original start.
+    pub moves: Vec<TaskMove>,
+    /// Over-allocations of the levelled baseline that no allowed delay could resolve.
+    pub remaining: Vec<OverAllocation>,
+}
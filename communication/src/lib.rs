@@ -1,6 +1,16 @@
 pub mod models;
 
+pub mod actuals;
 pub mod baselines;
+pub mod calendars;
 pub mod config;
+pub mod costs;
+pub mod datetime;
+pub mod errors;
+pub mod evm;
+pub mod levelling;
+pub mod paging;
 pub mod resources;
+pub mod schedule;
 pub mod tasks;
+pub mod wbs;
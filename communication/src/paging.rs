@@ -0,0 +1,12 @@
+/// One page of a list endpoint.
+///
+/// `next_cursor` is passed back as the `cursor` query parameter to read the following page and is
+/// `None` on the last one. `total` counts every row matching the filters, not only this page.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+    pub total: u64,
+}
@@ -47,7 +47,8 @@ pub struct Resource {
     pub cost_currency: String,
     pub billing_frequency: Option<Frequency>,
     pub billing_interval: Option<i32>,
-    pub availability: Option<String>, // TODO: placeholder, modify to reference to calendar
+    /// Working calendar of the resource; the default calendar applies without one.
+    pub calendar_id: Option<i64>,
     pub capacity: Option<f64>,
     pub capacity_unit: Option<String>,
     pub is_active: bool,
@@ -74,10 +75,38 @@ pub struct ResourceType {
     pub name: String,
     pub description: Option<String>,
     pub comment: Option<String>,
+    pub is_active: bool,
 }
 
 impl ResourceType {
-    pub fn fields() -> [&'static str; 4] {
+    pub fn fields() -> [&'static str; 5] {
         ResourceType::FIELD_NAMES_AS_ARRAY
     }
 }
+
+/// A person on the project, linked to the Personnel resource that plans and costs their work.
+#[derive(
+    bitcode::Encode,
+    bitcode::Decode,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    PartialEq,
+    Debug,
+    FieldNamesAsArray,
+)]
+pub struct TeamMember {
+    pub user_id: i64,
+    pub user_name: String,
+    pub user_last_name: String,
+    pub position: Option<String>,
+    pub comment: Option<String>,
+    /// Resource of type Personnel; each resource belongs to at most one team member.
+    pub resource_id: i64,
+}
+
+impl TeamMember {
+    pub fn fields() -> [&'static str; 6] {
+        TeamMember::FIELD_NAMES_AS_ARRAY
+    }
+}
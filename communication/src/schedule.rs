@@ -0,0 +1,32 @@
+use crate::datetime::Timestamp;
+
+/// Dates and floats of one task as computed by the forward and backward pass.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct ScheduledTask {
+    pub task_id: i64,
+    pub early_start: Timestamp,
+    pub early_finish: Timestamp,
+    pub late_start: Timestamp,
+    pub late_finish: Timestamp,
+    /// Seconds the task can slip without delaying the end of the baseline.
+    pub total_float: i64,
+    /// Seconds the task can slip without delaying any of its successors.
+    pub free_float: i64,
+    pub is_critical: bool,
+}
+
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct Schedule {
+    pub baseline_id: i64,
+    pub start: Timestamp,
+    pub finish: Timestamp,
+    /// In the order the tasks were scheduled, every task after its predecessors, followed by the
+    /// summary tasks.
+    pub tasks: Vec<ScheduledTask>,
+    /// Ids of the critical tasks ordered by early start.
+    pub critical_path: Vec<i64>,
+}
@@ -1,5 +1,7 @@
 use struct_field_names_as_array::FieldNamesAsArray;
 
+use crate::datetime::Timestamp;
+
 #[derive(
     bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
 )]
@@ -21,6 +23,37 @@ impl std::fmt::Display for TaskStatus {
     }
 }
 
+impl std::str::FromStr for TaskStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ToDo" => Ok(TaskStatus::ToDo),
+            "InProgress" => Ok(TaskStatus::InProgress),
+            "Done" => Ok(TaskStatus::Done),
+            "Cancelled" => Ok(TaskStatus::Cancelled),
+            _ => Err(format!("unknown task status: {s}")),
+        }
+    }
+}
+
+impl TaskStatus {
+    /// Whether a task may move from this status to `next`: work starts before it is done, done
+    /// work can be reopened, and cancelled tasks go back to the backlog before they start again.
+    pub fn can_become(&self, next: &TaskStatus) -> bool {
+        matches!(
+            (self, next),
+            (TaskStatus::ToDo, TaskStatus::InProgress | TaskStatus::Cancelled)
+                | (
+                    TaskStatus::InProgress,
+                    TaskStatus::ToDo | TaskStatus::Done | TaskStatus::Cancelled
+                )
+                | (TaskStatus::Done, TaskStatus::InProgress)
+                | (TaskStatus::Cancelled, TaskStatus::ToDo)
+        )
+    }
+}
+
 #[derive(
     bitcode::Encode,
     bitcode::Decode,
@@ -44,3 +77,74 @@ impl Task {
         Task::FIELD_NAMES_AS_ARRAY
     }
 }
+
+/// The status a task moves to and who moves it; the time of the change is recorded by the server.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct TaskStatusUpdate {
+    pub status: TaskStatus,
+    pub changed_by: String,
+}
+
+/// One recorded status change of a task.
+#[derive(
+    bitcode::Encode,
+    bitcode::Decode,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    PartialEq,
+    Debug,
+    FieldNamesAsArray,
+)]
+pub struct TaskStatusChange {
+    pub task_status_history_id: i64,
+    pub task_id: i64,
+    pub old_status: TaskStatus,
+    pub new_status: TaskStatus,
+    pub changed_at: Timestamp,
+    pub changed_by: String,
+}
+
+impl TaskStatusChange {
+    pub fn fields() -> [&'static str; 6] {
+        TaskStatusChange::FIELD_NAMES_AS_ARRAY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATUSES: [TaskStatus; 4] = [
+        TaskStatus::ToDo,
+        TaskStatus::InProgress,
+        TaskStatus::Done,
+        TaskStatus::Cancelled,
+    ];
+
+    #[test]
+    fn transitions_follow_the_table() {
+        // Rows are the current status, columns the next one, both in the order of `STATUSES`.
+        let allowed = [
+            [false, true, false, true],
+            [true, false, true, true],
+            [false, true, false, false],
+            [true, false, false, false],
+        ];
+        for (from, row) in STATUSES.iter().zip(allowed) {
+            for (to, allowed) in STATUSES.iter().zip(row) {
+                assert_eq!(from.can_become(to), allowed, "{from} -> {to}");
+            }
+        }
+    }
+
+    #[test]
+    fn statuses_parse_from_their_names() {
+        for status in STATUSES {
+            assert_eq!(status.to_string().parse::<TaskStatus>(), Ok(status));
+        }
+        assert!("Blocked".parse::<TaskStatus>().is_err());
+    }
+}
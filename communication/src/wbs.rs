@@ -0,0 +1,59 @@
+use std::collections::BTreeMap;
+
+use crate::baselines::TaskBaseline;
+use crate::datetime::{Date, Timestamp};
+
+/// A task of the WBS tree with dates, capacity, cost and progress rolled up over its whole
+/// subtree.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct WbsNode {
+    pub task: TaskBaseline,
+    pub depth: u32,
+    /// Indexes of the child nodes in `WbsTree::nodes`, ordered by wbs.
+    pub children: Vec<u32>,
+    /// Earliest start within the subtree.
+    pub start: Timestamp,
+    /// Latest finish within the subtree.
+    pub finish: Timestamp,
+    pub capacity_allocated: f64,
+    /// Planned cost of the subtree per currency code.
+    pub cost: BTreeMap<String, f64>,
+    /// Percent complete of a leaf task, or of the leaves below a summary task weighted by their
+    /// planned duration.
+    pub percent_complete: f64,
+    /// Earliest actual start within the subtree.
+    pub actual_start: Option<Date>,
+    /// Latest actual finish, once every leaf of the subtree has finished.
+    pub actual_finish: Option<Date>,
+    /// Hours of work estimated to remain in the subtree.
+    pub remaining_work: Option<f64>,
+}
+
+/// The WBS of a baseline, or of one subtree of it.
+///
+/// Nodes link to their children by index rather than nesting, as bitcode cannot derive recursive
+/// types. They are stored depth first, so every node is directly followed by its subtree.
+#[derive(
+    bitcode::Encode, bitcode::Decode, serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug,
+)]
+pub struct WbsTree {
+    pub baseline_id: i64,
+    /// Currency all costs were converted to; without one each cost stays in the currency it arose
+    /// in.
+    pub currency: Option<String>,
+    /// Progress is as assessed on or before this day; without one the latest assessments count.
+    pub status_date: Option<Date>,
+    /// Indexes of the top level nodes in `nodes`.
+    pub roots: Vec<u32>,
+    pub nodes: Vec<WbsNode>,
+}
+
+impl WbsTree {
+    pub fn children(&self, node: &WbsNode) -> impl Iterator<Item = &WbsNode> {
+        node.children
+            .iter()
+            .map(|child| &self.nodes[*child as usize])
+    }
+}
@@ -1,4 +1,6 @@
 use chrono::NaiveDateTime;
+use communication::errors::{ApiError, ErrorCode};
+use communication::paging::Page;
 use dioxus::prelude::*;
 
 // use tracing::*;
@@ -27,6 +29,8 @@ const MAIN_CSS: Asset = asset!("/assets/main.css");
 const HEADER_SVG: Asset = asset!("/assets/header.svg");
 const TAILWIND_CSS: Asset = asset!("/assets/tailwind.css");
 const PROJECT_CSS: Asset = asset!("/assets/project.css");
+/// Largest page pmbe serves.
+const PAGE_LIMIT: u64 = 1000;
 fn main() {
     dioxus::launch(App);
 }
@@ -184,10 +188,14 @@ fn get_task_cell_value(task: &communication::baselines::TaskBaseline, column: &s
             Some(parent) => parent.to_string(),
             None => "".to_string(),
         },
-        "start" => task.start.date().to_string(),
+        "start" => task.start.naive().date().to_string(),
         "start_timezone" => task.start_timezone.clone(),
-        "finish" => task.finish.date().to_string(),
+        "finish" => task.finish.naive().date().to_string(),
         "finish_timezone" => task.finish_timezone.clone(),
+        "milestone" => match task.milestone {
+            true => "◆".to_string(),
+            false => "".to_string(),
+        },
         _ => panic!("Invalid column: {}", column),
     }
 }
@@ -228,8 +236,8 @@ fn get_resource_cell_value(
             Some(billing_interval) => billing_interval.to_string(),
             None => "".to_string(),
         },
-        "availability" => match &resource.availability {
-            Some(availability) => availability.clone(),
+        "calendar_id" => match &resource.calendar_id {
+            Some(calendar_id) => calendar_id.to_string(),
             None => "".to_string(),
         },
         "capacity" => match &resource.capacity {
@@ -278,12 +286,92 @@ fn get_resource_allocation_cell_value(
     }
 }
 
+fn get_overallocation_cell_value(
+    overallocation: &communication::baselines::OverAllocation,
+    column: &str,
+) -> String {
+    match column {
+        "resource_id" => overallocation.resource_id.to_string(),
+        "resource_summary" => overallocation.resource_summary.clone(),
+        "start" => overallocation.start.to_string(),
+        "finish" => overallocation.finish.to_string(),
+        "capacity" => overallocation.capacity.to_string(),
+        "capacity_allocated" => overallocation.capacity_allocated.to_string(),
+        "task_ids" => overallocation
+            .task_ids
+            .iter()
+            .map(|task_id| task_id.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => panic!("Invalid column: {}", column),
+    }
+}
+
+/// Reads the `ApiError` pmbe sends with every failed response.
+async fn api_error(response: reqwest::Response) -> ApiError {
+    let status = response.status();
+    response.json().await.unwrap_or_else(|_| {
+        ApiError::new(
+            ErrorCode::Internal,
+            format!("Request failed with status {status}"),
+        )
+    })
+}
+
+fn request_failed(e: reqwest::Error) -> ApiError {
+    ApiError::new(ErrorCode::Internal, "Request failed").with_details(e.to_string())
+}
+
+async fn get_json<T: serde::de::DeserializeOwned>(url: &str) -> Result<T, ApiError> {
+    let response = reqwest::get(url).await.map_err(request_failed)?;
+    if !response.status().is_success() {
+        return Err(api_error(response).await);
+    }
+    response.json().await.map_err(request_failed)
+}
+
+/// Reads every page of a paged pmbe list; `url` must already carry a query string.
+async fn get_all_pages<T: serde::de::DeserializeOwned>(url: &str) -> Result<Vec<T>, ApiError> {
+    let mut items = Vec::new();
+    let mut page: Page<T> = get_json(&format!("{url}&limit={PAGE_LIMIT}")).await?;
+    loop {
+        items.append(&mut page.items);
+        match page.next_cursor {
+            Some(cursor) => {
+                page = get_json(&format!("{url}&limit={PAGE_LIMIT}&cursor={cursor}")).await?
+            }
+            None => return Ok(items),
+        }
+    }
+}
+
+/// pmbe answers in JSON unless asked for bitcode; errors are JSON either way.
+async fn get_bitcode<T: bitcode::DecodeOwned + serde::Serialize>(
+    url: &str,
+) -> Result<T, ApiError> {
+    let response = reqwest::Client::new()
+        .get(url)
+        .header(reqwest::header::ACCEPT, "application/octet-stream")
+        .send()
+        .await
+        .map_err(request_failed)?;
+    if !response.status().is_success() {
+        return Err(api_error(response).await);
+    }
+    let bytes = response.bytes().await.map_err(request_failed)?;
+    communication::datetime::decode(&bytes).map_err(|e| {
+        ApiError::new(ErrorCode::Internal, "Failed to decode response").with_details(e)
+    })
+}
+
 #[derive(Debug, PartialEq)]
 enum View {
     Loading,
+    Error,
     Gantt,
     ResourcesList,
     ResourcesAllocation,
+    ResourcesOverallocation,
     Reporting,
     Reports,
     Settings,
@@ -300,22 +388,25 @@ fn Project() -> Element {
         use_signal(Vec::new);
     let mut signal_resource_allocations: Signal<Vec<communication::baselines::ResourceAllocation>> =
         use_signal(Vec::new);
+    let mut signal_overallocations: Signal<Vec<communication::baselines::OverAllocation>> =
+        use_signal(Vec::new);
+    let mut signal_error: Signal<Option<ApiError>> = use_signal(|| None);
     let mut splitter_position = use_signal(|| 50.);
     let mut project_start = use_signal(|| NaiveDateTime::MIN);
     let mut project_finish = use_signal(|| NaiveDateTime::MAX);
 
     let fetch_tasks = move |_| async move {
-        signal_tasks.set(
-            serde_json::from_str(
-                &reqwest::get("http://localhost:22004/tasks?baseline_id=1")
-                    .await
-                    .unwrap()
-                    .text()
-                    .await
-                    .unwrap(),
-            )
-            .unwrap(),
-        );
+        match get_all_pages("http://localhost:22004/tasks?baseline_id=1").await {
+            Ok(tasks) => {
+                signal_error.set(None);
+                signal_tasks.set(tasks);
+            }
+            Err(e) => {
+                signal_error.set(Some(e));
+                view.set(View::Error);
+                return;
+            }
+        }
         project_start.set(
             signal_tasks
                 .read()
@@ -323,7 +414,8 @@ fn Project() -> Element {
                 .into_iter()
                 .min_by_key(|task| task.start)
                 .unwrap()
-                .start,
+                .start
+                .naive(),
         );
         project_finish.set(
             signal_tasks
@@ -332,65 +424,83 @@ fn Project() -> Element {
                 .into_iter()
                 .max_by_key(|task| task.finish)
                 .unwrap()
-                .finish,
+                .finish
+                .naive(),
         );
         view.set(View::Gantt);
     };
 
     let fetch_resources = move |_| async move {
-        let (resources, resource_types) = bitcode::decode(
-            &reqwest::get("http://localhost:22004/resources")
-                .await
-                .unwrap_or_else(|e| {
-                    panic!("Failed to fetch resources: {}", e);
-                })
-                .bytes()
-                .await
-                .unwrap_or_else(|e| {
-                    panic!("Failed to fetch resources: {}", e);
-                }),
-        )
-        .unwrap_or_else(|e| {
-            panic!("Failed to decode resources: {}", e);
-        });
+        let mut resources = Vec::new();
+        let mut cursor: Option<String> = None;
+        let resource_types = loop {
+            let url = match &cursor {
+                Some(cursor) => {
+                    format!("http://localhost:22004/resources?limit={PAGE_LIMIT}&cursor={cursor}")
+                }
+                None => format!("http://localhost:22004/resources?limit={PAGE_LIMIT}"),
+            };
+            let (mut page, resource_types): (Page<_>, Vec<_>) = match get_bitcode(&url).await {
+                Ok(page) => page,
+                Err(e) => {
+                    signal_error.set(Some(e));
+                    return;
+                }
+            };
+            resources.append(&mut page.items);
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break resource_types;
+            }
+        };
+        signal_error.set(None);
         signal_resources.set(resources);
         signal_resource_types.set(resource_types);
         view.set(View::ResourcesList);
     };
 
     let fetch_resource_allocations = move |_| async move {
-        let resource_allocations = bitcode::decode(
-            &reqwest::get("http://localhost:22004/resources/allocation?baseline_id=1")
-                .await
-                .unwrap_or_else(|e| {
-                    panic!("Failed to fetch resource allocations: {}", e);
-                })
-                .bytes()
-                .await
-                .unwrap_or_else(|e| {
-                    panic!("Failed to fetch resource allocations: {}", e);
-                }),
-        )
-        .unwrap_or_else(|e| {
-            panic!("Failed to decode resource allocations: {}", e);
-        });
+        let resource_allocations =
+            match get_bitcode("http://localhost:22004/resources/allocation?baseline_id=1").await {
+                Ok(resource_allocations) => resource_allocations,
+                Err(e) => {
+                    signal_error.set(Some(e));
+                    return;
+                }
+            };
 
+        signal_error.set(None);
         signal_resource_allocations.set(resource_allocations);
         view.set(View::ResourcesAllocation);
     };
 
+    let fetch_overallocations = move |_| async move {
+        let overallocations =
+            match get_bitcode("http://localhost:22004/baselines/1/overallocations").await {
+                Ok(overallocations) => overallocations,
+                Err(e) => {
+                    signal_error.set(Some(e));
+                    return;
+                }
+            };
+
+        signal_error.set(None);
+        signal_overallocations.set(overallocations);
+        view.set(View::ResourcesOverallocation);
+    };
+
     use_future(move || async move {
-        signal_tasks.set(
-            serde_json::from_str(
-                &reqwest::get("http://localhost:22004/tasks?baseline_id=1")
-                    .await
-                    .unwrap()
-                    .text()
-                    .await
-                    .unwrap(),
-            )
-            .unwrap(),
-        );
+        match get_all_pages("http://localhost:22004/tasks?baseline_id=1").await {
+            Ok(tasks) => {
+                signal_error.set(None);
+                signal_tasks.set(tasks);
+            }
+            Err(e) => {
+                signal_error.set(Some(e));
+                view.set(View::Error);
+                return;
+            }
+        }
         project_start.set(
             signal_tasks
                 .read()
@@ -398,7 +508,8 @@ fn Project() -> Element {
                 .into_iter()
                 .min_by_key(|task| task.start)
                 .unwrap()
-                .start,
+                .start
+                .naive(),
         );
         project_finish.set(
             signal_tasks
@@ -407,7 +518,8 @@ fn Project() -> Element {
                 .into_iter()
                 .max_by_key(|task| task.finish)
                 .unwrap()
-                .finish,
+                .finish
+                .naive(),
         );
         view.set(View::Gantt);
     });
@@ -443,9 +555,12 @@ fn Project() -> Element {
                     "Settings"
                 }
                 span { " | " }
-                if *view.read() == View::ResourcesList || *view.read() == View::ResourcesAllocation {
+                if *view.read() == View::ResourcesList || *view.read() == View::ResourcesAllocation
+                    || *view.read() == View::ResourcesOverallocation
+                {
                     button { class: "button", onclick: fetch_resources, "List" }
                     button { class: "button", onclick: fetch_resource_allocations, "Allocation" }
+                    button { class: "button", onclick: fetch_overallocations, "Over-allocation" }
                 }
                 if *view.read() == View::Gantt {
                     button {
@@ -468,6 +583,10 @@ fn Project() -> Element {
                 }
             }
 
+            if let Some(e) = signal_error.read().as_ref() {
+                div { id: "error", "{e}" }
+            }
+
             if *view.read() == View::ResourcesList {
                 div { id: "full_view", style: "",
                     div { id: "resources", class: "table",
@@ -502,6 +621,21 @@ fn Project() -> Element {
             }
 
 
+            if *view.read() == View::ResourcesOverallocation {
+                div { id: "full_view", style: "",
+                    div { id: "resources_overallocations", class: "table",
+                        for (row , overallocation) in signal_overallocations.read().clone().into_iter().enumerate() {
+                            for (column_index , column) in communication::baselines::OverAllocation::fields().iter().enumerate() {
+                                div {
+                                    class: "item",
+                                    style: "grid-row: {(row+1).to_string()}; grid-column: {(column_index+1).to_string()};",
+                                    "{get_overallocation_cell_value(&overallocation, column)}"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
 
             if *view.read() == View::Gantt {
                 div {
@@ -545,26 +679,36 @@ fn Project() -> Element {
                             div {
                                 class: "item",
                                 style: "grid-row: {(row+1).to_string()}; width: 110rem;",
-                                div {
-                                    width: ((task.finish - task.start).num_seconds() as f64 * 100.
-                                        / (*project_finish.read() - *project_start.read()).num_seconds() as f64)
-                                        .to_string() + "rem",
-                                    left: (((task.start - *project_start.read()).num_seconds() as f64 * 100.
-                                        / (*project_finish.read() - *project_start.read()).num_seconds() as f64) + 5.)
-                                        .to_string() + "rem",
-                                    style: "background-color: green; position: relative; height: 100%;
-                                        box-sizing: border-box; border-bottom: 0.2rem solid black; border-top: 0.2rem solid black;",
-                                    {
-                                        tracing::debug!(
-                                            "task: {}, start: {}, duration: {}", task.task_summary, (task.start - *
-                                            project_start.read()).num_seconds() as f64 * 100. / (* project_finish.read()
-                                            - * project_start.read()).num_seconds() as f64, (task.finish - task.start)
-                                            .num_seconds() as f64 * 100. / (* project_finish.read() - * project_start
-                                            .read()).num_seconds() as f64,
-                                        )
+                                if task.milestone {
+                                    // Centred on its date
+                                    div {
+                                        class: "milestone",
+                                        left: (((task.start.naive() - *project_start.read()).num_seconds() as f64 * 100.
+                                            / (*project_finish.read() - *project_start.read()).num_seconds() as f64) + 4.5)
+                                            .to_string() + "rem",
+                                    }
+                                } else {
+                                    div {
+                                        width: ((task.finish - task.start).num_seconds() as f64 * 100.
+                                            / (*project_finish.read() - *project_start.read()).num_seconds() as f64)
+                                            .to_string() + "rem",
+                                        left: (((task.start.naive() - *project_start.read()).num_seconds() as f64 * 100.
+                                            / (*project_finish.read() - *project_start.read()).num_seconds() as f64) + 5.)
+                                            .to_string() + "rem",
+                                        style: "background-color: green; position: relative; height: 100%;
+                                            box-sizing: border-box; border-bottom: 0.2rem solid black; border-top: 0.2rem solid black;",
+                                        {
+                                            tracing::debug!(
+                                                "task: {}, start: {}, duration: {}", task.task_summary, (task.start.naive() - *
+                                                project_start.read()).num_seconds() as f64 * 100. / (* project_finish.read()
+                                                - * project_start.read()).num_seconds() as f64, (task.finish - task.start)
+                                                .num_seconds() as f64 * 100. / (* project_finish.read() - * project_start
+                                                .read()).num_seconds() as f64,
+                                            )
+                                        }
                                     }
                                 }
-                                                        // div {
+                            // div {
                             //     style: "position: relative; height: 100%; z-index: 10; left: 10rem",
                             //     "{task.name}"
                             // }